@@ -0,0 +1,275 @@
+use crate::game::board::BoardDim;
+use crate::game::GameMode;
+use crate::menu::menuhelpers::{MenuGameOptions, MAX_NUM_PLAYERS, MAX_STARTING_LEVEL};
+use crate::menu::menuhelpers::{
+    MAX_BOARD_WIDTH_CONSTANT, MAX_BOARD_WIDTH_PER_PLAYER, MIN_BOARD_WIDTH_PER_PLAYER,
+};
+
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub static USAGE: &str = "usage: tetrisn-t [options]
+
+  --mode <classic|rotatris>       game mode
+  --players <n>                   number of players (classic)
+  --level <n>                     starting level
+  --board-width-per-player <n>    board width for each player (classic)
+  --board-width-constant <n>      extra board width on top of that (classic)
+  --seed <n>                      same seed, same pieces
+  --profile <name>                profile for the next player, in order; can be given more than once
+  --replay <file>                 play back a saved replay
+  --resources <dir>               where to look for resources like gamecontrollerdb.txt
+  --help                          show this
+
+any of --mode through --profile skips the menu and starts a game right away;
+the rest of the options are the ones that were last used, and none of these are saved";
+
+// the options the command line can replace, as they were before it did;
+// they're what gets saved for the rest of the run, so a scripted run doesn't change anyone's saved options
+#[derive(Copy, Clone)]
+pub struct OptionsUnderCli {
+    pub game_mode: GameMode,
+    pub num_players: u8,
+    pub starting_level: u8,
+    pub board_width_per_player: BoardDim,
+    pub board_width_constant: BoardDim,
+}
+
+// everything that can be asked for on the command line; None leaves the saved option alone
+#[derive(Default)]
+pub struct CliOptions {
+    pub game_mode: Option<GameMode>,
+    pub num_players: Option<u8>,
+    pub starting_level: Option<u8>,
+    pub board_width_per_player: Option<BoardDim>,
+    pub board_width_constant: Option<BoardDim>,
+    pub seed: Option<u64>,
+    pub vec_profile_names: Vec<String>,
+    pub replay: Option<PathBuf>,
+    pub resources: Option<PathBuf>,
+    pub help_flag: bool,
+}
+
+impl CliOptions {
+    pub fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Self, String> {
+        let mut cli_options = Self::default();
+        while let Some(arg) = args.next() {
+            if arg == "--help" || arg == "-h" {
+                cli_options.help_flag = true;
+                continue;
+            }
+            let value = match args.next() {
+                Some(value) => value,
+                None if arg.starts_with("--") => return Err(format!("{} needs a value", arg)),
+                None => return Err(format!("unknown argument \"{}\"", arg)),
+            };
+            match arg.as_str() {
+                "--mode" => {
+                    cli_options.game_mode = Some(match value.to_lowercase().as_str() {
+                        "classic" => GameMode::Classic,
+                        "rotatris" => GameMode::Rotatris,
+                        _ => return Err(format!("unknown mode \"{}\"", value)),
+                    })
+                }
+                "--players" => {
+                    cli_options.num_players =
+                        Some(parse_in_range(&arg, &value, 1, MAX_NUM_PLAYERS)?)
+                }
+                "--level" => {
+                    cli_options.starting_level =
+                        Some(parse_in_range(&arg, &value, 0, MAX_STARTING_LEVEL)?)
+                }
+                "--board-width-per-player" => {
+                    cli_options.board_width_per_player = Some(parse_in_range(
+                        &arg,
+                        &value,
+                        MIN_BOARD_WIDTH_PER_PLAYER,
+                        MAX_BOARD_WIDTH_PER_PLAYER,
+                    )?)
+                }
+                "--board-width-constant" => {
+                    cli_options.board_width_constant =
+                        Some(parse_in_range(&arg, &value, 0, MAX_BOARD_WIDTH_CONSTANT)?)
+                }
+                "--seed" => {
+                    cli_options.seed = Some(
+                        value
+                            .parse()
+                            .map_err(|_| format!("--seed needs a number, not \"{}\"", value))?,
+                    )
+                }
+                // profile names are all uppercase, so it doesn't matter how they're typed
+                "--profile" => cli_options.vec_profile_names.push(value.to_uppercase()),
+                "--replay" => cli_options.replay = Some(PathBuf::from(value)),
+                "--resources" => cli_options.resources = Some(PathBuf::from(value)),
+                _ => return Err(format!("unknown argument \"{}\"", arg)),
+            }
+        }
+
+        Ok(cli_options)
+    }
+
+    pub fn starts_game(&self) -> bool {
+        self.game_mode.is_some()
+            || self.num_players.is_some()
+            || self.starting_level.is_some()
+            || self.board_width_per_player.is_some()
+            || self.board_width_constant.is_some()
+            || self.seed.is_some()
+            || !self.vec_profile_names.is_empty()
+    }
+
+    // puts the options from the command line over the saved ones
+    pub fn apply(&self, game_options: &mut MenuGameOptions) {
+        game_options.options_under_cli = Some(OptionsUnderCli {
+            game_mode: game_options.game_mode,
+            num_players: game_options.num_players,
+            starting_level: game_options.starting_level,
+            board_width_per_player: game_options.settings.board_width_per_player,
+            board_width_constant: game_options.settings.board_width_constant,
+        });
+        if let Some(game_mode) = self.game_mode {
+            game_options.game_mode = game_mode;
+        }
+        if game_options.game_mode == GameMode::None {
+            game_options.game_mode = GameMode::Classic;
+        }
+        if let Some(num_players) = self.num_players {
+            game_options.num_players = num_players;
+        }
+        if game_options.game_mode == GameMode::Rotatris {
+            game_options.num_players = 1;
+        }
+        if let Some(starting_level) = self.starting_level {
+            game_options.starting_level = starting_level;
+        }
+        if let Some(board_width_per_player) = self.board_width_per_player {
+            game_options.settings.board_width_per_player = board_width_per_player;
+        }
+        if let Some(board_width_constant) = self.board_width_constant {
+            game_options.settings.board_width_constant = board_width_constant;
+        }
+        for (slot, name) in self
+            .vec_profile_names
+            .iter()
+            .enumerate()
+            .take(MAX_NUM_PLAYERS as usize)
+        {
+            let has_controls = match game_options
+                .profiles
+                .iter()
+                .find(|profile| &profile.name == name)
+            {
+                Some(profile) => profile.has_controls(),
+                None => {
+                    println!("[!] There's no profile named {}", name);
+                    continue;
+                }
+            };
+            // a profile can only sit in one slot
+            for profile_name in game_options.arr_profile_names.iter_mut() {
+                if profile_name.as_ref() == Some(name) {
+                    *profile_name = None;
+                }
+            }
+            game_options.arr_profile_names[slot] = Some(name.clone());
+            // a profile without controls yet just plays with the slot's
+            if has_controls {
+                game_options.apply_slot_profile(slot);
+            }
+        }
+    }
+}
+
+fn parse_in_range<T: FromStr + PartialOrd + Display>(
+    arg: &str,
+    value: &str,
+    min: T,
+    max: T,
+) -> Result<T, String> {
+    match value.parse::<T>() {
+        Ok(num) if num >= min && num <= max => Ok(num),
+        _ => Err(format!(
+            "{} needs a number from {} to {}, not \"{}\"",
+            arg, min, max, value
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SavedOptions;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, String> {
+        CliOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn bad_arguments_are_reported() {
+        assert_eq!(
+            parse(&["--players"]).err().unwrap(),
+            "--players needs a value"
+        );
+        assert_eq!(
+            parse(&["players", "3"]).err().unwrap(),
+            "unknown argument \"players\""
+        );
+        assert_eq!(
+            parse(&["--mode", "tetris"]).err().unwrap(),
+            "unknown mode \"tetris\""
+        );
+        assert_eq!(
+            parse(&["--players", "0"]).err().unwrap(),
+            format!(
+                "--players needs a number from 1 to {}, not \"0\"",
+                MAX_NUM_PLAYERS
+            )
+        );
+        assert!(parse(&["--level", &(MAX_STARTING_LEVEL as u16 + 1).to_string()]).is_err());
+        assert!(parse(&["--board-width-per-player", "3"]).is_err());
+        assert!(parse(&["--seed", "-1"]).is_err());
+
+        let cli_options = parse(&[
+            "--mode",
+            "Rotatris",
+            "--level",
+            &MAX_STARTING_LEVEL.to_string(),
+            "--profile",
+            "cat",
+            "-h",
+        ])
+        .unwrap();
+        assert!(cli_options.game_mode == Some(GameMode::Rotatris));
+        assert_eq!(cli_options.starting_level, Some(MAX_STARTING_LEVEL));
+        assert_eq!(cli_options.vec_profile_names, vec!["CAT".to_string()]);
+        assert!(cli_options.help_flag && cli_options.starts_game());
+        assert!(!parse(&["--resources", "res"]).unwrap().starts_game());
+    }
+
+    #[test]
+    fn command_line_options_are_not_saved() {
+        let mut game_options = MenuGameOptions {
+            game_mode: GameMode::Classic,
+            ..Default::default()
+        };
+        let saved_options = SavedOptions::from(&game_options);
+        parse(&[
+            "--players",
+            "3",
+            "--level",
+            "18",
+            "--board-width-constant",
+            "0",
+        ])
+        .unwrap()
+        .apply(&mut game_options);
+        assert_eq!(game_options.num_players, 3);
+        assert_eq!(game_options.starting_level, 18);
+        assert!(SavedOptions::from(&game_options) == saved_options);
+        // everything else still gets saved
+        game_options.settings.ghost_pieces_state = !game_options.settings.ghost_pieces_state;
+        assert!(SavedOptions::from(&game_options) != saved_options);
+    }
+}
@@ -0,0 +1,651 @@
+use ggez::event::{Axis, Button, KeyCode};
+use ggez::filesystem;
+use ggez::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::game::{GameMode, GameSettings};
+use crate::inputs::{GamepadControlScheme, GamepadInput, KeyboardControlScheme, MenuKeys};
+use crate::inputs::{SocdPolicy, StickSettings, MAX_KEYS_PER_MOVEMENT};
+use crate::menu::menuhelpers::{MenuGameOptions, MAX_NUM_PLAYERS, MAX_STARTING_LEVEL};
+use crate::menu::menuhelpers::{
+    MAX_BOARD_WIDTH_CONSTANT, MAX_BOARD_WIDTH_PER_PLAYER, MIN_BOARD_WIDTH_PER_PLAYER,
+};
+use crate::movement::Movement;
+use crate::theme;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// every config file is stamped with this; new fields only need a default, so bump it when the meaning of an existing field changes
+pub const CONFIG_VERSION: u32 = 1;
+
+pub static CONTROLS_FILE: &str = "controls.toml";
+pub static OPTIONS_FILE: &str = "options.toml";
+
+#[derive(Serialize, Deserialize)]
+struct ControlsConfig {
+    #[serde(default = "config_version")]
+    version: u32,
+    #[serde(default)]
+    menu_keys: MenuKeys,
+    #[serde(default)]
+    players: Vec<PlayerControls>,
+}
+
+impl Default for ControlsConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            menu_keys: MenuKeys::default(),
+            players: vec![],
+        }
+    }
+}
+
+// only players that have something set up are written out
+#[derive(Serialize, Deserialize)]
+struct PlayerControls {
+    player: u8,
+    #[serde(default)]
+    gamepad: bool,
+    // the profile sitting in this slot, if any
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    socd: SocdPolicy,
+    #[serde(default)]
+    keys: Vec<KeyBinding>,
+    // empty for the default gamepad layout, which isn't written out since it would land after the keys tables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    buttons: Vec<GamepadBinding>,
+    // a table, so it goes last; left out while it's the defaults
+    #[serde(default, skip_serializing_if = "StickSettings::is_default")]
+    sticks: StickSettings,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct KeyBinding {
+    pub key: KeyCode,
+    pub movement: Movement,
+}
+
+impl KeyBinding {
+    pub fn from_scheme(k_ctrl_scheme: &KeyboardControlScheme) -> Vec<KeyBinding> {
+        k_ctrl_scheme
+            .vec_keycode_movement_pair
+            .iter()
+            .map(|(key, movement)| KeyBinding {
+                key: *key,
+                movement: *movement,
+            })
+            .collect()
+    }
+}
+
+// toml can't hold GamepadInput as is, so it's spread out over plain values;
+// one of button and axis is set, and an axis also says which way it's pushed
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GamepadBinding {
+    pub movement: Movement,
+    #[serde(default)]
+    pub button: Option<Button>,
+    #[serde(default)]
+    pub axis: Option<Axis>,
+    #[serde(default)]
+    pub positive: bool,
+}
+
+impl GamepadBinding {
+    pub fn new(input: GamepadInput, movement: Movement) -> Self {
+        let (button, axis, positive) = match input {
+            GamepadInput::Button(btn) => (Some(btn), None, false),
+            GamepadInput::AxisPositive(axis) => (None, Some(axis), true),
+            GamepadInput::AxisNegative(axis) => (None, Some(axis), false),
+        };
+        Self {
+            movement,
+            button,
+            axis,
+            positive,
+        }
+    }
+
+    pub fn input(&self) -> Option<GamepadInput> {
+        match (self.button, self.axis) {
+            (Some(btn), None) => Some(GamepadInput::Button(btn)),
+            (None, Some(axis)) if self.positive => Some(GamepadInput::AxisPositive(axis)),
+            (None, Some(axis)) => Some(GamepadInput::AxisNegative(axis)),
+            _ => None,
+        }
+    }
+
+    // the default layout isn't written out, so it still changes along with the game;
+    // the stick settings are written out on their own
+    pub fn from_scheme(g_ctrl_scheme: &GamepadControlScheme) -> Vec<GamepadBinding> {
+        if g_ctrl_scheme.has_default_layout() {
+            return vec![];
+        }
+        g_ctrl_scheme
+            .vec_input_movement_pair
+            .iter()
+            .map(|(input, movement)| GamepadBinding::new(*input, *movement))
+            .collect()
+    }
+
+    pub fn to_scheme(
+        vec_bindings: &[GamepadBinding],
+        sticks: StickSettings,
+    ) -> GamepadControlScheme {
+        if vec_bindings.is_empty() {
+            return GamepadControlScheme::default().with_sticks(sticks);
+        }
+        let mut g_ctrl_scheme = GamepadControlScheme::new_empty().with_sticks(sticks);
+        for binding in vec_bindings.iter() {
+            if let Some(input) = binding.input() {
+                g_ctrl_scheme.add_pair(input, binding.movement);
+            }
+        }
+        g_ctrl_scheme
+    }
+
+    // a button can only do one thing, and Start and Select can't be bound at all;
+    // more than one button for a movement is fine, like the D-pad and stick in the default layout
+    pub fn fix_up(
+        vec_bindings: &[GamepadBinding],
+        path: &Path,
+        owner: &str,
+    ) -> Vec<GamepadBinding> {
+        let mut vec_fixed: Vec<GamepadBinding> = Vec::with_capacity(vec_bindings.len());
+        for binding in vec_bindings.iter() {
+            let input = match binding.input() {
+                Some(input) => input,
+                None => {
+                    println!(
+                        "[!] {}: ignoring a binding for {:?} of {} that needs exactly one of button or axis",
+                        path.display(),
+                        binding.movement,
+                        owner
+                    );
+                    continue;
+                }
+            };
+            if !input.is_bindable() || vec_fixed.iter().any(|b| b.input() == Some(input)) {
+                println!(
+                    "[!] {}: ignoring {} for {:?} of {}, it's already in use or can't be bound",
+                    path.display(),
+                    input,
+                    binding.movement,
+                    owner
+                );
+                continue;
+            }
+            vec_fixed.push(binding.clone());
+        }
+        vec_fixed
+    }
+}
+
+// the last used options from the start, settings and choose mode menus
+#[derive(Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct SavedOptions {
+    version: u32,
+    game_mode: GameMode,
+    num_players: u8,
+    starting_level: u8,
+    initials: String,
+    // by name, since theme files can come and go
+    theme: String,
+    settings: GameSettings,
+}
+
+impl Default for SavedOptions {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            game_mode: GameMode::None,
+            num_players: 1,
+            starting_level: 0,
+            initials: "AAA".to_string(),
+            theme: theme::built_in_themes()[0].0.clone(),
+            settings: GameSettings::default(),
+        }
+    }
+}
+
+impl From<&MenuGameOptions> for SavedOptions {
+    fn from(game_options: &MenuGameOptions) -> Self {
+        let mut saved_options = Self {
+            version: CONFIG_VERSION,
+            game_mode: game_options.game_mode,
+            num_players: game_options.num_players,
+            starting_level: game_options.starting_level,
+            initials: game_options.initials.clone(),
+            theme: game_options.vec_themes[game_options.theme_index].0.clone(),
+            settings: game_options.settings,
+        };
+        // whatever the command line set is only for this run
+        if let Some(options_under_cli) = &game_options.options_under_cli {
+            saved_options.game_mode = options_under_cli.game_mode;
+            saved_options.num_players = options_under_cli.num_players;
+            saved_options.starting_level = options_under_cli.starting_level;
+            saved_options.settings.board_width_per_player =
+                options_under_cli.board_width_per_player;
+            saved_options.settings.board_width_constant = options_under_cli.board_width_constant;
+        }
+        saved_options
+    }
+}
+
+impl SavedOptions {
+    // anything out of the range the menus allow goes back to its default
+    fn apply(self, game_options: &mut MenuGameOptions) {
+        let default_settings = GameSettings::default();
+        game_options.game_mode = self.game_mode;
+        game_options.num_players = if self.game_mode == GameMode::Rotatris {
+            1
+        } else if (1..=MAX_NUM_PLAYERS).contains(&self.num_players) {
+            self.num_players
+        } else {
+            1
+        };
+        game_options.starting_level = if self.starting_level <= MAX_STARTING_LEVEL {
+            self.starting_level
+        } else {
+            0
+        };
+        match game_options
+            .vec_themes
+            .iter()
+            .position(|(name, _)| *name == self.theme)
+        {
+            Some(theme_index) => game_options.theme_index = theme_index,
+            None => println!(
+                "[!] there's no theme named \"{}\" anymore, using {}",
+                self.theme, game_options.vec_themes[0].0
+            ),
+        }
+        if self.initials.len() == 3 && self.initials.chars().all(|c| c.is_ascii_uppercase()) {
+            game_options.initials = self.initials;
+        }
+        game_options.settings = self.settings;
+        if !(MIN_BOARD_WIDTH_PER_PLAYER..=MAX_BOARD_WIDTH_PER_PLAYER)
+            .contains(&self.settings.board_width_per_player)
+        {
+            game_options.settings.board_width_per_player = default_settings.board_width_per_player;
+        }
+        if self.settings.board_width_constant > MAX_BOARD_WIDTH_CONSTANT {
+            game_options.settings.board_width_constant = default_settings.board_width_constant;
+        }
+    }
+}
+
+fn config_version() -> u32 {
+    CONFIG_VERSION
+}
+
+pub fn config_dir(ctx: &Context) -> PathBuf {
+    filesystem::user_config_dir(ctx).to_path_buf()
+}
+
+// things the game writes on its own (high scores, replays) go here instead of with the config
+pub fn data_dir(ctx: &Context) -> PathBuf {
+    filesystem::user_data_dir(ctx).to_path_buf()
+}
+
+// a missing file just means nothing was saved yet; a broken one is moved out of the way so it doesn't get overwritten
+pub fn load_controls(
+    dir: &Path,
+    arr_controls: &mut [(KeyboardControlScheme, bool)],
+    arr_gamepad_controls: &mut [GamepadControlScheme],
+    arr_profile_names: &mut [Option<String>],
+    arr_socd: &mut [SocdPolicy],
+    menu_keys: &mut MenuKeys,
+) {
+    let path = dir.join(CONTROLS_FILE);
+    let string = match fs::read_to_string(&path) {
+        Ok(string) => string,
+        Err(_) => return,
+    };
+    let controls_config: ControlsConfig = match toml::from_str(&string) {
+        Ok(controls_config) => controls_config,
+        Err(e) => {
+            report_corrupt_file(&path, &e.to_string());
+            return;
+        }
+    };
+    check_version(&path, controls_config.version);
+    // menu keys that trip over each other could leave the menus unusable, so those all go back to the defaults
+    if controls_config.menu_keys.is_usable() {
+        *menu_keys = controls_config.menu_keys;
+    } else {
+        println!(
+            "[!] {}: the menu keys can't share keys with each other, using the default menu keys",
+            path.display()
+        );
+        *menu_keys = MenuKeys::default();
+    }
+    let mut vec_used_keycode: Vec<KeyCode> = vec![];
+    for player_controls in controls_config.players.iter() {
+        let ctrls = match arr_controls.get_mut(player_controls.player as usize) {
+            Some(ctrls) => ctrls,
+            None => {
+                println!(
+                    "[!] {}: ignoring controls for player {}, which is past the max number of players",
+                    path.display(),
+                    player_controls.player as u16 + 1
+                );
+                continue;
+            }
+        };
+        ctrls.1 = player_controls.gamepad;
+        let owner = format!("player {}", player_controls.player as u16 + 1);
+        let mut sticks = player_controls.sticks;
+        if sticks.fix_up() {
+            println!(
+                "[!] {}: the stick settings of {} are out of range, using the defaults for those",
+                path.display(),
+                owner
+            );
+        }
+        arr_gamepad_controls[player_controls.player as usize] = GamepadBinding::to_scheme(
+            &GamepadBinding::fix_up(&player_controls.buttons, &path, &owner),
+            sticks,
+        );
+        arr_profile_names[player_controls.player as usize] = player_controls.profile.clone();
+        arr_socd[player_controls.player as usize] = player_controls.socd;
+        for binding in player_controls.keys.iter() {
+            // keys can't be shared between players (or movements), same as in the controls menu
+            if vec_used_keycode.contains(&binding.key)
+                || ctrls.0.keycodes_from_movement(binding.movement).len() >= MAX_KEYS_PER_MOVEMENT
+            {
+                println!(
+                    "[!] {}: ignoring {:?} for {:?} of player {}, it's already in use",
+                    path.display(),
+                    binding.key,
+                    binding.movement,
+                    player_controls.player + 1
+                );
+                continue;
+            }
+            vec_used_keycode.push(binding.key);
+            ctrls.0.add_pair(binding.key, binding.movement);
+        }
+    }
+    println!("[+] Loaded controls from {}", path.display());
+}
+
+pub fn save_controls(
+    dir: &Path,
+    arr_controls: &[(KeyboardControlScheme, bool)],
+    arr_gamepad_controls: &[GamepadControlScheme],
+    arr_profile_names: &[Option<String>],
+    arr_socd: &[SocdPolicy],
+    menu_keys: &MenuKeys,
+) {
+    let mut controls_config = ControlsConfig {
+        menu_keys: *menu_keys,
+        ..ControlsConfig::default()
+    };
+    for (player, (ctrls, profile)) in arr_controls.iter().zip(arr_profile_names).enumerate() {
+        let buttons = GamepadBinding::from_scheme(&arr_gamepad_controls[player]);
+        let sticks = arr_gamepad_controls[player].sticks;
+        if ctrls.0.is_empty()
+            && !ctrls.1
+            && profile.is_none()
+            && buttons.is_empty()
+            && sticks.is_default()
+            && arr_socd[player] == SocdPolicy::default()
+        {
+            continue;
+        }
+        controls_config.players.push(PlayerControls {
+            player: player as u8,
+            gamepad: ctrls.1,
+            keys: KeyBinding::from_scheme(&ctrls.0),
+            buttons,
+            profile: profile.clone(),
+            socd: arr_socd[player],
+            sticks,
+        });
+    }
+    match toml::to_string(&controls_config) {
+        Ok(string) => write_file(dir, CONTROLS_FILE, &string),
+        Err(e) => println!("[!] failed to serialize controls: {}", e),
+    }
+}
+
+pub fn load_options(dir: &Path, game_options: &mut MenuGameOptions) {
+    let path = dir.join(OPTIONS_FILE);
+    let string = match fs::read_to_string(&path) {
+        Ok(string) => string,
+        Err(_) => return,
+    };
+    match toml::from_str::<SavedOptions>(&string) {
+        Ok(saved_options) => {
+            check_version(&path, saved_options.version);
+            saved_options.apply(game_options);
+            println!("[+] Loaded options from {}", path.display());
+        }
+        Err(e) => report_corrupt_file(&path, &e.to_string()),
+    }
+}
+
+pub fn save_options(dir: &Path, game_options: &MenuGameOptions) {
+    match toml::to_string(&SavedOptions::from(game_options)) {
+        Ok(string) => write_file(dir, OPTIONS_FILE, &string),
+        Err(e) => println!("[!] failed to serialize options: {}", e),
+    }
+}
+
+// a newer file still gets read as far as this version understands it
+pub fn check_version(path: &Path, version: u32) {
+    if version > CONFIG_VERSION {
+        println!(
+            "[!] {} is from a newer version (config version {}, this is {}); some of it may be ignored",
+            path.display(),
+            version,
+            CONFIG_VERSION
+        );
+    }
+}
+
+pub fn write_file(dir: &Path, file_name: &str, contents: &str) {
+    let path = dir.join(file_name);
+    if let Err(e) = fs::create_dir_all(dir) {
+        println!("[!] failed to create {}: {}", dir.display(), e);
+    } else if let Err(e) = fs::write(&path, contents) {
+        println!("[!] failed to write {}: {}", path.display(), e);
+    }
+}
+
+// a folder of a test's own for its files, made fresh and deleted again once the test is done with it (even if it fails)
+#[cfg(test)]
+pub struct TestDir(PathBuf);
+
+#[cfg(test)]
+impl TestDir {
+    pub fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("tetrisn-t-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).expect("[!] Failed to create test directory");
+        Self(path)
+    }
+}
+
+#[cfg(test)]
+impl std::ops::Deref for TestDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+pub fn report_corrupt_file(path: &Path, error: &str) {
+    let mut corrupt_path = path.as_os_str().to_owned();
+    corrupt_path.push(".corrupt");
+    let corrupt_path = PathBuf::from(corrupt_path);
+    println!(
+        "[!] {} is corrupt and will not be used: {}",
+        path.display(),
+        error
+    );
+    match fs::rename(path, &corrupt_path) {
+        Ok(()) => println!("[!] moved it to {}", corrupt_path.display()),
+        Err(e) => println!("[!] failed to move it out of the way: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn controls_round_trip() {
+        let dir = TestDir::new("controls");
+        let mut arr_controls = vec![(KeyboardControlScheme::default(), false); 4];
+        arr_controls[0].0.add_pair(KeyCode::Left, Movement::Left);
+        arr_controls[0].0.add_pair(KeyCode::Right, Movement::Right);
+        arr_controls[0].0.add_pair(KeyCode::Down, Movement::Down);
+        arr_controls[0].0.add_pair(KeyCode::X, Movement::RotateCw);
+        arr_controls[0].0.add_pair(KeyCode::Z, Movement::RotateCcw);
+        // WASD alongside the arrow keys
+        arr_controls[0].0.add_pair(KeyCode::A, Movement::Left);
+        arr_controls[0].0.add_pair(KeyCode::D, Movement::Right);
+        arr_controls[0].0.add_pair(KeyCode::S, Movement::Down);
+        arr_controls[2].1 = true;
+        // rotations on the shoulder buttons
+        let mut arr_gamepad_controls = vec![GamepadControlScheme::default(); 4];
+        arr_gamepad_controls[2] = GamepadControlScheme::new_empty();
+        arr_gamepad_controls[2]
+            .add_pair(GamepadInput::AxisNegative(Axis::LeftStickX), Movement::Left);
+        arr_gamepad_controls[2].add_pair(
+            GamepadInput::AxisPositive(Axis::LeftStickX),
+            Movement::Right,
+        );
+        arr_gamepad_controls[2].add_pair(GamepadInput::Button(Button::DPadDown), Movement::Down);
+        arr_gamepad_controls[2].add_pair(
+            GamepadInput::Button(Button::RightTrigger),
+            Movement::RotateCw,
+        );
+        arr_gamepad_controls[2].add_pair(
+            GamepadInput::Button(Button::LeftTrigger),
+            Movement::RotateCcw,
+        );
+        // a drifting controller on the default layout, with its y axis upside down
+        arr_gamepad_controls[3].sticks = StickSettings {
+            deadzone: 35,
+            invert_y: true,
+            ..StickSettings::default()
+        };
+        let mut arr_profile_names: Vec<Option<String>> = vec![None; 4];
+        arr_profile_names[0] = Some("CAT".to_string());
+        // a hitbox-style controller that stops on left and right together
+        let mut arr_socd = vec![SocdPolicy::default(); 4];
+        arr_socd[2] = SocdPolicy::Neutral;
+        // player one keeps Escape, so backing out and pausing move elsewhere
+        let menu_keys = MenuKeys {
+            back: KeyCode::Back,
+            pause: KeyCode::P,
+            ..MenuKeys::default()
+        };
+        save_controls(
+            &dir,
+            &arr_controls,
+            &arr_gamepad_controls,
+            &arr_profile_names,
+            &arr_socd,
+            &menu_keys,
+        );
+
+        let mut arr_loaded = vec![(KeyboardControlScheme::default(), false); 4];
+        let mut arr_loaded_gamepad = vec![GamepadControlScheme::new_empty(); 4];
+        let mut arr_loaded_names: Vec<Option<String>> = vec![None; 4];
+        let mut arr_loaded_socd = vec![SocdPolicy::default(); 4];
+        let mut loaded_menu_keys = MenuKeys::default();
+        load_controls(
+            &dir,
+            &mut arr_loaded,
+            &mut arr_loaded_gamepad,
+            &mut arr_loaded_names,
+            &mut arr_loaded_socd,
+            &mut loaded_menu_keys,
+        );
+        assert_eq!(
+            arr_loaded[0].0.vec_keycode_movement_pair,
+            arr_controls[0].0.vec_keycode_movement_pair
+        );
+        assert!(!arr_loaded[0].1);
+        assert!(arr_loaded[1].0.is_empty() && !arr_loaded[1].1);
+        assert!(arr_loaded[2].1);
+        assert!(arr_loaded_gamepad[0] == GamepadControlScheme::default());
+        assert!(arr_loaded_gamepad[2] == arr_gamepad_controls[2]);
+        assert!(arr_loaded_gamepad[3] == arr_gamepad_controls[3]);
+        assert_eq!(arr_loaded_names, arr_profile_names);
+        assert_eq!(arr_loaded_socd, arr_socd);
+        assert_eq!(loaded_menu_keys, menu_keys);
+
+        // menu keys that collide go back to the defaults
+        fs::write(dir.join(CONTROLS_FILE), "[menu_keys]\nup = \"Down\"\n").unwrap();
+        load_controls(
+            &dir,
+            &mut arr_loaded,
+            &mut arr_loaded_gamepad,
+            &mut arr_loaded_names,
+            &mut arr_loaded_socd,
+            &mut loaded_menu_keys,
+        );
+        assert_eq!(loaded_menu_keys, MenuKeys::default());
+
+        // a broken file leaves the controls alone and gets moved aside
+        fs::write(dir.join(CONTROLS_FILE), "players = 3").unwrap();
+        let mut arr_untouched = vec![(KeyboardControlScheme::default(), false); 4];
+        load_controls(
+            &dir,
+            &mut arr_untouched,
+            &mut arr_loaded_gamepad,
+            &mut arr_loaded_names,
+            &mut arr_loaded_socd,
+            &mut loaded_menu_keys,
+        );
+        assert!(arr_untouched.iter().all(|c| c.0.is_empty() && !c.1));
+        assert!(!dir.join(CONTROLS_FILE).exists());
+    }
+
+    #[test]
+    fn options_defaults_and_ranges() {
+        // fields missing from an older file keep their defaults; bad values are fixed up
+        let saved_options: SavedOptions = toml::from_str(
+            "version = 1\ngame_mode = \"Classic\"\nnum_players = 200\nstarting_level = 5\n\n[settings]\nboard_width_per_player = 2\n",
+        )
+        .unwrap();
+        let mut game_options = MenuGameOptions::default();
+        saved_options.apply(&mut game_options);
+        assert_eq!(game_options.game_mode, GameMode::Classic);
+        assert_eq!(game_options.num_players, 1);
+        assert_eq!(game_options.starting_level, 5);
+        assert_eq!(game_options.initials, "AAA");
+        assert!(game_options.settings == GameSettings::default());
+        assert_eq!(game_options.theme_index, 0);
+
+        let saved_options: SavedOptions = toml::from_str("theme = \"Midnight\"\n").unwrap();
+        saved_options.apply(&mut game_options);
+        assert_eq!(
+            game_options.vec_themes[game_options.theme_index].0,
+            "Midnight"
+        );
+
+        let string = toml::to_string(&SavedOptions::from(&game_options)).unwrap();
+        assert!(
+            toml::from_str::<SavedOptions>(&string).unwrap() == SavedOptions::from(&game_options)
+        );
+    }
+}
@@ -4,19 +4,36 @@ use ggez::graphics;
 use ggez::timer;
 use ggez::{Context, GameResult};
 
+use crate::cli::CliOptions;
+use crate::config;
+use crate::game::replay::Replay;
+use crate::game::savegame;
+use crate::game::tile::TileSkin;
 use crate::game::{Game, GameOptions};
+use crate::highscores::HighScores;
 use crate::menu::{menuhelpers::MenuGameOptions, Menu};
+use crate::profiles;
+use crate::theme;
+
+use std::path::PathBuf;
+
+pub const DESIRED_FPS: u32 = 60;
 
 static STATE_MENU_BUT_MENU_NONE: &str =
     "[!] control.state == ProgramState::Menu but control.menu == None";
 static STATE_GAME_BUT_GAME_NONE: &str =
     "[!] control.state == ProgramState::Game but control.game == None";
+static STATE_TRANSITION: &str =
+    "[!] control.state == ProgramState::Restart or ProgramState::Continue, which are only transitions";
 
 #[repr(u8)]
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub enum ProgramState {
     Menu,
     Game,
+    Restart,
+    // picks up the saved game, or goes back to the menu if it can't be loaded
+    Continue,
 }
 
 pub struct Control {
@@ -24,29 +41,128 @@ pub struct Control {
     menu: Option<Menu>,
     game: Option<Game>,
     game_options: MenuGameOptions,
+    // only for games asked for on the command line (and restarts of them); going back to the menu drops these
+    seed: Option<u64>,
+    playback: Option<Replay>,
 }
 
 impl Control {
-    pub fn new(ctx: &mut Context) -> Control {
-        let menu_game_options = MenuGameOptions::default();
-        Self {
+    pub fn new(
+        ctx: &mut Context,
+        cli_options: &CliOptions,
+        vec_resource_dirs: &[(&'static str, PathBuf)],
+    ) -> Control {
+        let mut menu_game_options = MenuGameOptions::default();
+        let config_dir = config::config_dir(ctx);
+        // the saved options pick a theme by name, so the theme files go first
+        menu_game_options.vec_themes = theme::load_themes(&config_dir);
+        config::load_options(&config_dir, &mut menu_game_options);
+        menu_game_options.tile_skin = TileSkin::load(vec_resource_dirs);
+        menu_game_options.profiles = profiles::load_profiles(&config_dir);
+        config::load_controls(
+            &config_dir,
+            &mut menu_game_options.arr_controls,
+            &mut menu_game_options.arr_gamepad_controls,
+            &mut menu_game_options.arr_profile_names,
+            &mut menu_game_options.arr_socd,
+            &mut menu_game_options.menu_keys,
+        );
+        menu_game_options.forget_missing_profiles();
+        let mut start_flag = cli_options.starts_game();
+        if start_flag {
+            cli_options.apply(&mut menu_game_options);
+        }
+        // the saved game mode decides which saved keyboard schemes are usable
+        menu_game_options.clear_incomplete_controls();
+        if start_flag && !menu_game_options.enough_controls() {
+            println!(
+                "[!] Not enough controls are set up for {} players, starting at the menu",
+                menu_game_options.num_players
+            );
+            start_flag = false;
+        }
+        let playback = match &cli_options.replay {
+            Some(path) => match Replay::load(path) {
+                Ok(replay) => {
+                    println!("[+] Playing back {}", path.display());
+                    Some(replay)
+                }
+                Err(e) => {
+                    println!("[!] Failed to load replay: {}", e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let mut control = Self {
             state: ProgramState::Menu,
-            menu: Some(Menu::new(ctx, &menu_game_options)),
+            menu: None,
             game: None,
             game_options: menu_game_options,
+            seed: cli_options.seed,
+            playback,
+        };
+        if start_flag || control.playback.is_some() {
+            control.change_state(ctx, ProgramState::Game);
+        } else {
+            control.change_state(ctx, ProgramState::Menu);
         }
+        control
     }
 
     fn change_state(&mut self, ctx: &mut Context, new_state: ProgramState) {
         self.state = match new_state {
             ProgramState::Menu => {
+                self.seed = None;
+                self.playback = None;
                 self.menu = Some(Menu::new(ctx, &self.game_options));
                 ProgramState::Menu
             }
-            ProgramState::Game => {
-                self.game = Some(Game::new(ctx, &GameOptions::from(&self.game_options)));
+            // restarting builds a new game from the same options without going through the menu
+            ProgramState::Game | ProgramState::Restart => {
+                self.game = Some(match &self.playback {
+                    Some(replay) => {
+                        // replays don't keep the overlay or tile patterns, it's up to whoever is watching
+                        let mut replay = replay.clone();
+                        replay.settings.input_overlay_state =
+                            self.game_options.settings.input_overlay_state;
+                        replay.settings.tile_patterns_state =
+                            self.game_options.settings.tile_patterns_state;
+                        Game::new_playback(
+                            ctx,
+                            replay,
+                            &self.game_options.initials,
+                            self.game_options.theme(),
+                            &self.game_options.tile_skin,
+                        )
+                    }
+                    None => {
+                        let mut game_options = GameOptions::from(&self.game_options);
+                        game_options.seed = self.seed;
+                        Game::new(ctx, &game_options)
+                    }
+                });
                 ProgramState::Game
             }
+            ProgramState::Continue => match savegame::take(&config::data_dir(ctx)) {
+                Some(mut saved_game) => {
+                    println!("[+] Continuing the saved game");
+                    // like the theme, tile patterns are whatever is picked now
+                    saved_game.settings.tile_patterns_state =
+                        self.game_options.settings.tile_patterns_state;
+                    self.game = Some(Game::from_saved_game(
+                        ctx,
+                        saved_game,
+                        self.game_options.theme(),
+                        &self.game_options.tile_skin,
+                    ));
+                    ProgramState::Game
+                }
+                None => {
+                    self.menu = Some(Menu::new(ctx, &self.game_options));
+                    ProgramState::Menu
+                }
+            },
         };
     }
 }
@@ -54,8 +170,6 @@ impl Control {
 // this is run once every frame and passes control off to whichever state the game is in
 impl EventHandler<ggez::GameError> for Control {
     fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
-        const DESIRED_FPS: u32 = 60;
-
         while timer::check_update_time(ctx, DESIRED_FPS) {
             match self.state {
                 ProgramState::Menu => {
@@ -64,7 +178,7 @@ impl EventHandler<ggez::GameError> for Control {
                         .menu
                         .as_mut()
                         .expect(STATE_MENU_BUT_MENU_NONE)
-                        .update(&mut self.game_options)
+                        .update(ctx, &mut self.game_options)
                     {
                         self.menu = None;
                         self.change_state(ctx, new_state);
@@ -72,14 +186,61 @@ impl EventHandler<ggez::GameError> for Control {
                 }
                 ProgramState::Game => {
                     // update the game and get the state that the program should be in
-                    let state_returned =
-                        self.game.as_mut().expect(STATE_GAME_BUT_GAME_NONE).update();
+                    let state_returned = self
+                        .game
+                        .as_mut()
+                        .expect(STATE_GAME_BUT_GAME_NONE)
+                        .update(ctx);
                     // should we change states?
                     if self.state != state_returned {
-                        self.game = None;
+                        let last_game = self.game.take().expect(STATE_GAME_BUT_GAME_NONE);
+                        // keep settings that were changed from the pause menu (a replay's settings aren't anyone's)
+                        if self.playback.is_none() {
+                            let saved_options = config::SavedOptions::from(&self.game_options);
+                            self.game_options.settings = last_game.settings;
+                            self.game_options.initials = last_game.get_initials();
+                            if config::SavedOptions::from(&self.game_options) != saved_options {
+                                config::save_options(&config::config_dir(ctx), &self.game_options);
+                            }
+                        }
+                        let vec_profile_results = last_game.profile_results();
+                        if !vec_profile_results.is_empty() {
+                            for (name, player_stats, frames) in vec_profile_results.iter() {
+                                if let Some(profile) = self
+                                    .game_options
+                                    .profiles
+                                    .iter_mut()
+                                    .find(|profile| &profile.name == name)
+                                {
+                                    profile.stats.add_game(player_stats, *frames);
+                                }
+                            }
+                            profiles::save_profiles(
+                                &config::config_dir(ctx),
+                                &self.game_options.profiles,
+                            );
+                        }
+                        if let Some((key, entry)) = last_game.high_score_entry() {
+                            let data_dir = config::data_dir(ctx);
+                            let mut high_scores = HighScores::load(&data_dir);
+                            if let Some(place) = high_scores.add(key, entry) {
+                                println!("[+] New high score, #{} in its table", place + 1);
+                                high_scores.save(&data_dir);
+                            }
+                        }
                         self.change_state(ctx, state_returned);
+                        if state_returned == ProgramState::Restart {
+                            // so players don't have to press buttons to get their gamepads assigned again
+                            self.game
+                                .as_mut()
+                                .expect(STATE_GAME_BUT_GAME_NONE)
+                                .keep_gamepad_mappings(&last_game);
+                        }
                     }
                 }
+                ProgramState::Restart | ProgramState::Continue => {
+                    unreachable!("{}", STATE_TRANSITION)
+                }
             };
         }
 
@@ -88,7 +249,7 @@ impl EventHandler<ggez::GameError> for Control {
 
     fn key_down_event(
         &mut self,
-        _ctx: &mut Context,
+        ctx: &mut Context,
         keycode: KeyCode,
         _keymod: KeyMods,
         repeat: bool,
@@ -98,12 +259,13 @@ impl EventHandler<ggez::GameError> for Control {
                 .menu
                 .as_mut()
                 .expect(STATE_MENU_BUT_MENU_NONE)
-                .key_down_event(keycode, repeat),
+                .key_down_event(keycode, repeat, &self.game_options.menu_keys),
             ProgramState::Game => self
                 .game
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
-                .key_down_event(keycode, repeat),
+                .key_down_event(ctx, keycode, repeat, &self.game_options.menu_keys),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
     }
 
@@ -113,45 +275,61 @@ impl EventHandler<ggez::GameError> for Control {
                 .menu
                 .as_mut()
                 .expect(STATE_MENU_BUT_MENU_NONE)
-                .key_up_event(keycode),
+                .key_up_event(keycode, &self.game_options.menu_keys),
             ProgramState::Game => self
                 .game
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
-                .key_up_event(keycode),
+                .key_up_event(keycode, &self.game_options.menu_keys),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
     }
 
-    fn gamepad_button_down_event(&mut self, _ctx: &mut Context, btn: Button, id: GamepadId) {
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, id: GamepadId) {
         match self.state {
-            ProgramState::Menu => (),
+            ProgramState::Menu => self
+                .menu
+                .as_mut()
+                .expect(STATE_MENU_BUT_MENU_NONE)
+                .gamepad_button_down_event(ctx, btn, id),
             ProgramState::Game => self
                 .game
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
-                .gamepad_button_down_event(btn, id),
+                .gamepad_button_down_event(ctx, btn, id),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
     }
 
     fn gamepad_button_up_event(&mut self, _ctx: &mut Context, btn: Button, id: GamepadId) {
         match self.state {
-            ProgramState::Menu => (),
+            ProgramState::Menu => self
+                .menu
+                .as_mut()
+                .expect(STATE_MENU_BUT_MENU_NONE)
+                .gamepad_button_up_event(btn),
             ProgramState::Game => self
                 .game
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
                 .gamepad_button_up_event(btn, id),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
     }
 
     fn gamepad_axis_event(&mut self, _ctx: &mut Context, axis: Axis, value: f32, id: GamepadId) {
         match self.state {
-            ProgramState::Menu => (),
+            ProgramState::Menu => self
+                .menu
+                .as_mut()
+                .expect(STATE_MENU_BUT_MENU_NONE)
+                .gamepad_axis_event(axis, value, id, &self.game_options),
             ProgramState::Game => self
                 .game
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
                 .gamepad_axis_event(axis, value, id),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         }
     }
 
@@ -167,6 +345,7 @@ impl EventHandler<ggez::GameError> for Control {
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
                 .draw(ctx),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
 
         graphics::present(ctx)
@@ -187,6 +366,7 @@ impl EventHandler<ggez::GameError> for Control {
                 .as_mut()
                 .expect(STATE_GAME_BUT_GAME_NONE)
                 .resize_event(width, height),
+            ProgramState::Restart | ProgramState::Continue => unreachable!("{}", STATE_TRANSITION),
         };
     }
 
@@ -1,24 +1,45 @@
 use ggez::event::{Axis, Button, GamepadId, KeyCode};
 use ggez::graphics::{self, spritebatch, DrawParam};
 use ggez::graphics::{PxScale, Text, TextFragment};
+use ggez::input::keyboard;
 use ggez::Context;
 
 use ggez::mint::{Point2, Vector2};
 
 use rand::random;
+use rand::SeedableRng;
+use rand_pcg::Pcg64Mcg;
+use serde::{Deserialize, Serialize};
 
+use crate::config;
 use crate::control::ProgramState;
 use crate::movement::Movement;
 use crate::movement::CONVERSION_FAILED_MOVEMENT_FROM_U8;
 
 use std::convert::TryFrom;
+use std::path::PathBuf;
 
 mod player;
 use crate::game::player::{Player, SPAWN_DELAY};
 
-mod tile;
-use crate::game::tile::TileGraphic;
+mod pausemenu;
+use crate::game::pausemenu::PauseMenu;
+
+pub mod stats;
+use crate::game::stats::{GameStats, PlayerStats, NUM_COUNTED_INPUTS};
+
+pub mod replay;
+use crate::game::replay::{Replay, ReplayEvent};
+
+mod results;
+use crate::game::results::{PlayerResult, ResultsScreen};
+
+pub mod savegame;
+use crate::game::savegame::{SavedGame, SAVEGAME_VERSION};
+
+pub mod tile;
 use crate::game::tile::NUM_PIXEL_ROWS_PER_TILEGRAPHIC;
+use crate::game::tile::{TileGraphic, TileSkin};
 
 mod piece;
 use crate::game::piece::{NextPiece, Shapes};
@@ -27,8 +48,12 @@ pub mod board;
 use crate::game::board::BoardHandler;
 use crate::game::board::{BoardDim, BoardPos, BOARD_HEIGHT, ROTATRIS_BOARD_SIDE_LENGTH};
 
-use crate::inputs::KeyboardControlScheme;
-use crate::menu::menuhelpers::MenuGameOptions;
+use crate::highscores::{HighScoreEntry, HighScoreKey};
+use crate::inputs::{GamepadControlScheme, InputEvent, KeyboardControlScheme, MenuKeys};
+use crate::inputs::{SocdPolicy, StickSettings};
+use crate::menu::menuhelpers::{MenuGameOptions, MenuItemTrigger, MAX_NUM_PLAYERS};
+use crate::profiles::Profile;
+use crate::theme::Theme;
 
 pub const CLEAR_DELAY_CLASSIC: i8 = 30i8;
 
@@ -38,6 +63,9 @@ pub const SCORE_TRIPLE_BASE: u16 = 300u16;
 pub const SCORE_QUADRUPLE_BASE: u16 = 1200u16;
 
 const GAME_OVER_DELAY: i8 = 60i8;
+// Select on a gamepad has to be held this many frames to restart, so a tap
+// (or Select landing a frame before Down when leaving) doesn't restart everyone's game
+const RESTART_HOLD_FRAMES: u8 = 45u8;
 
 // space up of the board that is not the board in tiles
 const NON_BOARD_SPACE_U: BoardDim = 4;
@@ -49,6 +77,13 @@ const NON_BOARD_SPACE_D: BoardDim = 3;
 const TILE_SIZE_DOWN_SCALE: f32 = 8.5;
 
 const LITTLE_TEXT_SCALE: f32 = 20.0;
+// profile names over the spawn columns, relative to the tile size
+const PROFILE_NAME_SCALE: f32 = 0.8;
+// the input overlay's buttons and the space between them, in tiles, and how many go in a row
+const INPUT_OVERLAY_BUTTON_SIZE: f32 = 0.8;
+const INPUT_OVERLAY_BUTTON_SPACING: f32 = 0.1;
+const INPUT_OVERLAY_BUTTONS_PER_ROW: usize = 4;
+const INPUT_OVERLAY_UNLIT: graphics::Color = graphics::Color::new(0.3, 0.3, 0.3, 1.0);
 
 // for each level (as the index), the number of frames it takes for a piece to move down one row (everything after 29 is also 0)
 // it's actually 1 less than the number of frames it takes the piece to fall because the game logic works out better that way
@@ -66,21 +101,16 @@ const FALL_DELAY_VALUES_ROTATRIS: [u8; 30] = [
 pub const FORCE_FALL_DELAY: u8 = 2;
 
 // first das threshold (eg left is pressed; how many frames to until the piece auto-shifts left?)
-const DAS_THRESHOLD_BIG: u8 = 14;
+pub const DAS_THRESHOLD_BIG: u8 = 14;
 // second das threshold (eg left is pressed and it auto shifts once; how many frames until it auto-shifts again?)
-const DAS_THRESHOLD_LITTLE: u8 = 5;
+pub const DAS_THRESHOLD_LITTLE: u8 = 5;
 
 // how long the pieces don't move down at the start
 pub const INITIAL_HANG_FRAMES: u8 = 180;
 
-// gamepad joystick thresholds
-pub const DETECT_GAMEPAD_AXIS_THRESHOLD: f32 = 0.5;
-pub const UNDETECT_GAMEPAD_AXIS_THRESHOLD: f32 = 0.2;
-
-static INVALID_MENU_CONTROLS: &str = "[!] last used controls was Some() but invalid data";
 static GAME_MODE_NONE: &str = "[!] GameMode unexpectedly None";
 
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum GameMode {
     None,
     Classic,
@@ -107,11 +137,19 @@ impl From<usize> for GameMode {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GameSettings {
     pub ghost_pieces_state: bool,
     pub board_width_per_player: BoardDim,
     pub board_width_constant: BoardDim,
+    pub elimination_state: bool,
+    // a rotation pressed during the spawn delay rotates the next piece as it spawns (IRS)
+    pub initial_rotation_state: bool,
+    // each player's inputs lit up under their spawn column
+    pub input_overlay_state: bool,
+    // a different pattern stamped on each player's tiles, for telling players apart without relying on color
+    pub tile_patterns_state: bool,
 }
 
 impl Default for GameSettings {
@@ -120,6 +158,21 @@ impl Default for GameSettings {
             ghost_pieces_state: true,
             board_width_per_player: 4,
             board_width_constant: 6,
+            elimination_state: false,
+            initial_rotation_state: false,
+            input_overlay_state: false,
+            tile_patterns_state: false,
+        }
+    }
+}
+
+impl GameSettings {
+    // the pattern for `player_num`'s tiles, if they get one
+    pub fn tile_pattern(&self, player_num: u8) -> Option<u8> {
+        if self.tile_patterns_state {
+            Some(player_num)
+        } else {
+            None
         }
     }
 }
@@ -130,81 +183,91 @@ pub struct GameOptions {
     pub starting_level: u8,
     pub game_mode: GameMode,
     pub vec_controls: Vec<(Option<KeyboardControlScheme>, bool)>,
+    // the profile of each player in vec_controls, if they picked one
+    pub vec_profiles: Vec<Option<Profile>>,
+    // the gamepad layout of each player in vec_controls; only used by the ones on a gamepad
+    pub vec_gamepad_controls: Vec<GamepadControlScheme>,
+    // the gamepad each player in vec_controls picked in the lobby; None gets the first gamepad that presses something in game
+    pub vec_gamepad_ids: Vec<Option<GamepadId>>,
+    // how each player in vec_controls settles holding left and right together
+    pub vec_socd: Vec<SocdPolicy>,
+    // configured keyboard schemes that aren't in use at the start and can drop in during a classic game
+    pub vec_drop_in_controls: Vec<(KeyboardControlScheme, Option<Profile>, SocdPolicy)>,
     pub settings: GameSettings,
+    // the same seed and inputs always play out the same game; None picks a random seed
+    pub seed: Option<u64>,
+    pub initials: String,
+    // only changes how the game looks, so replays and saved games are played with whichever theme is picked
+    pub theme: Theme,
+    // the same goes for the tile skin
+    pub tile_skin: TileSkin,
 }
 
 impl From<&MenuGameOptions> for GameOptions {
     fn from(menu_game_options: &MenuGameOptions) -> Self {
         let mut vec_controls: Vec<(Option<KeyboardControlScheme>, bool)> =
             Vec::with_capacity(menu_game_options.arr_controls.len());
+        let mut vec_profiles: Vec<Option<Profile>> =
+            Vec::with_capacity(menu_game_options.arr_controls.len());
+        let mut vec_gamepad_controls: Vec<GamepadControlScheme> =
+            Vec::with_capacity(menu_game_options.arr_controls.len());
+        let mut vec_gamepad_ids: Vec<Option<GamepadId>> =
+            Vec::with_capacity(menu_game_options.arr_controls.len());
+        let mut vec_socd: Vec<SocdPolicy> =
+            Vec::with_capacity(menu_game_options.arr_controls.len());
+        let mut vec_drop_in_controls: Vec<(KeyboardControlScheme, Option<Profile>, SocdPolicy)> =
+            vec![];
         let mut counted_active_controls: u8 = 0;
         match menu_game_options.game_mode {
             GameMode::None => unreachable!("{}", GAME_MODE_NONE),
             GameMode::Classic => {
-                for ctrls in menu_game_options.arr_controls.iter() {
+                for (slot, ctrls) in menu_game_options.arr_controls.iter().enumerate() {
+                    let profile = menu_game_options.slot_profile(slot).cloned();
+                    if counted_active_controls == menu_game_options.num_players {
+                        // every other configured keyboard scheme can join in later
+                        if !(ctrls.0).is_empty() {
+                            vec_drop_in_controls.push((
+                                classic_scheme_from_menu(&ctrls.0),
+                                profile,
+                                menu_game_options.arr_socd[slot],
+                            ));
+                        }
+                        continue;
+                    }
                     if !(ctrls.0).is_empty() {
-                        vec_controls.push((
-                            Some(KeyboardControlScheme::new_classic(
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Left)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Right)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Down)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::RotateCw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::RotateCcw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                            )),
-                            false,
-                        ));
+                        vec_controls.push((Some(classic_scheme_from_menu(&ctrls.0)), false));
+                        vec_profiles.push(profile);
+                        vec_gamepad_controls.push(GamepadControlScheme::default());
+                        vec_gamepad_ids.push(None);
+                        vec_socd.push(menu_game_options.arr_socd[slot]);
                         counted_active_controls += 1;
                     } else if ctrls.1 {
                         vec_controls.push((None, true));
+                        vec_profiles.push(profile);
+                        vec_gamepad_controls
+                            .push(menu_game_options.arr_gamepad_controls[slot].clone());
+                        vec_gamepad_ids.push(menu_game_options.arr_gamepad_ids[slot]);
+                        vec_socd.push(menu_game_options.arr_socd[slot]);
                         counted_active_controls += 1;
                     }
-                    if counted_active_controls == menu_game_options.num_players {
-                        break;
-                    }
                 }
             }
             GameMode::Rotatris => {
-                for ctrls in menu_game_options.arr_controls.iter() {
+                for (slot, ctrls) in menu_game_options.arr_controls.iter().enumerate() {
                     if !(ctrls.0).is_empty() {
-                        vec_controls.push((
-                            Some(KeyboardControlScheme::new_rotatris(
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Left)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Right)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::Down)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::RotateCw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::RotateCcw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::BoardCw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                                (ctrls.0)
-                                    .keycode_from_movement(Movement::BoardCcw)
-                                    .expect(INVALID_MENU_CONTROLS),
-                            )),
-                            false,
-                        ));
+                        vec_controls.push((Some(ctrls.0.copy()), false));
+                        vec_profiles.push(menu_game_options.slot_profile(slot).cloned());
+                        vec_gamepad_controls.push(GamepadControlScheme::default());
+                        vec_gamepad_ids.push(None);
+                        vec_socd.push(menu_game_options.arr_socd[slot]);
                         counted_active_controls += 1;
                     } else if ctrls.1 {
                         vec_controls.push((None, true));
+                        vec_profiles.push(menu_game_options.slot_profile(slot).cloned());
+                        vec_gamepad_controls
+                            .push(menu_game_options.arr_gamepad_controls[slot].clone());
+                        vec_gamepad_ids.push(menu_game_options.arr_gamepad_ids[slot]);
+                        vec_socd.push(menu_game_options.arr_socd[slot]);
                         counted_active_controls += 1;
                     }
                     if counted_active_controls == menu_game_options.num_players {
@@ -219,11 +282,33 @@ impl From<&MenuGameOptions> for GameOptions {
             starting_level: menu_game_options.starting_level,
             game_mode: menu_game_options.game_mode,
             vec_controls,
+            vec_profiles,
+            vec_gamepad_controls,
+            vec_gamepad_ids,
+            vec_socd,
+            vec_drop_in_controls,
             settings: menu_game_options.settings,
+            seed: None,
+            initials: menu_game_options.initials.clone(),
+            theme: *menu_game_options.theme(),
+            tile_skin: menu_game_options.tile_skin.clone(),
         }
     }
 }
 
+// a profile's color takes over the lane's color, except in single player where the colors go by level
+pub fn lane_color(player_num: u8, profile: Option<&Profile>, num_players: u8) -> u8 {
+    match profile.and_then(|profile| profile.color) {
+        Some(color) if num_players > 1 => color,
+        _ => player_num,
+    }
+}
+
+// the menu can still have board rotation keys from rotatris, which classic has no use for
+fn classic_scheme_from_menu(ctrls: &KeyboardControlScheme) -> KeyboardControlScheme {
+    ctrls.without_movements(&[Movement::BoardCw, Movement::BoardCcw])
+}
+
 pub struct Game {
     // GAME STUFF
     // logic (mostly)
@@ -237,14 +322,34 @@ pub struct Game {
     starting_level: u8,
     num_cleared_lines: u16,
     score: u64,
-    keycode_down_flags: (bool, bool),
-    keycode_escape_flags: (bool, bool),
+    keycode_pause_flags: (bool, bool),
+    keycode_restart_flags: (bool, bool),
+    // how long a gamepad's Select has been held toward restarting
+    select_hold_frames: u8,
+    // on the game over screen, gamepads don't belong to players anymore
+    results_select_held_flag: bool,
     pause_flags: (bool, bool),
     gravity_direction: Movement,
     game_over_flag: bool,
     game_over_delay: i8,
     determine_ghost_tile_locations: bool,
+    elimination_flag: bool,
+    pub settings: GameSettings,
+    frame_count: u32,
+    vec_drop_in_controls: Vec<(KeyboardControlScheme, Option<Profile>, SocdPolicy)>,
+    // (profile name, stats, frames played) of players with a profile that already left the game
+    vec_profile_results: Vec<(String, PlayerStats, u32)>,
+    board_width_per_player: BoardDim,
+    rng: Pcg64Mcg,
+    stats: GameStats,
+    replay: Replay,
+    // Some while playing back a replay, with the index of the next event to play back
+    playback_index: Option<usize>,
+    replay_dir: PathBuf,
+    savegame_dir: PathBuf,
     // drawing
+    theme: Theme,
+    tile_skin: TileSkin,
     tile_size: f32,
     batch_empty_tile: spritebatch::SpriteBatch,
     batch_highlight_active_tile: spritebatch::SpriteBatch,
@@ -254,8 +359,8 @@ pub struct Game {
     vec_batch_player_piece: Vec<spritebatch::SpriteBatch>,
     vec_batch_next_piece: Vec<spritebatch::SpriteBatch>,
     game_info_text: Text,
-    pause_text: Text,
-    game_over_text: Text,
+    pause_menu: PauseMenu,
+    results: ResultsScreen,
 }
 
 impl Game {
@@ -278,6 +383,8 @@ impl Game {
         let num_players = game_options.num_players;
         let bh = BoardHandler::new(board_width, board_height, num_players, mode);
         let spawn_columns: Vec<BoardPos> = bh.get_spawn_columns();
+        let seed: u64 = game_options.seed.unwrap_or_else(random);
+        let mut rng = Pcg64Mcg::seed_from_u64(seed);
         let mut vec_players: Vec<Player> = Vec::with_capacity(game_options.num_players as usize);
         for player_index in 0..num_players {
             // control_scheme; we need to create a copy of game_options.vec_controls, but to do that,
@@ -290,10 +397,17 @@ impl Game {
             vec_players.push(Player::new(
                 player_index,
                 control_scheme,
+                game_options.vec_profiles[player_index as usize].clone(),
                 spawn_columns[player_index as usize],
+                &mut rng,
             ));
+            vec_players[player_index as usize].gamepad_scheme =
+                game_options.vec_gamepad_controls[player_index as usize].clone();
+            vec_players[player_index as usize].socd = game_options.vec_socd[player_index as usize];
         }
-        let mut batch_empty_tile = spritebatch::SpriteBatch::new(TileGraphic::new_empty(ctx).image);
+        let mut batch_empty_tile = spritebatch::SpriteBatch::new(
+            TileGraphic::new_empty(ctx, &game_options.theme, &game_options.tile_skin).image,
+        );
         // the emtpy tile batch will be constant once the game starts with
         // the player tile batches drawing on top of it, so just set that up here
         for x in 0..board_width {
@@ -313,8 +427,11 @@ impl Game {
         let mut num_gamepads_to_initialize: u8 = 0;
         for (idx, controls) in game_options.vec_controls.iter().enumerate() {
             if controls.1 {
-                temp_vec.push((None, idx as u8));
-                num_gamepads_to_initialize += 1;
+                let gamepad_id = game_options.vec_gamepad_ids[idx];
+                temp_vec.push((gamepad_id, idx as u8));
+                if gamepad_id.is_none() {
+                    num_gamepads_to_initialize += 1;
+                }
             }
         }
         if !temp_vec.is_empty() {
@@ -329,12 +446,37 @@ impl Game {
         let mut vec_batch_next_piece: Vec<spritebatch::SpriteBatch> =
             Vec::with_capacity(std::cmp::max(game_options.num_players as usize, 3));
         for player in 0..std::cmp::max(game_options.num_players as usize, 3) {
+            let color = lane_color(
+                player as u8,
+                vec_players.get(player).and_then(|p| p.profile.as_ref()),
+                num_players,
+            );
+            // the single player's batches are different colors of the same player's pieces
+            let pattern = if num_players == 1 {
+                game_options.settings.tile_pattern(0)
+            } else {
+                game_options.settings.tile_pattern(player as u8)
+            };
             vec_next_piece.push(NextPiece::new(Shapes::None));
             vec_batch_player_piece.push(spritebatch::SpriteBatch::new(
-                TileGraphic::new_player(ctx, player as u8).image,
+                TileGraphic::new_player(
+                    ctx,
+                    color,
+                    pattern,
+                    &game_options.theme,
+                    &game_options.tile_skin,
+                )
+                .image,
             ));
             vec_batch_next_piece.push(spritebatch::SpriteBatch::new(
-                TileGraphic::new_player(ctx, player as u8).image,
+                TileGraphic::new_player(
+                    ctx,
+                    color,
+                    pattern,
+                    &game_options.theme,
+                    &game_options.tile_skin,
+                )
+                .image,
             ));
         }
         let little_text_scale = PxScale::from(LITTLE_TEXT_SCALE);
@@ -376,17 +518,6 @@ impl Game {
                 .color(graphics::Color::WHITE)
                 .scale(little_text_scale),
         );
-        let pause_text = Text::new(
-            TextFragment::new("PAUSED\n\nDown + ESC/Start to quit")
-                .color(graphics::Color::WHITE)
-                .scale(little_text_scale),
-        );
-        let game_over_text = Text::new(
-            TextFragment::new("Game Over!")
-                .color(graphics::Color::WHITE)
-                .scale(PxScale::from(LITTLE_TEXT_SCALE * 2.0)),
-        );
-
         let (window_width, window_height) = graphics::size(ctx);
 
         Self {
@@ -400,13 +531,35 @@ impl Game {
             starting_level: game_options.starting_level,
             num_cleared_lines: 0u16,
             score: 0u64,
-            keycode_down_flags: (false, false),
-            keycode_escape_flags: (false, false),
+            keycode_pause_flags: (false, false),
+            keycode_restart_flags: (false, false),
+            select_hold_frames: 0,
+            results_select_held_flag: false,
             pause_flags: (false, false),
             gravity_direction: Movement::Down,
             game_over_flag: false,
             game_over_delay: GAME_OVER_DELAY,
             determine_ghost_tile_locations: game_options.settings.ghost_pieces_state,
+            elimination_flag: game_options.settings.elimination_state,
+            settings: game_options.settings,
+            frame_count: 0,
+            vec_drop_in_controls: game_options
+                .vec_drop_in_controls
+                .iter()
+                .map(|(k_ctrl_scheme, profile, socd)| {
+                    (k_ctrl_scheme.copy(), profile.clone(), *socd)
+                })
+                .collect(),
+            vec_profile_results: vec![],
+            board_width_per_player: game_options.settings.board_width_per_player,
+            rng,
+            stats: GameStats::new(num_players),
+            replay: Replay::new(game_options, seed),
+            playback_index: None,
+            replay_dir: config::data_dir(ctx).join("replays"),
+            savegame_dir: config::data_dir(ctx),
+            theme: game_options.theme,
+            tile_skin: game_options.tile_skin.clone(),
             tile_size: TileGraphic::get_size(
                 window_width,
                 window_height,
@@ -415,37 +568,228 @@ impl Game {
             ),
             batch_empty_tile,
             batch_highlight_active_tile: spritebatch::SpriteBatch::new(
-                TileGraphic::new_active_highlight(ctx).image,
+                TileGraphic::new_active_highlight(
+                    ctx,
+                    &game_options.theme,
+                    &game_options.tile_skin,
+                )
+                .image,
             ),
             batch_highlight_clearing_standard_tile: spritebatch::SpriteBatch::new(
-                TileGraphic::new_clear_standard_highlight(ctx).image,
+                TileGraphic::new_clear_standard_highlight(
+                    ctx,
+                    &game_options.theme,
+                    &game_options.tile_skin,
+                )
+                .image,
             ),
             batch_highlight_clearing_tetrisnt_tile: spritebatch::SpriteBatch::new(
-                TileGraphic::new_clear_tetrisnt_highlight(ctx).image,
+                TileGraphic::new_clear_tetrisnt_highlight(
+                    ctx,
+                    &game_options.theme,
+                    &game_options.tile_skin,
+                )
+                .image,
             ),
             batch_highlight_ghost_tile: spritebatch::SpriteBatch::new(
-                TileGraphic::new_ghost_highlight(ctx).image,
+                TileGraphic::new_ghost_highlight(ctx, &game_options.theme, &game_options.tile_skin)
+                    .image,
             ),
             vec_batch_player_piece,
             vec_batch_next_piece,
             game_info_text,
-            pause_text,
-            game_over_text,
+            pause_menu: PauseMenu::new(
+                &game_options.settings,
+                &game_options.theme,
+                (window_width, window_height),
+            ),
+            results: ResultsScreen::new(
+                &game_options.initials,
+                &game_options.theme,
+                (window_width, window_height),
+            ),
+        }
+    }
+
+    // plays a replay back from its recorded events; nobody's inputs count and Escape goes back to the menu
+    pub fn new_playback(
+        ctx: &mut Context,
+        replay: Replay,
+        initials: &str,
+        theme: &Theme,
+        tile_skin: &TileSkin,
+    ) -> Game {
+        let game_options = GameOptions {
+            num_players: replay.num_players,
+            starting_level: replay.starting_level,
+            game_mode: replay.game_mode,
+            vec_controls: vec![(None, false); replay.num_players as usize],
+            vec_profiles: vec![None; replay.num_players as usize],
+            vec_gamepad_controls: vec![
+                GamepadControlScheme::default();
+                replay.num_players as usize
+            ],
+            vec_gamepad_ids: vec![None; replay.num_players as usize],
+            vec_socd: replay.vec_socd.clone(),
+            vec_drop_in_controls: vec![],
+            settings: replay.settings,
+            seed: Some(replay.seed),
+            initials: initials.to_string(),
+            theme: *theme,
+            tile_skin: tile_skin.clone(),
+        };
+        let mut game = Game::new(ctx, &game_options);
+        for (player, das) in game.vec_players.iter_mut().zip(replay.vec_das.iter()) {
+            player.control_scheme = (None, false);
+            player.das_threshold_big = das.0;
+            player.das_threshold_little = das.1;
+            player.das_countdown = das.0;
         }
+        game.vec_gamepad_id_map_to_player.clear();
+        game.num_gamepads_to_initialize = 0;
+        game.replay = replay;
+        game.playback_index = Some(0);
+        game
     }
 
-    pub fn update(&mut self) -> ProgramState {
+    // picks a game back up from `to_saved_game`; the graphics are built fresh around the saved state
+    // and it starts out paused so everyone can get ready (and gamepads get picked up again)
+    pub fn from_saved_game(
+        ctx: &mut Context,
+        saved_game: SavedGame,
+        theme: &Theme,
+        tile_skin: &TileSkin,
+    ) -> Game {
+        let game_options = GameOptions {
+            num_players: saved_game.num_players,
+            starting_level: saved_game.starting_level,
+            game_mode: saved_game.bh.mode,
+            vec_controls: saved_game
+                .vec_players
+                .iter()
+                .map(|player| player.control_scheme.clone())
+                .collect(),
+            vec_profiles: saved_game
+                .vec_players
+                .iter()
+                .map(|player| player.profile.clone())
+                .collect(),
+            vec_gamepad_controls: saved_game
+                .vec_players
+                .iter()
+                .map(|player| player.gamepad_scheme.clone())
+                .collect(),
+            // gamepads are picked up again by pressing a button
+            vec_gamepad_ids: vec![None; saved_game.vec_players.len()],
+            vec_socd: saved_game
+                .vec_players
+                .iter()
+                .map(|player| player.socd)
+                .collect(),
+            vec_drop_in_controls: vec![],
+            settings: saved_game.settings,
+            seed: Some(saved_game.replay.seed),
+            initials: saved_game.initials.clone(),
+            theme: *theme,
+            tile_skin: tile_skin.clone(),
+        };
+        let mut game = Game::new(ctx, &game_options);
+        game.bh = saved_game.bh;
+        game.vec_players = saved_game.vec_players;
+        for (player, shape) in game
+            .vec_players
+            .iter_mut()
+            .zip(saved_game.vec_next_piece.iter())
+        {
+            game.vec_next_piece[player.player_num as usize] = NextPiece::new(*shape);
+            player.redraw_next_piece_flag = true;
+        }
+        game.level = saved_game.level;
+        game.num_cleared_lines = saved_game.num_cleared_lines;
+        game.score = saved_game.score;
+        game.gravity_direction = saved_game.gravity_direction;
+        game.elimination_flag = saved_game.elimination_flag;
+        game.frame_count = saved_game.frame_count;
+        game.vec_drop_in_controls = saved_game.vec_drop_in_controls;
+        game.vec_profile_results = saved_game.vec_profile_results;
+        game.board_width_per_player = saved_game.board_width_per_player;
+        game.rng = saved_game.rng;
+        game.stats = saved_game.stats;
+        game.replay = saved_game.replay;
+        game.game_info_text.fragments_mut()[1].text = format!("{:03}", game.num_cleared_lines);
+        game.game_info_text.fragments_mut()[3].text = format!("{:07}", game.score);
+        game.game_info_text.fragments_mut()[5].text = format!("{:02}", game.level);
+        game.pause_flags = (true, true);
+        game
+    }
+
+    fn to_saved_game(&self) -> SavedGame {
+        SavedGame {
+            version: SAVEGAME_VERSION,
+            bh: self.bh.clone(),
+            num_players: self.num_players,
+            vec_players: self.vec_players.clone(),
+            vec_next_piece: self
+                .vec_next_piece
+                .iter()
+                .take(self.num_players as usize)
+                .map(|next_piece| next_piece.shape)
+                .collect(),
+            level: self.level,
+            starting_level: self.starting_level,
+            num_cleared_lines: self.num_cleared_lines,
+            score: self.score,
+            gravity_direction: self.gravity_direction,
+            elimination_flag: self.elimination_flag,
+            settings: self.settings,
+            frame_count: self.frame_count,
+            vec_drop_in_controls: self.vec_drop_in_controls.clone(),
+            vec_profile_results: self.vec_profile_results.clone(),
+            board_width_per_player: self.board_width_per_player,
+            rng: self.rng.clone(),
+            stats: self.stats.clone(),
+            replay: self.replay.clone(),
+            initials: self.results.get_initials(),
+        }
+    }
+
+    pub fn update(&mut self, ctx: &mut Context) -> ProgramState {
+        // select held without down (which is leaving) restarts; nobody's gamepad counts in a replay
+        let select_held_flag = if self.game_over_flag {
+            self.results_select_held_flag
+        } else {
+            self.playback_index.is_none()
+                && !self.pause_flags.0
+                && self.vec_players.iter().any(|p| {
+                    !p.eliminated && !p.idle && p.input.keydown_select.0 && !p.input.keydown_down.0
+                })
+        };
+        self.select_hold_frames = if select_held_flag {
+            self.select_hold_frames.saturating_add(1)
+        } else {
+            0
+        };
+        if self.keycode_restart_flags.1 || self.select_hold_frames >= RESTART_HOLD_FRAMES {
+            return ProgramState::Restart;
+        }
         if self.game_over_flag {
             if self.game_over_delay == 0 {
                 // GAME OVER LOGIC
-                if self.keycode_escape_flags.1 {
-                    return ProgramState::Menu;
-                }
-                for player in &mut self.vec_players {
-                    // should we quit to main menu?
-                    if player.input.keydown_start.1 {
-                        return ProgramState::Menu;
+                match self.results.update() {
+                    MenuItemTrigger::SaveReplay => {
+                        let status = self
+                            .replay
+                            .save(&self.replay_dir)
+                            .map(|path| path.display().to_string());
+                        match &status {
+                            Ok(path) => println!("[+] Saved replay to {}", path),
+                            Err(e) => println!("[!] Failed to save replay: {}", e),
+                        }
+                        self.results.set_replay_status(status);
                     }
+                    MenuItemTrigger::Restart => return ProgramState::Restart,
+                    MenuItemTrigger::QuitToMenu => return ProgramState::Menu,
+                    _ => {}
                 }
                 self.was_just_pressed_setfalse_all_players();
             } else {
@@ -457,37 +801,64 @@ impl Game {
                 // if the pause flag was just set, reset all inputs to false in case focus was lost or keyboard hardware is acting up somehow or another
                 self.pause_flags.1 = false;
                 for player in &mut self.vec_players {
-                    player.input.reset_all();
+                    player.reset_input();
                 }
+                self.replay
+                    .record(self.frame_count, ReplayEvent::ResetInputs);
+                self.pause_menu.open(&self.vec_players);
             } else {
-                // down arrow key held and escape key results in quit to menu
-                if self.keycode_escape_flags.1 && self.keycode_down_flags.0 {
-                    return ProgramState::Menu;
-                }
-                // this loop is mostly due to gamepad/keyboard controls meshing together weirdly
-                for player in &mut self.vec_players {
-                    // should we quit to main menu? (down and start, but start on keyboard is Escape and not specific to a player, so check if players holding down are using keyboard)
-                    if player.input.keydown_down.0
-                        && (player.input.keydown_start.1
-                            || ((player.control_scheme.0).is_some() && self.keycode_escape_flags.1))
-                    {
-                        return ProgramState::Menu;
-                    }
-                    // should we resume?
-                    if player.input.keydown_start.1 {
-                        self.pause_flags = (false, false);
-                        break;
+                match self.pause_menu.update(&mut self.settings) {
+                    MenuItemTrigger::Resume => self.pause_flags = (false, false),
+                    MenuItemTrigger::Restart => return ProgramState::Restart,
+                    MenuItemTrigger::QuitToMenu => return ProgramState::Menu,
+                    MenuItemTrigger::SaveAndQuit => {
+                        match savegame::save(&self.savegame_dir, &self.to_saved_game()) {
+                            Ok(path) => {
+                                println!("[+] Saved the game to {}", path.display());
+                                return ProgramState::Menu;
+                            }
+                            Err(e) => {
+                                println!("[!] Failed to save the game: {}", e);
+                                self.pause_menu.set_save_failed();
+                            }
+                        }
                     }
+                    _ => {}
                 }
-                // should we resume because of escape press?
-                if self.keycode_escape_flags.1 {
-                    self.pause_flags = (false, false);
+                // settings that can be changed live from the pause menu
+                if self.determine_ghost_tile_locations && !self.settings.ghost_pieces_state {
+                    self.batch_highlight_ghost_tile.clear();
                 }
-                self.was_just_pressed_setfalse_all_players();
+                self.determine_ghost_tile_locations = self.settings.ghost_pieces_state;
+                if self.elimination_flag != self.settings.elimination_state {
+                    self.elimination_flag = self.settings.elimination_state;
+                    self.replay.record(
+                        self.frame_count,
+                        ReplayEvent::Elimination(self.elimination_flag),
+                    );
+                }
+                self.was_just_pressed_setfalse_common();
             }
         } else {
             // GAME LOGIC
+            if self.playback_index.is_some() {
+                self.play_back_events(ctx);
+            }
+            self.frame_count += 1;
+            let mut vec_leaving_players: Vec<u8> = vec![];
             for player in &mut self.vec_players {
+                // eliminated players' lanes are frozen; their inputs are ignored for the rest of the game
+                if player.eliminated || player.idle {
+                    player.input.was_just_pressed_setfalse();
+                    continue;
+                }
+
+                if player.wants_to_leave() {
+                    vec_leaving_players.push(player.player_num);
+                    player.input.was_just_pressed_setfalse();
+                    continue;
+                }
+
                 if !player.spawn_piece_flag
                     && self.bh.get_shape_from_player(player.player_num) == Shapes::None
                 {
@@ -497,6 +868,9 @@ impl Game {
 
                 // piece spawning
                 if player.spawn_piece_flag {
+                    if self.settings.initial_rotation_state {
+                        player.buffer_initial_rotation();
+                    }
                     if player.spawn_delay <= 0 {
                         // (blocked, blocked by some !active tile); if .1, game over sequence, if .0 and !.1, only blocked by other players, wait until they move, then carry on
                         let blocked: (bool, bool) = self.bh.attempt_piece_spawn(
@@ -506,39 +880,38 @@ impl Game {
                         );
                         if blocked.0 {
                             if blocked.1 {
-                                self.game_over_flag = true;
+                                if self.elimination_flag {
+                                    // only this player is knocked out; the game ends once everyone is
+                                    player.eliminated = true;
+                                    player.survival_frames = self.frame_count;
+                                    self.vec_next_piece[player.player_num as usize] =
+                                        NextPiece::new(Shapes::None);
+                                    player.redraw_next_piece_flag = true;
+                                } else {
+                                    self.game_over_flag = true;
+                                }
                             }
                             continue;
                         } else {
                             self.bh.playerify_piece(player.player_num);
+                            if self.settings.initial_rotation_state {
+                                if let Some(rotation) = player.take_initial_rotation() {
+                                    // if it doesn't fit rotated where it spawned, it just comes in unrotated
+                                    self.bh.attempt_piece_movement(rotation, player.player_num);
+                                }
+                            }
                             player.spawn_delay = SPAWN_DELAY;
                             player.spawn_piece_flag = false;
                             // set das_countdown to the smaller das value if input left or right is pressed as the piece spawns in
                             if player.input.keydown_left.0 || player.input.keydown_right.0 {
-                                player.das_countdown = DAS_THRESHOLD_LITTLE;
+                                player.das_countdown = player.das_threshold_little;
                             }
                             // set next piece to random; reroll once if it chooses the same piece as it just was
-                            let mut rand: u8;
-                            loop {
-                                rand = random::<u8>();
-                                if rand < 252 {
-                                    break;
-                                }
-                            }
-                            let random_shape =
-                                Shapes::try_from(rand % 7).expect("Unable to get random piece");
+                            let random_shape = Shapes::random(&mut self.rng);
                             if self.bh.get_shape_from_player(player.player_num) != random_shape {
                                 player.next_piece_shape = random_shape;
                             } else {
-                                let mut rand: u8;
-                                loop {
-                                    rand = random::<u8>();
-                                    if rand < 252 {
-                                        break;
-                                    }
-                                }
-                                player.next_piece_shape =
-                                    Shapes::try_from(rand % 7).expect("Unable to get random piece");
+                                player.next_piece_shape = Shapes::random(&mut self.rng);
                             }
                             self.vec_next_piece[player.player_num as usize] =
                                 NextPiece::new(player.next_piece_shape);
@@ -585,7 +958,7 @@ impl Game {
                             player.player_num,
                         )
                         .0;
-                    player.das_countdown = DAS_THRESHOLD_BIG;
+                    player.das_countdown = player.das_threshold_big;
                 }
                 if player.input.keydown_right.1 {
                     // if it didn't move on the initial input, set waiting_to_shift to true
@@ -599,7 +972,7 @@ impl Game {
                             player.player_num,
                         )
                         .0;
-                    player.das_countdown = DAS_THRESHOLD_BIG;
+                    player.das_countdown = player.das_threshold_big;
                 }
                 if (player.input.keydown_left.0 && !player.input.keydown_left.1)
                     || (player.input.keydown_right.0 && !player.input.keydown_right.1)
@@ -624,7 +997,7 @@ impl Game {
                         {
                             // if the piece moved, set variables accordingly
                             player.das_countdown =
-                                std::cmp::max(DAS_THRESHOLD_LITTLE, player.das_countdown);
+                                std::cmp::max(player.das_threshold_little, player.das_countdown);
                             player.waiting_to_shift = false;
                         } else {
                             // failed to move piece, so we are waiting to shift the piece
@@ -647,6 +1020,7 @@ impl Game {
                     || (player.input.keydown_down.0 && player.force_fall_countdown == 0)
                     || player.fall_countdown == 0
                 {
+                    let shape_before_moving = self.bh.get_shape_from_player(player.player_num);
                     let (moved_flag, caused_full_line_flag): (bool, bool) =
                         self.bh.attempt_piece_movement(
                             Movement::try_from(
@@ -657,6 +1031,8 @@ impl Game {
                         );
                     // if the piece got locked, piece.shape gets set to Shapes::None, so set the spawn piece flag
                     if self.bh.get_shape_from_player(player.player_num) == Shapes::None {
+                        self.stats.vec_player_stats[player.player_num as usize]
+                            .add_piece(shape_before_moving);
                         player.spawn_piece_flag = true;
                         player.fall_countdown = if self.level < 30 {
                             self.bh.get_fall_delay_from_level(self.level)
@@ -684,7 +1060,8 @@ impl Game {
                     player.fall_countdown -= 1;
                 }
 
-                if player.input.keydown_start.1 {
+                // a replay keeps going through the pauses it recorded
+                if player.input.keydown_start.1 && self.playback_index.is_none() {
                     self.pause_flags = (true, true);
                 }
 
@@ -692,14 +1069,32 @@ impl Game {
                 player.input.was_just_pressed_setfalse();
             }
 
+            for player_num in vec_leaving_players {
+                self.player_leave(player_num);
+            }
+
+            // the game keeps going as long as someone is still playing
+            if !self.game_over_flag && self.vec_players.iter().all(|p| p.eliminated || p.idle) {
+                self.game_over_flag = true;
+            }
+            if self.game_over_flag {
+                self.build_results();
+            }
+
             // update controls so that the logic realizes next frame that the button inputs made were run through the logic
-            if self.keycode_escape_flags.1 {
+            if self.keycode_pause_flags.1 {
+                if self.playback_index.is_some() {
+                    return ProgramState::Menu;
+                }
                 self.pause_flags = (true, true);
             }
             self.was_just_pressed_setfalse_common();
 
             // attempt to line clear (go through the vector of FullLine's and decrement clear_delay if > 0, clear and return (lines_cleared, score) for <= 0)
             let (returned_lines, returned_score) = self.bh.attempt_clear(self.level);
+            for (player_num, lines, score) in self.bh.take_recent_clears() {
+                self.stats.vec_player_stats[player_num as usize].add_clear(lines, score);
+            }
             if returned_lines > 0 {
                 self.num_cleared_lines += returned_lines as u16;
                 self.game_info_text.fragments_mut()[1].text =
@@ -726,116 +1121,544 @@ impl Game {
         ProgramState::Game
     }
 
-    fn was_just_pressed_setfalse_all_players(&mut self) {
-        for player in self.vec_players.iter_mut() {
-            player.input.was_just_pressed_setfalse();
+    // everyone still playing at the end survived the whole game
+    fn build_results(&mut self) {
+        let mut vec_player_results: Vec<PlayerResult> = self
+            .vec_players
+            .iter()
+            .map(|p| PlayerResult {
+                player_num: p.player_num,
+                survival_frames: if p.eliminated || p.idle {
+                    p.survival_frames
+                } else {
+                    self.frame_count
+                },
+            })
+            .collect();
+        self.results.set_results(
+            self.bh.mode,
+            &self.stats,
+            self.frame_count,
+            &mut vec_player_results,
+            self.elimination_flag,
+        );
+    }
+
+    // (profile name, stats, frames played) of everyone that played with a profile, for their lifetime stats
+    pub fn profile_results(&self) -> Vec<(String, PlayerStats, u32)> {
+        if !self.game_over_flag {
+            return vec![];
         }
-        self.was_just_pressed_setfalse_common();
+        let mut vec_profile_results = self.vec_profile_results.clone();
+        for player in self.vec_players.iter().filter(|p| !p.idle) {
+            if let Some(name) = player.profile_name() {
+                let end_frame = if player.eliminated {
+                    player.survival_frames
+                } else {
+                    self.frame_count
+                };
+                vec_profile_results.push((
+                    name.to_string(),
+                    self.stats.vec_player_stats[player.player_num as usize]
+                        .since(&player.stats_baseline),
+                    end_frame - player.join_frame,
+                ));
+            }
+        }
+        vec_profile_results
     }
 
-    fn was_just_pressed_setfalse_common(&mut self) {
-        self.keycode_down_flags.1 = false;
-        self.keycode_escape_flags.1 = false;
+    pub fn get_initials(&self) -> String {
+        self.results.get_initials()
     }
 
-    pub fn key_down_event(&mut self, keycode: KeyCode, repeat: bool) {
-        if !repeat {
-            if keycode == KeyCode::Escape {
-                self.keycode_escape_flags = (true, true);
-                return;
-            } else if keycode == KeyCode::Down {
-                self.keycode_down_flags = (true, true);
-            }
-            for player in &mut self.vec_players {
-                if player.update_input_keydown(keycode) {
-                    return;
-                }
+    // only finished games that got somewhere count for high scores, and a replay already got its chance
+    pub fn high_score_entry(&self) -> Option<(HighScoreKey, HighScoreEntry)> {
+        if !self.game_over_flag
+            || (self.score == 0 && self.num_cleared_lines == 0)
+            || self.playback_index.is_some()
+        {
+            return None;
+        }
+        let mut vec_names: Vec<String> = vec![];
+        for (name, _, _) in self.profile_results() {
+            if !vec_names.contains(&name) {
+                vec_names.push(name);
             }
         }
+        Some((
+            HighScoreKey {
+                game_mode: self.bh.mode,
+                starting_level: self.starting_level,
+                // players can drop in and out, so it's how many started
+                num_players: self.replay.num_players,
+            },
+            HighScoreEntry::new(
+                self.results.get_initials(),
+                vec_names,
+                self.score,
+                self.num_cleared_lines,
+                self.level,
+                self.frame_count,
+            ),
+        ))
     }
 
-    pub fn key_up_event(&mut self, keycode: KeyCode) {
-        if keycode == KeyCode::Escape {
-            self.keycode_escape_flags = (false, false);
-            return;
-        } else if keycode == KeyCode::Down {
-            self.keycode_down_flags = (false, false);
-            return;
+    // changes a player's inputs and keeps it for the replay (and the input counts) if anything actually changed
+    fn apply_player_input(&mut self, player_num: u8, input_event: InputEvent) {
+        if self.vec_players[player_num as usize].apply_input_event(input_event) {
+            self.stats.vec_player_stats[player_num as usize].add_input(input_event);
+            self.replay.record(
+                self.frame_count,
+                ReplayEvent::Input(player_num, input_event),
+            );
         }
-        for player in &mut self.vec_players {
-            if player.update_input_keyup(keycode) {
-                return;
+    }
+
+    // everything the replay recorded before this frame happens now, in the order it happened the first time
+    fn play_back_events(&mut self, ctx: &mut Context) {
+        while let Some(idx) = self.playback_index {
+            let event = match self.replay.vec_events.get(idx) {
+                Some(&(frame, event)) if frame <= self.frame_count => event,
+                _ => break,
+            };
+            self.playback_index = Some(idx + 1);
+            match event {
+                ReplayEvent::Input(player_num, input_event) => {
+                    if let Some(player) = self.vec_players.get_mut(player_num as usize) {
+                        if player.apply_input_event(input_event) {
+                            self.stats.vec_player_stats[player_num as usize].add_input(input_event);
+                        }
+                    }
+                }
+                ReplayEvent::ResetInputs => {
+                    for player in self.vec_players.iter_mut() {
+                        player.reset_input();
+                    }
+                }
+                ReplayEvent::Join(das_big, das_little, socd) => {
+                    let player_num = self
+                        .vec_players
+                        .iter()
+                        .position(|p| p.idle)
+                        .unwrap_or(self.vec_players.len());
+                    if self.player_join(ctx, (None, false), None, socd, None) {
+                        let player = &mut self.vec_players[player_num];
+                        player.das_threshold_big = das_big;
+                        player.das_threshold_little = das_little;
+                        player.das_countdown = das_big;
+                    }
+                }
+                ReplayEvent::Elimination(on) => {
+                    self.elimination_flag = on;
+                    self.settings.elimination_state = on;
+                }
             }
         }
     }
 
-    pub fn gamepad_button_down_event(&mut self, btn: Button, id: GamepadId) {
+    // which player a gamepad belongs to; an unknown gamepad takes the first open gamepad slot if `assign`
+    fn player_from_gamepad(&mut self, id: GamepadId, assign: bool) -> Option<u8> {
         for map in self.vec_gamepad_id_map_to_player.iter() {
             if Some(id) == map.0 {
-                self.vec_players[map.1 as usize].update_input_buttondown(btn);
-                return;
+                return Some(map.1);
             }
         }
-        if self.num_gamepads_to_initialize > 0 {
+        if assign && self.num_gamepads_to_initialize > 0 {
             for map in self.vec_gamepad_id_map_to_player.iter_mut() {
                 if None == map.0 {
                     map.0 = Some(id);
-                    self.vec_players[map.1 as usize].update_input_buttondown(btn);
+                    let player_num = map.1;
                     if self.vec_gamepad_id_map_to_player.len()
                         == self.vec_gamepad_id_map_to_player.capacity()
                     {
                         self.num_gamepads_to_initialize -= 1;
                     }
-                    return;
+                    return Some(player_num);
                 }
             }
         }
+
+        None
     }
 
-    pub fn gamepad_button_up_event(&mut self, btn: Button, id: GamepadId) {
-        for map in self.vec_gamepad_id_map_to_player.iter() {
-            if Some(id) == map.0 {
-                self.vec_players[map.1 as usize].update_input_buttonup(btn);
-                return;
+    // a gamepad nobody has yet goes by the default stick settings
+    fn gamepad_sticks(&self, id: GamepadId) -> StickSettings {
+        self.vec_gamepad_id_map_to_player
+            .iter()
+            .find(|map| map.0 == Some(id))
+            .map_or_else(StickSettings::default, |map| {
+                self.vec_players[map.1 as usize].gamepad_scheme.sticks
+            })
+    }
+
+    // the lane stays on the board (as does whatever the player already stacked there) so someone else can take it over
+    fn player_leave(&mut self, player_num: u8) {
+        let player = &mut self.vec_players[player_num as usize];
+        player.idle = true;
+        player.survival_frames = self.frame_count;
+        player.spawn_piece_flag = false;
+        player.reset_input();
+        let profile = player.profile.take();
+        if let Some(profile) = &profile {
+            self.vec_profile_results.push((
+                profile.name.clone(),
+                self.stats.vec_player_stats[player_num as usize].since(&player.stats_baseline),
+                player.survival_frames - player.join_frame,
+            ));
+        }
+        if let Some(k_ctrl_scheme) = player.control_scheme.0.take() {
+            self.vec_drop_in_controls
+                .push((k_ctrl_scheme, profile, player.socd));
+        }
+        player.control_scheme = (None, false);
+        self.vec_gamepad_id_map_to_player
+            .retain(|map| map.1 != player_num);
+        self.bh.remove_active_piece(player_num);
+        self.vec_next_piece[player_num as usize] = NextPiece::new(Shapes::None);
+        player.redraw_next_piece_flag = true;
+        println!("[+] Player {} left the game", player_num + 1);
+    }
+
+    // takes over the first idle lane, otherwise the board widens by one lane for the new player
+    fn player_join(
+        &mut self,
+        ctx: &mut Context,
+        control_scheme: (Option<KeyboardControlScheme>, bool),
+        profile: Option<Profile>,
+        socd: SocdPolicy,
+        gamepad_id: Option<GamepadId>,
+    ) -> bool {
+        if self.bh.mode != GameMode::Classic || self.game_over_flag || self.pause_flags.0 {
+            return false;
+        }
+        let player_num: u8 = match self.vec_players.iter().position(|p| p.idle) {
+            Some(idx) => {
+                let spawn_column = self.vec_players[idx].spawn_column;
+                self.vec_players[idx] = Player::new(
+                    idx as u8,
+                    control_scheme,
+                    profile,
+                    spawn_column,
+                    &mut self.rng,
+                );
+                self.vec_players[idx].stats_baseline = self.stats.vec_player_stats[idx].clone();
+                // the lane takes on the color of whoever took it over
+                self.recolor_lane(ctx, idx as u8);
+                idx as u8
+            }
+            None => {
+                if self.num_players >= MAX_NUM_PLAYERS {
+                    return false;
+                }
+                let old_width = self.bh.get_width();
+                self.bh.add_player_lane(self.board_width_per_player);
+                let new_width = self.bh.get_width();
+                let height = self.bh.get_height();
+                for x in old_width..new_width {
+                    for y in 0..height {
+                        let empty_tile = graphics::DrawParam::new().dest(Point2::from_slice(&[
+                            x as f32 * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as f32,
+                            y as f32 * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as f32,
+                        ]));
+                        self.batch_empty_tile.add(empty_tile);
+                    }
+                }
+                let player_num = self.num_players;
+                self.num_players += 1;
+                self.stats.add_player();
+                let spawn_columns: Vec<BoardPos> = self.bh.get_spawn_columns();
+                for player in self.vec_players.iter_mut() {
+                    player.spawn_column = spawn_columns[player.player_num as usize];
+                    // the single player colors by shape, so everything gets redrawn by player color now
+                    player.redraw_next_piece_flag = true;
+                }
+                self.vec_players.push(Player::new(
+                    player_num,
+                    control_scheme,
+                    profile,
+                    spawn_columns[player_num as usize],
+                    &mut self.rng,
+                ));
+                if self.vec_next_piece.len() <= player_num as usize {
+                    let pattern = self.settings.tile_pattern(player_num);
+                    self.vec_next_piece.push(NextPiece::new(Shapes::None));
+                    self.vec_batch_player_piece
+                        .push(spritebatch::SpriteBatch::new(
+                            TileGraphic::new_player(
+                                ctx,
+                                player_num,
+                                pattern,
+                                &self.theme,
+                                &self.tile_skin,
+                            )
+                            .image,
+                        ));
+                    self.vec_batch_next_piece
+                        .push(spritebatch::SpriteBatch::new(
+                            TileGraphic::new_player(
+                                ctx,
+                                player_num,
+                                pattern,
+                                &self.theme,
+                                &self.tile_skin,
+                            )
+                            .image,
+                        ));
+                }
+                self.recolor_lane(ctx, player_num);
+                // the first player's profile color only shows once it's not single player anymore
+                if self.num_players == 2 {
+                    self.recolor_lane(ctx, 0);
+                }
+                let (window_width, window_height) = graphics::size(ctx);
+                self.resize_event(window_width, window_height);
+                player_num
             }
+        };
+        if gamepad_id.is_some() {
+            self.vec_gamepad_id_map_to_player
+                .push((gamepad_id, player_num));
         }
-        if self.num_gamepads_to_initialize > 0 {
+        let player = &mut self.vec_players[player_num as usize];
+        player.join_frame = self.frame_count;
+        player.socd = socd;
+        if self.playback_index.is_none() {
+            self.replay.record(
+                self.frame_count,
+                ReplayEvent::Join(
+                    player.das_threshold_big,
+                    player.das_threshold_little,
+                    player.socd,
+                ),
+            );
+        }
+        println!("[+] Player {} joined the game", player_num + 1);
+        true
+    }
+
+    fn recolor_lane(&mut self, ctx: &mut Context, player_num: u8) {
+        if self.num_players < 2 {
+            return;
+        }
+        let color = lane_color(
+            player_num,
+            self.vec_players[player_num as usize].profile.as_ref(),
+            self.num_players,
+        );
+        let pattern = self.settings.tile_pattern(player_num);
+        self.vec_batch_player_piece[player_num as usize] = spritebatch::SpriteBatch::new(
+            TileGraphic::new_player(ctx, color, pattern, &self.theme, &self.tile_skin).image,
+        );
+        self.vec_batch_next_piece[player_num as usize] = spritebatch::SpriteBatch::new(
+            TileGraphic::new_player(ctx, color, pattern, &self.theme, &self.tile_skin).image,
+        );
+        self.vec_players[player_num as usize].redraw_next_piece_flag = true;
+    }
+
+    fn was_just_pressed_setfalse_all_players(&mut self) {
+        for player in self.vec_players.iter_mut() {
+            player.input.was_just_pressed_setfalse();
+        }
+        self.was_just_pressed_setfalse_common();
+    }
+
+    fn was_just_pressed_setfalse_common(&mut self) {
+        self.keycode_pause_flags.1 = false;
+        self.keycode_restart_flags.1 = false;
+    }
+
+    // gamepads that were already assigned to players in the last game stay with those players
+    pub fn keep_gamepad_mappings(&mut self, last_game: &Game) {
+        for last_map in last_game.vec_gamepad_id_map_to_player.iter() {
+            if last_map.0.is_none() {
+                continue;
+            }
             for map in self.vec_gamepad_id_map_to_player.iter_mut() {
-                if None == map.0 {
-                    map.0 = Some(id);
-                    self.vec_players[map.1 as usize].update_input_buttonup(btn);
-                    if self.vec_gamepad_id_map_to_player.len()
-                        == self.vec_gamepad_id_map_to_player.capacity()
-                    {
-                        self.num_gamepads_to_initialize -= 1;
-                    }
-                    return;
+                if map.1 == last_map.1 && map.0.is_none() {
+                    map.0 = last_map.0;
+                    self.num_gamepads_to_initialize -= 1;
+                    break;
                 }
             }
         }
     }
 
-    pub fn gamepad_axis_event(&mut self, axis: Axis, value: f32, id: GamepadId) {
-        for map in self.vec_gamepad_id_map_to_player.iter() {
-            if Some(id) == map.0 {
-                self.vec_players[map.1 as usize].update_input_axis(axis, value);
+    // a player who bound the restart or pause key gets to keep it
+    fn is_player_key(&self, keycode: KeyCode) -> bool {
+        self.playback_index.is_none()
+            && self.vec_players.iter().any(|p| {
+                p.control_scheme
+                    .0
+                    .as_ref()
+                    .is_some_and(|k| k.movement_from_keycode(keycode).is_some())
+            })
+    }
+
+    pub fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: KeyCode,
+        repeat: bool,
+        menu_keys: &MenuKeys,
+    ) {
+        if !repeat {
+            if keycode == menu_keys.restart && !self.is_player_key(keycode) {
+                self.keycode_restart_flags = (true, true);
                 return;
             }
-        }
-        if self.num_gamepads_to_initialize > 0
-            && !(-DETECT_GAMEPAD_AXIS_THRESHOLD..=DETECT_GAMEPAD_AXIS_THRESHOLD).contains(&value)
-        {
-            for map in self.vec_gamepad_id_map_to_player.iter_mut() {
-                if None == map.0 {
-                    map.0 = Some(id);
-                    self.vec_players[map.1 as usize].update_input_axis(axis, value);
-                    if self.vec_gamepad_id_map_to_player.len()
-                        == self.vec_gamepad_id_map_to_player.capacity()
-                    {
-                        self.num_gamepads_to_initialize -= 1;
+            if self.game_over_flag {
+                self.results.key_down_event(keycode, menu_keys);
+                return;
+            }
+            if self.pause_flags.0 {
+                self.pause_menu
+                    .input
+                    .update_menu_keydown(keycode, menu_keys);
+                return;
+            }
+            if keycode == menu_keys.pause && !self.is_player_key(keycode) {
+                self.keycode_pause_flags = (true, true);
+                return;
+            }
+            if self.playback_index.is_some() {
+                return;
+            }
+            if let Some((player_num, input_event)) = self.vec_players.iter_mut().find_map(|p| {
+                p.input_event_from_keycode(keycode, true)
+                    .map(|input_event| (p.player_num, input_event))
+            }) {
+                // a player's own restart key works like the global one, as long as they're still playing
+                if input_event == InputEvent::Press(Movement::Restart) {
+                    let player = &self.vec_players[player_num as usize];
+                    if !player.eliminated && !player.idle {
+                        self.keycode_restart_flags = (true, true);
                     }
                     return;
                 }
+                self.apply_player_input(player_num, input_event);
+                return;
+            }
+            // a configured keyboard scheme that isn't playing drops in with its clockwise rotation while holding its Down,
+            // so a stray key doesn't widen the board for everyone
+            if let Some(idx) = self
+                .vec_drop_in_controls
+                .iter()
+                .position(|(k_ctrl_scheme, _, _)| {
+                    k_ctrl_scheme.movement_from_keycode(keycode) == Some(Movement::RotateCw)
+                        && k_ctrl_scheme
+                            .keycodes_from_movement(Movement::Down)
+                            .iter()
+                            .any(|k| keyboard::is_key_pressed(ctx, *k))
+                })
+            {
+                let k_ctrl_scheme = self.vec_drop_in_controls[idx].0.copy();
+                let profile = self.vec_drop_in_controls[idx].1.clone();
+                let socd = self.vec_drop_in_controls[idx].2;
+                if self.player_join(ctx, (Some(k_ctrl_scheme), false), profile, socd, None) {
+                    self.vec_drop_in_controls.remove(idx);
+                }
+            }
+        }
+    }
+
+    pub fn key_up_event(&mut self, keycode: KeyCode, menu_keys: &MenuKeys) {
+        self.pause_menu.input.update_menu_keyup(keycode, menu_keys);
+        self.results.input.update_menu_keyup(keycode, menu_keys);
+        if keycode == menu_keys.pause {
+            self.keycode_pause_flags = (false, false);
+        }
+        if keycode == menu_keys.restart {
+            self.keycode_restart_flags = (false, false);
+        }
+        if self.playback_index.is_some() {
+            return;
+        }
+        if let Some((player_num, input_event)) = self.vec_players.iter_mut().find_map(|p| {
+            p.input_event_from_keycode(keycode, false)
+                .map(|input_event| (p.player_num, input_event))
+        }) {
+            self.apply_player_input(player_num, input_event);
+        }
+    }
+
+    pub fn gamepad_button_down_event(&mut self, ctx: &mut Context, btn: Button, id: GamepadId) {
+        if self.game_over_flag {
+            // holding select goes again like it does in game
+            if btn == Button::Select {
+                self.results_select_held_flag = true;
+            } else {
+                self.results.input.update_menu_buttondown(btn);
+            }
+            return;
+        }
+        if self.pause_flags.0 {
+            // any gamepad can drive the pause menu
+            self.pause_menu.input.update_menu_buttondown(btn);
+            return;
+        }
+        if self.playback_index.is_some() {
+            return;
+        }
+        // an unknown gamepad pressing start joins once every gamepad slot from the menu is taken
+        if btn == Button::Start
+            && !self
+                .vec_gamepad_id_map_to_player
+                .iter()
+                .any(|map| map.0 == Some(id) || map.0.is_none())
+        {
+            self.player_join(ctx, (None, true), None, SocdPolicy::default(), Some(id));
+            return;
+        }
+        if let Some(player_num) = self.player_from_gamepad(id, true) {
+            if let Some(input_event) =
+                self.vec_players[player_num as usize].input_event_from_button(btn, true)
+            {
+                self.apply_player_input(player_num, input_event);
+            }
+        }
+    }
+
+    pub fn gamepad_button_up_event(&mut self, btn: Button, id: GamepadId) {
+        self.pause_menu.input.update_menu_buttonup(btn);
+        self.results.input.update_menu_buttonup(btn);
+        if btn == Button::Select {
+            self.results_select_held_flag = false;
+        }
+        if self.playback_index.is_some() {
+            return;
+        }
+        if let Some(player_num) = self.player_from_gamepad(id, true) {
+            if let Some(input_event) =
+                self.vec_players[player_num as usize].input_event_from_button(btn, false)
+            {
+                self.apply_player_input(player_num, input_event);
+            }
+        }
+    }
+
+    pub fn gamepad_axis_event(&mut self, axis: Axis, value: f32, id: GamepadId) {
+        if self.game_over_flag {
+            let sticks = self.gamepad_sticks(id);
+            self.results.input.update_menu_axis(axis, value, &sticks);
+            return;
+        }
+        if self.pause_flags.0 {
+            let sticks = self.gamepad_sticks(id);
+            self.pause_menu.input.update_menu_axis(axis, value, &sticks);
+            return;
+        }
+        if self.playback_index.is_some() {
+            return;
+        }
+        // a gamepad nobody has yet goes by the default stick settings until it's someone's
+        let threshold = StickSettings::default().press_threshold();
+        let assign = !(-threshold..=threshold).contains(&value);
+        if let Some(player_num) = self.player_from_gamepad(id, assign) {
+            for input_event in
+                self.vec_players[player_num as usize].input_events_from_axis(axis, value)
+            {
+                self.apply_player_input(player_num, input_event);
             }
         }
     }
@@ -855,21 +1678,10 @@ impl Game {
         let (window_width, window_height) = graphics::size(ctx);
         if self.game_over_flag && self.game_over_delay == 0 {
             // DRAW GAME OVER
-            self.draw_text(
-                ctx,
-                &self.game_over_text,
-                0.4,
-                &(window_width, window_height),
-            );
-            self.draw_text(
-                ctx,
-                &self.game_info_text,
-                0.55,
-                &(window_width, window_height),
-            );
+            self.results.draw(ctx);
         } else if self.pause_flags.0 {
             // DRAW PAUSE
-            self.draw_text(ctx, &self.pause_text, 0.4, &(window_width, window_height));
+            self.pause_menu.draw(ctx);
         } else {
             // DRAW GAME
 
@@ -1187,6 +1999,31 @@ impl Game {
                     .unwrap();
                 }
             }
+            // profile names, in the row between the next pieces and the board
+            let tile_width = scaled_tile_size * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as f32;
+            for player in self.vec_players.iter().filter(|p| !p.idle) {
+                if let Some(name) = player.profile_name() {
+                    let name_text = Text::new(
+                        TextFragment::new(name)
+                            .color(graphics::Color::WHITE)
+                            .scale(PxScale::from(self.tile_size * PROFILE_NAME_SCALE)),
+                    );
+                    let name_width = name_text.dimensions(ctx).w;
+                    graphics::draw(
+                        ctx,
+                        &name_text,
+                        DrawParam::new().dest(Point2::from_slice(&[
+                            board_top_left_corner + player.spawn_column as f32 * tile_width
+                                - name_width / 2.0,
+                            (NON_BOARD_SPACE_U - 1) as f32 * self.tile_size,
+                        ])),
+                    )
+                    .unwrap();
+                }
+            }
+            if self.settings.input_overlay_state {
+                self.draw_input_overlay(ctx, board_top_left_corner, tile_width, window_height);
+            }
             // score text; TODO: perhaps make a separate function for something based on the bottom,
             // or just figure out how to do this better so we don't divide out by the window_height
             self.draw_text(
@@ -1209,6 +2046,92 @@ impl Game {
         }
     }
 
+    // under each spawn column, directions and start over the rotations (and board rotations in rotatris),
+    // lit while held and with how many times each was pressed
+    fn draw_input_overlay(
+        &self,
+        ctx: &mut Context,
+        board_top_left_corner: f32,
+        tile_width: f32,
+        window_height: f32,
+    ) {
+        let num_buttons = if self.bh.classic.is_some() {
+            NUM_COUNTED_INPUTS - 2
+        } else {
+            NUM_COUNTED_INPUTS
+        };
+        let button_size = tile_width * INPUT_OVERLAY_BUTTON_SIZE;
+        let button_step = tile_width * (INPUT_OVERLAY_BUTTON_SIZE + INPUT_OVERLAY_BUTTON_SPACING);
+        let row_width = button_step * INPUT_OVERLAY_BUTTONS_PER_ROW as f32
+            - tile_width * INPUT_OVERLAY_BUTTON_SPACING;
+        // in the space under the board, below the score text
+        let top = window_height - (NON_BOARD_SPACE_D as f32 - 0.5) * self.tile_size;
+        let mut mesh_builder = graphics::MeshBuilder::new();
+        let mut vec_count_texts: Vec<(Text, f32, f32)> = vec![];
+        for player in self.vec_players.iter().filter(|p| !p.idle) {
+            let input = &player.input;
+            // in the same order as the input counts
+            let arr_held: [bool; NUM_COUNTED_INPUTS] = [
+                input.keydown_left.0,
+                input.keydown_down.0,
+                input.keydown_right.0,
+                input.keydown_start.0,
+                input.keydown_rotate_ccw.0,
+                input.keydown_rotate_cw.0,
+                input.keydown_board_ccw.0,
+                input.keydown_board_cw.0,
+            ];
+            let input_counts =
+                &self.stats.vec_player_stats[player.player_num as usize].input_counts;
+            let left =
+                board_top_left_corner + player.spawn_column as f32 * tile_width - row_width / 2.0;
+            for index in 0..num_buttons {
+                let x = left + (index % INPUT_OVERLAY_BUTTONS_PER_ROW) as f32 * button_step;
+                let y = top + (index / INPUT_OVERLAY_BUTTONS_PER_ROW) as f32 * button_step;
+                let (fill_color, text_color) = if arr_held[index] {
+                    (graphics::Color::WHITE, graphics::Color::BLACK)
+                } else {
+                    (INPUT_OVERLAY_UNLIT, graphics::Color::WHITE)
+                };
+                mesh_builder
+                    .rectangle(
+                        graphics::DrawMode::fill(),
+                        graphics::Rect {
+                            x,
+                            y,
+                            w: button_size,
+                            h: button_size,
+                        },
+                        fill_color,
+                    )
+                    .unwrap();
+                let count_text = Text::new(
+                    TextFragment::new(input_counts[index].to_string())
+                        .color(text_color)
+                        .scale(PxScale::from(button_size / 2.0)),
+                );
+                vec_count_texts.push((count_text, x + button_size / 2.0, y + button_size / 2.0));
+            }
+        }
+        if vec_count_texts.is_empty() {
+            return;
+        }
+        let buttons_mesh = mesh_builder.build(ctx).unwrap();
+        graphics::draw(ctx, &buttons_mesh, DrawParam::new()).unwrap();
+        for (count_text, center_x, center_y) in vec_count_texts.iter() {
+            let count_text_dimensions = count_text.dimensions(ctx);
+            graphics::draw(
+                ctx,
+                count_text,
+                DrawParam::new().dest(Point2::from_slice(&[
+                    center_x - count_text_dimensions.w / 2.0,
+                    center_y - count_text_dimensions.h / 2.0,
+                ])),
+            )
+            .unwrap();
+        }
+    }
+
     fn draw_text(
         &self,
         ctx: &mut Context,
@@ -1229,6 +2152,8 @@ impl Game {
     }
 
     pub fn resize_event(&mut self, width: f32, height: f32) {
+        self.pause_menu.resize_event(height);
+        self.results.resize_event(height);
         self.tile_size = TileGraphic::get_size(
             width,
             height,
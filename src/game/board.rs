@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::game::piece::{Piece, Shapes};
 use crate::game::tile::Tile;
 use crate::game::GameMode;
@@ -17,7 +19,7 @@ static BH_WRONG_MODE: &str = "[!] BoardHandler has wrong GameMode setup";
 static BH_MODE_NONE: &str = "[!] BoardHandler has GameMode None";
 
 #[repr(u8)]
-#[derive(Copy, Clone, Eq, PartialEq)]
+#[derive(Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Gravity {
     Down,
     Left,
@@ -51,6 +53,7 @@ impl From<Movement> for Gravity {
 }
 
 // abstract the board and the possible gamemodes into one struct
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BoardHandler {
     pub mode: GameMode,
     pub classic: Option<BoardClassic>,
@@ -317,6 +320,56 @@ impl BoardHandler {
         }
     }
 
+    pub fn take_recent_clears(&mut self) -> Vec<(u8, BoardDim, u32)> {
+        match self.mode {
+            GameMode::None => unreachable!("{}", BH_MODE_NONE),
+            GameMode::Classic => std::mem::take(
+                &mut self
+                    .classic
+                    .as_mut()
+                    .expect(BH_WRONG_MODE)
+                    .vec_recent_clears,
+            ),
+            GameMode::Rotatris => std::mem::take(
+                &mut self
+                    .rotatris
+                    .as_mut()
+                    .expect(BH_WRONG_MODE)
+                    .vec_recent_clears,
+            ),
+        }
+    }
+
+    pub fn add_player_lane(&mut self, lane_width: BoardDim) {
+        match self.mode {
+            GameMode::None => unreachable!("{}", BH_MODE_NONE),
+            GameMode::Classic => self
+                .classic
+                .as_mut()
+                .expect(BH_WRONG_MODE)
+                .add_player_lane(lane_width),
+            GameMode::Rotatris => {
+                println!("[!] `add_player_lane` called but mode is GameMode::Rotatris")
+            }
+        }
+    }
+
+    pub fn remove_active_piece(&mut self, player: u8) {
+        match self.mode {
+            GameMode::None => unreachable!("{}", BH_MODE_NONE),
+            GameMode::Classic => self
+                .classic
+                .as_mut()
+                .expect(BH_WRONG_MODE)
+                .remove_active_piece(player),
+            GameMode::Rotatris => self
+                .rotatris
+                .as_mut()
+                .expect(BH_WRONG_MODE)
+                .remove_active_piece(player),
+        }
+    }
+
     pub fn playerify_piece(&mut self, player: u8) {
         match self.mode {
             GameMode::None => unreachable!("{}", BH_MODE_NONE),
@@ -338,6 +391,7 @@ impl BoardHandler {
 // [(0, 0)][(0, 1)]
 // [(1, 0)][(1, 1)]
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BoardClassic {
     pub width: BoardDim,
     pub height: BoardDim,
@@ -346,6 +400,8 @@ pub struct BoardClassic {
     pub matrix: Vec<Vec<Tile>>,
     pub vec_active_piece: Vec<Piece>,
     pub vec_full_lines: Vec<FullLine>,
+    // (player, lines cleared together, score) for each clear since the last `take_recent_clears`
+    pub vec_recent_clears: Vec<(u8, BoardDim, u32)>,
 }
 
 impl BoardClassic {
@@ -381,6 +437,7 @@ impl BoardClassic {
             matrix,
             vec_active_piece,
             vec_full_lines: vec![],
+            vec_recent_clears: vec![],
         }
     }
 
@@ -418,6 +475,23 @@ impl BoardClassic {
         }
     }
 
+    // widens the board on the right side and gives the new lane's player an empty active piece;
+    // existing tiles and pieces keep their positions
+    pub fn add_player_lane(&mut self, lane_width: BoardDim) {
+        self.width += lane_width;
+        for row in self.matrix.iter_mut() {
+            row.resize(self.width as usize, Tile::default());
+        }
+        self.vec_active_piece.push(Piece::new(Shapes::None));
+    }
+
+    pub fn remove_active_piece(&mut self, player: u8) {
+        if self.vec_active_piece[player as usize].shape != Shapes::None {
+            self.emptify_piece(player);
+            self.vec_active_piece[player as usize].shape = Shapes::None;
+        }
+    }
+
     pub fn get_ghost_highlight_positions(&self) -> Vec<[(BoardPos, BoardPos); 4]> {
         let mut ghost_highlight_positions: Vec<[(BoardPos, BoardPos); 4]> = vec![];
 
@@ -683,7 +757,7 @@ impl BoardClassic {
                 }
             }
             checked_lines_for_scoring += lines_player_cleared;
-            score += match lines_player_cleared {
+            let player_score = match lines_player_cleared {
                 1 => SCORE_SINGLE_BASE as u32 * (level as u32 + 1),
                 2 => SCORE_DOUBLE_BASE as u32 * (level as u32 + 1),
                 3 => SCORE_TRIPLE_BASE as u32 * (level as u32 + 1),
@@ -693,6 +767,12 @@ impl BoardClassic {
                     0u32
                 }
             };
+            score += player_score;
+            self.vec_recent_clears.push((
+                player_num,
+                lines_player_cleared as BoardDim,
+                player_score,
+            ));
         }
 
         // emptify pieces here before clear lines so the tiles don't move with the line clear and then playerify them after
@@ -740,7 +820,7 @@ impl BoardClassic {
     }
 }
 
-#[derive(Ord, Eq, PartialOrd, PartialEq)]
+#[derive(Ord, Eq, PartialOrd, PartialEq, Clone, Serialize, Deserialize)]
 pub struct FullLine {
     pub row: BoardPos,
     pub lines_cleared_together: BoardDim,
@@ -762,12 +842,15 @@ impl FullLine {
 }
 
 // rotatris
+#[derive(Clone, Serialize, Deserialize)]
 pub struct BoardRotatris {
     pub gravity: Gravity,
     pub board_size: BoardDim,
     pub spawn_row: BoardPos,
     pub matrix: Vec<Vec<Tile>>,
     pub vec_active_piece: Vec<Piece>,
+    // (player, rings cleared together, score) for each clear since the last `take_recent_clears`
+    pub vec_recent_clears: Vec<(u8, BoardDim, u32)>,
 }
 
 impl BoardRotatris {
@@ -800,6 +883,7 @@ impl BoardRotatris {
             spawn_row,
             matrix,
             vec_active_piece,
+            vec_recent_clears: vec![],
         }
     }
 
@@ -1165,6 +1249,13 @@ impl BoardRotatris {
         }
     }
 
+    pub fn remove_active_piece(&mut self, player: u8) {
+        if self.vec_active_piece[player as usize].shape != Shapes::None {
+            self.emptify_piece(player);
+            self.vec_active_piece[player as usize].shape = Shapes::None;
+        }
+    }
+
     pub fn attempt_clear_rings(&mut self, level: u8) -> (BoardDim, u32) {
         let mut num_cleared_rings = 0;
         let mut score_from_cleared_rings = 0;
@@ -1196,6 +1287,9 @@ impl BoardRotatris {
                     0u32
                 }
             };
+            // rotatris is single player
+            self.vec_recent_clears
+                .push((0, num_cleared_rings, score_from_cleared_rings));
         }
 
         (num_cleared_rings, score_from_cleared_rings)
@@ -1270,13 +1364,20 @@ mod tests {
     fn clearing_and_scoring() {
         let board_width = 5;
         let board_height = 20;
+        let board_height_buffer = 2;
         let num_players = 3;
         let mut score: u64 = 0;
         let mut num_cleared_lines: u16 = 0;
-        let mut board = BoardClassic::new(board_width, board_height, num_players);
+        let mut board = BoardClassic::new(
+            board_width,
+            board_height,
+            board_height_buffer,
+            0,
+            num_players,
+        );
 
         for x in 0..4 {
-            for y in (board_height + self.height_buffer - 8)..board_height + self.height_buffer {
+            for y in (board_height + board_height_buffer - 8)..board_height + board_height_buffer {
                 board.matrix[y as usize][x as usize] = Tile::new(false, false, 0u8, Shapes::I);
             }
         }
@@ -1317,7 +1418,7 @@ mod tests {
         let mut num_cleared_lines: u16 = 0;
 
         for x in 0..board_width - 2 {
-            for y in (board_height + self.height_buffer - 4)..board_height + self.height_buffer {
+            for y in (board_height + board_height_buffer - 4)..board_height + board_height_buffer {
                 board.matrix[y as usize][x as usize] = Tile::new(false, false, 0u8, Shapes::I);
             }
         }
@@ -1367,7 +1468,7 @@ mod tests {
         let mut num_cleared_lines: u16 = 0;
 
         for x in 0..board_width - 1 {
-            for y in (board_height + self.height_buffer - 8)..board_height + self.height_buffer {
+            for y in (board_height + board_height_buffer - 8)..board_height + board_height_buffer {
                 board.matrix[y as usize][x as usize] = Tile::new(false, false, 0u8, Shapes::I);
             }
         }
@@ -1410,4 +1511,32 @@ mod tests {
         );
         println!("[~] Passed scoring 2 tetrises one frame apart");
     }
+
+    #[test]
+    fn adding_player_lane() {
+        let board_width = 10;
+        let board_height = 20;
+        let board_height_buffer = 2;
+        let mut board = BoardClassic::new(board_width, board_height, board_height_buffer, 0, 2);
+
+        board.matrix[21][9] = Tile::new(false, false, 0u8, Shapes::I);
+        board.attempt_piece_spawn(1, 5, Shapes::T);
+        let positions_before = board.vec_active_piece[1].positions;
+
+        board.add_player_lane(4);
+
+        assert_eq!(board.width, 14);
+        assert_eq!(board.vec_active_piece.len(), 3);
+        assert!(board.vec_active_piece[2].shape == Shapes::None);
+        for row in board.matrix.iter() {
+            assert_eq!(row.len(), 14);
+        }
+        assert!(!board.matrix[21][9].empty);
+        assert!(board.matrix[21][13].empty);
+        assert_eq!(board.vec_active_piece[1].positions, positions_before);
+
+        // the new player can spawn in the new lane and the old one can still move into it
+        assert_eq!(board.attempt_piece_spawn(2, 12, Shapes::O), (false, false));
+        assert!(board.attempt_piece_movement(Movement::Right, 1).0);
+    }
 }
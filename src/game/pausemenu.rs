@@ -0,0 +1,450 @@
+use ggez::graphics::{self, Color, DrawParam, Font, PxScale, Text, TextFragment};
+use ggez::mint::Point2;
+use ggez::Context;
+
+use crate::game::player::Player;
+use crate::game::GameSettings;
+use crate::inputs::Input;
+use crate::menu::menuhelpers::{MenuItem, MenuItemTrigger, MenuState};
+use crate::menu::menuhelpers::{SUB_TEXT_SCALE_DOWN, TEXT_SCALE_DOWN};
+use crate::movement::Movement;
+use crate::theme::Theme;
+
+enum PauseMenuItemId {
+    Resume,
+    Restart,
+    Settings,
+    Controls,
+    SaveAndQuit,
+    QuitToMenu,
+}
+
+enum PauseSettingsMenuItemId {
+    Back,
+    GhostPiecesState,
+    EliminationState,
+    InputOverlayState,
+}
+
+#[derive(PartialEq, Eq)]
+enum PauseMenuState {
+    Main,
+    Settings,
+    Controls,
+}
+
+pub struct PauseMenu {
+    // logic
+    pub input: Input,
+    state: PauseMenuState,
+    selection: usize,
+    vec_menu_items: Vec<MenuItem>,
+    settings_selection: usize,
+    vec_settings_menu_items: Vec<MenuItem>,
+    controls_back_item: MenuItem,
+    // drawing
+    paused_text: Text,
+    controls_text: Text,
+    text_color: Color,
+    background_color: Color,
+    window_height: f32,
+}
+
+impl PauseMenu {
+    pub fn new(
+        starting_settings: &GameSettings,
+        theme: &Theme,
+        window_dimensions: (f32, f32),
+    ) -> Self {
+        let mut vec_menu_items: Vec<MenuItem> = vec![
+            MenuItem::new_novalue(
+                "Resume",
+                PauseMenuItemId::Resume as u8,
+                MenuItemTrigger::Resume,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Restart",
+                PauseMenuItemId::Restart as u8,
+                MenuItemTrigger::Restart,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Settings",
+                PauseMenuItemId::Settings as u8,
+                MenuItemTrigger::SubMenu(MenuState::Settings),
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Controls",
+                PauseMenuItemId::Controls as u8,
+                MenuItemTrigger::SubMenu(MenuState::InputConfig),
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Save & Quit",
+                PauseMenuItemId::SaveAndQuit as u8,
+                MenuItemTrigger::SaveAndQuit,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Quit to Menu",
+                PauseMenuItemId::QuitToMenu as u8,
+                MenuItemTrigger::QuitToMenu,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+        ];
+        vec_menu_items[0].set_select(true);
+        // only the settings that make sense to change in the middle of a game
+        let mut vec_settings_menu_items: Vec<MenuItem> = vec![
+            MenuItem::new_novalue(
+                "Back",
+                PauseSettingsMenuItemId::Back as u8,
+                MenuItemTrigger::Back,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Ghost Pieces: ",
+                PauseSettingsMenuItemId::GhostPiecesState as u8,
+                starting_settings.ghost_pieces_state,
+                MenuItemTrigger::None,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Elimination: ",
+                PauseSettingsMenuItemId::EliminationState as u8,
+                starting_settings.elimination_state,
+                MenuItemTrigger::None,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Input Overlay: ",
+                PauseSettingsMenuItemId::InputOverlayState as u8,
+                starting_settings.input_overlay_state,
+                MenuItemTrigger::None,
+                theme,
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+        ];
+        vec_settings_menu_items[0].set_select(true);
+        let mut controls_back_item = MenuItem::new_novalue(
+            "Back",
+            0,
+            MenuItemTrigger::Back,
+            theme,
+            window_dimensions.1,
+            TEXT_SCALE_DOWN,
+        );
+        controls_back_item.set_select(true);
+        let mut paused_text = Text::new(TextFragment::new("PAUSED").color(theme.menu_text_color()));
+        paused_text.set_font(
+            Font::default(),
+            PxScale::from(window_dimensions.1 / TEXT_SCALE_DOWN),
+        );
+        Self {
+            // logic
+            input: Input::new(),
+            state: PauseMenuState::Main,
+            selection: 0,
+            vec_menu_items,
+            settings_selection: 0,
+            vec_settings_menu_items,
+            controls_back_item,
+            // drawing
+            paused_text,
+            controls_text: Text::default(),
+            text_color: theme.menu_text_color(),
+            background_color: theme.menu_background_color(),
+            window_height: window_dimensions.1,
+        }
+    }
+
+    // called when the game gets paused so it always opens on "Resume" with the current controls listed
+    pub fn open(&mut self, vec_players: &[Player]) {
+        self.input.reset_all();
+        self.state = PauseMenuState::Main;
+        self.vec_menu_items[self.selection].set_select(false);
+        self.selection = 0;
+        self.vec_menu_items[self.selection].set_select(true);
+        self.build_controls_text(vec_players);
+    }
+
+    // the game stays paused if it couldn't be saved, so say so on the item itself
+    pub fn set_save_failed(&mut self) {
+        for item in self.vec_menu_items.iter_mut() {
+            if item.id == PauseMenuItemId::SaveAndQuit as u8 {
+                item.text.fragments_mut()[0].text = "Save & Quit (failed)".to_string();
+            }
+        }
+    }
+
+    pub fn update(&mut self, settings: &mut GameSettings) -> MenuItemTrigger {
+        let trigger = match self.state {
+            PauseMenuState::Main => {
+                if self.input.keydown_rotate_ccw.1 {
+                    // escape was pressed
+                    MenuItemTrigger::Resume
+                } else {
+                    Self::navigate(&self.input, &mut self.selection, &mut self.vec_menu_items);
+                    if self.input.keydown_start.1 {
+                        self.vec_menu_items[self.selection].trigger.clone()
+                    } else {
+                        MenuItemTrigger::None
+                    }
+                }
+            }
+            PauseMenuState::Settings => {
+                if self.input.keydown_right.1 {
+                    self.vec_settings_menu_items[self.settings_selection].change_val(true);
+                }
+                if self.input.keydown_left.1 {
+                    self.vec_settings_menu_items[self.settings_selection].change_val(false);
+                }
+                settings.ghost_pieces_state = self.get_ghost_pieces_state();
+                settings.elimination_state = self.get_elimination_state();
+                settings.input_overlay_state = self.get_input_overlay_state();
+                Self::navigate(
+                    &self.input,
+                    &mut self.settings_selection,
+                    &mut self.vec_settings_menu_items,
+                );
+                if self.input.keydown_rotate_ccw.1
+                    || (self.input.keydown_start.1
+                        && self.vec_settings_menu_items[self.settings_selection].trigger
+                            == MenuItemTrigger::Back)
+                {
+                    MenuItemTrigger::Back
+                } else {
+                    MenuItemTrigger::None
+                }
+            }
+            PauseMenuState::Controls => {
+                if self.input.keydown_rotate_ccw.1 || self.input.keydown_start.1 {
+                    MenuItemTrigger::Back
+                } else {
+                    MenuItemTrigger::None
+                }
+            }
+        };
+        self.input.was_just_pressed_setfalse();
+
+        // submenus are handled here; everything else is for the game to deal with
+        match trigger {
+            MenuItemTrigger::SubMenu(MenuState::Settings) => {
+                self.state = PauseMenuState::Settings;
+                MenuItemTrigger::None
+            }
+            MenuItemTrigger::SubMenu(MenuState::InputConfig) => {
+                self.state = PauseMenuState::Controls;
+                MenuItemTrigger::None
+            }
+            MenuItemTrigger::Back => {
+                self.state = PauseMenuState::Main;
+                MenuItemTrigger::None
+            }
+            _ => trigger,
+        }
+    }
+
+    fn navigate(input: &Input, selection: &mut usize, vec_menu_items: &mut [MenuItem]) {
+        if input.keydown_down.1 {
+            vec_menu_items[*selection].set_select(false);
+            *selection = (*selection + 1) % vec_menu_items.len();
+            vec_menu_items[*selection].set_select(true);
+        }
+
+        if input.keydown_up.1 {
+            vec_menu_items[*selection].set_select(false);
+            *selection = if *selection == 0 {
+                vec_menu_items.len() - 1
+            } else {
+                *selection - 1
+            };
+            vec_menu_items[*selection].set_select(true);
+        }
+    }
+
+    fn get_ghost_pieces_state(&self) -> bool {
+        for item in self.vec_settings_menu_items.iter() {
+            if item.id == PauseSettingsMenuItemId::GhostPiecesState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get ghost pieces state in PauseMenu");
+    }
+
+    fn get_elimination_state(&self) -> bool {
+        for item in self.vec_settings_menu_items.iter() {
+            if item.id == PauseSettingsMenuItemId::EliminationState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get elimination state in PauseMenu");
+    }
+
+    fn get_input_overlay_state(&self) -> bool {
+        for item in self.vec_settings_menu_items.iter() {
+            if item.id == PauseSettingsMenuItemId::InputOverlayState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get input overlay state in PauseMenu");
+    }
+
+    fn build_controls_text(&mut self, vec_players: &[Player]) {
+        self.controls_text = Text::default();
+        for player in vec_players.iter() {
+            let mut line = format!("Player {}: ", player.player_num + 1);
+            if player.idle {
+                line.push_str("(open seat)");
+            } else if let Some(k_ctrl_scheme) = &player.control_scheme.0 {
+                for movement in [
+                    Movement::Left,
+                    Movement::Right,
+                    Movement::Down,
+                    Movement::RotateCw,
+                    Movement::RotateCcw,
+                    Movement::BoardCw,
+                    Movement::BoardCcw,
+                    Movement::Restart,
+                ]
+                .iter()
+                {
+                    let keycodes = k_ctrl_scheme.keycodes_from_movement(*movement);
+                    if !keycodes.is_empty() {
+                        let keys: Vec<String> =
+                            keycodes.iter().map(|key| format!("{:?}", key)).collect();
+                        line.push_str(&format!("{:?}: {}  ", movement, keys.join("/")));
+                    }
+                }
+            } else if player.control_scheme.1 {
+                line.push_str("Gamepad  ");
+                for movement in [
+                    Movement::Left,
+                    Movement::Right,
+                    Movement::Down,
+                    Movement::RotateCw,
+                    Movement::RotateCcw,
+                    Movement::BoardCw,
+                    Movement::BoardCcw,
+                ]
+                .iter()
+                {
+                    if let Some(gamepad_input) =
+                        player.gamepad_scheme.input_from_movement(*movement)
+                    {
+                        line.push_str(&format!("{:?}: {}  ", movement, gamepad_input));
+                    }
+                }
+            } else {
+                line.push_str("Gamepad");
+            }
+            line.push('\n');
+            self.controls_text
+                .add(TextFragment::new(line).color(self.text_color));
+        }
+        self.controls_text.add(
+            TextFragment::new(
+                "\nLeave: hold Down + press Select (gamepad) or both rotations (keyboard)",
+            )
+            .color(self.text_color),
+        );
+        self.controls_text.set_font(
+            Font::default(),
+            PxScale::from(self.window_height / SUB_TEXT_SCALE_DOWN),
+        );
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) {
+        graphics::clear(ctx, self.background_color);
+        let window_dimensions = graphics::size(ctx);
+
+        match self.state {
+            PauseMenuState::Main => {
+                let num_menu_items_to_draw = self.vec_menu_items.len() + 1;
+                self.draw_text(
+                    ctx,
+                    &self.paused_text,
+                    1.0 / (num_menu_items_to_draw + 1) as f32,
+                    &window_dimensions,
+                );
+                for (index, item) in self.vec_menu_items.iter().enumerate() {
+                    self.draw_text(
+                        ctx,
+                        &item.text,
+                        (index + 2) as f32 / (num_menu_items_to_draw + 1) as f32,
+                        &window_dimensions,
+                    );
+                }
+            }
+            PauseMenuState::Settings => {
+                let num_menu_items_to_draw = self.vec_settings_menu_items.len();
+                for (index, item) in self.vec_settings_menu_items.iter().enumerate() {
+                    self.draw_text(
+                        ctx,
+                        &item.text,
+                        (index + 1) as f32 / (num_menu_items_to_draw + 1) as f32,
+                        &window_dimensions,
+                    );
+                }
+            }
+            PauseMenuState::Controls => {
+                self.draw_text(ctx, &self.controls_back_item.text, 0.1, &window_dimensions);
+                self.draw_text(ctx, &self.controls_text, 0.6, &window_dimensions);
+            }
+        }
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text_var: &Text,
+        vertical_position: f32,
+        window_dimensions: &(f32, f32),
+    ) {
+        let text_var_dimensions = text_var.dimensions(ctx);
+        graphics::draw(
+            ctx,
+            text_var,
+            DrawParam::new().dest(Point2::from_slice(&[
+                (window_dimensions.0 - text_var_dimensions.w) / 2.0,
+                (window_dimensions.1 - text_var_dimensions.h) * vertical_position,
+            ])),
+        )
+        .unwrap();
+    }
+
+    pub fn resize_event(&mut self, height: f32) {
+        self.window_height = height;
+        for item in self.vec_menu_items.iter_mut() {
+            item.resize(height);
+        }
+        for item in self.vec_settings_menu_items.iter_mut() {
+            item.resize(height);
+        }
+        self.controls_back_item.resize(height);
+        self.paused_text
+            .set_font(Font::default(), PxScale::from(height / TEXT_SCALE_DOWN));
+        self.controls_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+    }
+}
@@ -1,10 +1,13 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
 use crate::game::board::{BoardDim, BoardPos, Gravity};
 use crate::movement::Movement;
 
 use std::convert::TryFrom;
 
 #[repr(u8)]
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum Shapes {
     I,
     O,
@@ -33,7 +36,20 @@ impl TryFrom<u8> for Shapes {
     }
 }
 
-#[derive(Copy, Clone)]
+impl Shapes {
+    pub fn random<R: Rng>(rng: &mut R) -> Self {
+        let mut rand: u8;
+        loop {
+            rand = rng.gen::<u8>();
+            if rand < 252 {
+                break;
+            }
+        }
+        Shapes::try_from(rand % 7).expect("Unable to get random piece")
+    }
+}
+
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Piece {
     pub shape: Shapes,
     pub positions: [(BoardPos, BoardPos); 4],
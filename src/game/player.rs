@@ -1,22 +1,46 @@
 use ggez::event::{Axis, Button, KeyCode};
-use rand::random;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 use crate::game::board::BoardPos;
 use crate::game::piece::Shapes;
-use crate::game::{DAS_THRESHOLD_BIG, FORCE_FALL_DELAY, INITIAL_HANG_FRAMES};
-use crate::game::{DETECT_GAMEPAD_AXIS_THRESHOLD, UNDETECT_GAMEPAD_AXIS_THRESHOLD};
-use crate::inputs::{Input, KeyboardControlScheme};
+use crate::game::stats::PlayerStats;
+use crate::game::{DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE, FORCE_FALL_DELAY, INITIAL_HANG_FRAMES};
+use crate::inputs::SocdPolicy;
+use crate::inputs::{GamepadControlScheme, GamepadInput, Input, InputEvent, KeyboardControlScheme};
 use crate::movement::Movement;
-
-use std::convert::TryFrom;
+use crate::profiles::Profile;
 
 pub const SPAWN_DELAY: i16 = 20i16;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Player {
     pub player_num: u8,
     pub control_scheme: (Option<KeyboardControlScheme>, bool),
-    axis_wait_for_unpress_x: bool,
-    axis_wait_for_unpress_y: bool,
+    pub profile: Option<Profile>,
+    // only used when control_scheme.1 is set
+    #[serde(default)]
+    pub gamepad_scheme: GamepadControlScheme,
+    // gamepads have to be picked up again when a saved game is continued, sticks and all
+    #[serde(skip)]
+    vec_held_axis_input: Vec<GamepadInput>,
+    // like keys, a movement on a gamepad is only let go once none of its buttons or stick directions are held
+    #[serde(skip)]
+    vec_held_buttons: Vec<Button>,
+    // a movement can have more than one key, and it's only let go once none of them are held
+    #[serde(skip)]
+    vec_held_keycodes: Vec<KeyCode>,
+    // the last rotation pressed during the spawn delay, for initial rotation
+    #[serde(default)]
+    buffered_rotation: Option<Movement>,
+    #[serde(default)]
+    pub socd: SocdPolicy,
+    // whether left and right are physically held, which can differ from the inputs once the socd policy had its say;
+    // nothing is held anymore once a saved game is continued
+    #[serde(skip)]
+    held_left: bool,
+    #[serde(skip)]
+    held_right: bool,
     pub input: Input,
     pub spawn_piece_flag: bool,
     pub spawn_column: BoardPos,
@@ -26,39 +50,107 @@ pub struct Player {
     pub fall_countdown: u8,
     pub force_fall_countdown: u8,
     pub das_countdown: u8,
+    pub das_threshold_big: u8,
+    pub das_threshold_little: u8,
     pub waiting_to_shift: bool,
+    pub eliminated: bool,
+    pub idle: bool,
+    pub survival_frames: u32,
+    // what the lane had already done when this player took it over, so their profile only gets their own part
+    pub stats_baseline: PlayerStats,
+    pub join_frame: u32,
 }
 
 impl Player {
-    pub fn new(
+    pub fn new<R: Rng>(
         player_num: u8,
         control_scheme: (Option<KeyboardControlScheme>, bool),
+        profile: Option<Profile>,
         spawn_column: BoardPos,
+        rng: &mut R,
     ) -> Self {
+        // players without a profile get the default handling
+        let (das_threshold_big, das_threshold_little) = match &profile {
+            Some(profile) => (profile.das_threshold_big, profile.das_threshold_little),
+            None => (DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE),
+        };
         Self {
             player_num,
             control_scheme,
-            axis_wait_for_unpress_x: false,
-            axis_wait_for_unpress_y: false,
+            profile,
+            gamepad_scheme: GamepadControlScheme::default(),
+            vec_held_axis_input: vec![],
+            vec_held_buttons: vec![],
+            vec_held_keycodes: vec![],
+            buffered_rotation: None,
+            socd: SocdPolicy::default(),
+            held_left: false,
+            held_right: false,
             input: Input::new(),
             spawn_piece_flag: true,
             spawn_column,
             spawn_delay: SPAWN_DELAY,
-            next_piece_shape: {
-                let mut rand: u8;
-                loop {
-                    rand = random::<u8>();
-                    if rand < 252 {
-                        break;
-                    }
-                }
-                Shapes::try_from(rand % 7).expect("Unable to get random piece")
-            },
+            next_piece_shape: Shapes::random(rng),
             redraw_next_piece_flag: true,
             fall_countdown: INITIAL_HANG_FRAMES,
             force_fall_countdown: FORCE_FALL_DELAY,
-            das_countdown: DAS_THRESHOLD_BIG,
+            das_countdown: das_threshold_big,
+            das_threshold_big,
+            das_threshold_little,
             waiting_to_shift: false,
+            eliminated: false,
+            idle: false,
+            survival_frames: 0,
+            stats_baseline: PlayerStats::default(),
+            join_frame: 0,
+        }
+    }
+
+    // holding down and pressing select (gamepad) or both rotations (keyboard) leaves the game
+    pub fn wants_to_leave(&self) -> bool {
+        self.input.keydown_down.0
+            && (self.input.keydown_select.1
+                || (self.input.keydown_rotate_cw.0
+                    && self.input.keydown_rotate_ccw.0
+                    && (self.input.keydown_rotate_cw.1 || self.input.keydown_rotate_ccw.1)))
+    }
+
+    // anything held is forgotten, so it has to be pressed again to count
+    pub fn reset_input(&mut self) {
+        self.input.reset_all();
+        self.vec_held_keycodes.clear();
+        self.vec_held_buttons.clear();
+        self.vec_held_axis_input.clear();
+        self.held_left = false;
+        self.held_right = false;
+    }
+
+    pub fn profile_name(&self) -> Option<&str> {
+        self.profile.as_ref().map(|profile| profile.name.as_str())
+    }
+
+    // rotations pressed while waiting for the next piece are kept for when it spawns instead of rotating it a frame late
+    pub fn buffer_initial_rotation(&mut self) {
+        if self.input.keydown_rotate_cw.1 {
+            self.buffered_rotation = Some(Movement::RotateCw);
+            self.input.keydown_rotate_cw.1 = false;
+        }
+        if self.input.keydown_rotate_ccw.1 {
+            self.buffered_rotation = Some(Movement::RotateCcw);
+            self.input.keydown_rotate_ccw.1 = false;
+        }
+    }
+
+    // a rotation key that's still held when the piece spawns wins over one that was only tapped
+    pub fn take_initial_rotation(&mut self) -> Option<Movement> {
+        let buffered_rotation = self.buffered_rotation.take();
+        match (
+            self.input.keydown_rotate_cw.0,
+            self.input.keydown_rotate_ccw.0,
+        ) {
+            (true, false) => Some(Movement::RotateCw),
+            (false, true) => Some(Movement::RotateCcw),
+            _ => buffered_rotation,
         }
     }
 
@@ -73,213 +165,308 @@ impl Player {
         false
     }
 
-    pub fn update_input_keydown(&mut self, input: KeyCode) -> bool {
-        if let Some(k_ctrl_scheme) = &self.control_scheme.0 {
-            let movement_opt = k_ctrl_scheme.movement_from_keycode(input);
-            if let Some(movement) = movement_opt {
-                match movement {
-                    Movement::Down => {
-                        if !self.input.keydown_down.0 {
-                            self.input.keydown_down = (true, true);
-                            return true;
-                        }
-                    }
-                    Movement::Left => {
-                        if !self.input.keydown_left.0 {
-                            self.input.keydown_left = (true, true);
-                            // for auto-shift reasons and controller reasons...
-                            self.input.keydown_right.0 = false;
-                            return true;
-                        }
-                    }
-                    Movement::Up => {
-                        if !self.input.keydown_up.0 {
-                            self.input.keydown_up = (true, true);
-                            return true;
-                        }
-                    }
-                    Movement::Right => {
-                        if !self.input.keydown_right.0 {
-                            self.input.keydown_right = (true, true);
-                            // for auto-shift reasons and controller reasons...
-                            self.input.keydown_left.0 = false;
-                            return true;
-                        }
-                    }
-                    Movement::RotateCw => {
-                        if !self.input.keydown_rotate_cw.0 {
-                            self.input.keydown_rotate_cw = (true, true);
-                            return true;
-                        }
-                    }
-                    Movement::RotateCcw => {
-                        if !self.input.keydown_rotate_ccw.0 {
-                            self.input.keydown_rotate_ccw = (true, true);
-                            return true;
-                        }
-                    }
-                    Movement::BoardCw => {
-                        if !self.input.keydown_board_cw.0 {
-                            self.input.keydown_board_cw = (true, true);
-                            return true;
-                        }
-                    }
-                    Movement::BoardCcw => {
-                        if !self.input.keydown_board_ccw.0 {
-                            self.input.keydown_board_ccw = (true, true);
-                            return true;
-                        }
-                    }
-                    _ => {}
-                }
+    // which input event (if any) a key from this player's keyboard scheme stands for;
+    // letting go of a key while another key for the same movement is still held doesn't count
+    pub fn input_event_from_keycode(
+        &mut self,
+        keycode: KeyCode,
+        pressed: bool,
+    ) -> Option<InputEvent> {
+        let k_ctrl_scheme = self.control_scheme.0.as_ref()?;
+        let movement = k_ctrl_scheme.movement_from_keycode(keycode)?;
+        if pressed {
+            if !self.vec_held_keycodes.contains(&keycode) {
+                self.vec_held_keycodes.push(keycode);
             }
+            return Some(InputEvent::Press(movement));
+        }
+        self.vec_held_keycodes
+            .retain(|held_key| *held_key != keycode);
+        if self
+            .vec_held_keycodes
+            .iter()
+            .any(|held_key| k_ctrl_scheme.movement_from_keycode(*held_key) == Some(movement))
+        {
+            return None;
         }
+        Some(InputEvent::Release(movement))
+    }
 
-        false
+    // Start and Select are the same on every gamepad; everything else comes from the player's gamepad scheme
+    pub fn input_event_from_button(&mut self, btn: Button, pressed: bool) -> Option<InputEvent> {
+        let movement = match btn {
+            Button::Start => return Some(InputEvent::Start(pressed)),
+            Button::Select => return Some(InputEvent::Select(pressed)),
+            _ => self
+                .gamepad_scheme
+                .movement_from_input(GamepadInput::Button(btn))?,
+        };
+        if pressed {
+            if !self.vec_held_buttons.contains(&btn) {
+                self.vec_held_buttons.push(btn);
+            }
+            return Some(InputEvent::Press(movement));
+        }
+        self.vec_held_buttons.retain(|held_btn| *held_btn != btn);
+        if self.gamepad_movement_held(movement) {
+            return None;
+        }
+        Some(InputEvent::Release(movement))
     }
 
-    pub fn update_input_keyup(&mut self, input: KeyCode) -> bool {
-        if let Some(k_ctrl_scheme) = &self.control_scheme.0 {
-            let movement_opt = k_ctrl_scheme.movement_from_keycode(input);
-            if let Some(movement) = movement_opt {
-                match movement {
-                    Movement::Down => {
-                        self.input.keydown_down = (false, false);
-                        return true;
-                    }
-                    Movement::Left => {
-                        // for auto-shift reasons
-                        if self.input.keydown_left.0 {
-                            self.das_countdown = DAS_THRESHOLD_BIG;
-                            self.waiting_to_shift = false;
-                        }
-                        self.input.keydown_left = (false, false);
-                        return true;
-                    }
-                    Movement::Up => {
-                        self.input.keydown_up = (false, false);
-                        return true;
-                    }
-                    Movement::Right => {
-                        // for auto-shift reasons
-                        if self.input.keydown_right.0 {
-                            self.das_countdown = DAS_THRESHOLD_BIG;
-                            self.waiting_to_shift = false;
-                        }
-                        self.input.keydown_right = (false, false);
-                        return true;
-                    }
-                    Movement::RotateCw => {
-                        self.input.keydown_rotate_cw = (false, false);
-                        return true;
-                    }
-                    Movement::RotateCcw => {
-                        self.input.keydown_rotate_ccw = (false, false);
-                        return true;
-                    }
-                    Movement::BoardCw => {
-                        self.input.keydown_board_cw = (false, false);
-                        return true;
-                    }
-                    Movement::BoardCcw => {
-                        self.input.keydown_board_ccw = (false, false);
-                        return true;
-                    }
-                    _ => {}
+    // a bound axis direction acts like a button once it's pushed past the threshold, and lets go once it comes back near the center
+    pub fn input_events_from_axis(&mut self, axis: Axis, value: f32) -> Vec<InputEvent> {
+        let sticks = self.gamepad_scheme.sticks;
+        let value = sticks.read_axis(axis, value);
+        let mut vec_input_events: Vec<InputEvent> = vec![];
+        for (input, movement) in self.gamepad_scheme.vec_input_movement_pair.iter() {
+            let pushed = match input.axis_value(axis, value) {
+                Some(pushed) => pushed,
+                None => continue,
+            };
+            let held = self.vec_held_axis_input.contains(input);
+            if !held && pushed > sticks.press_threshold() {
+                self.vec_held_axis_input.push(*input);
+                vec_input_events.push(InputEvent::Press(*movement));
+            } else if held && pushed < sticks.release_threshold() {
+                self.vec_held_axis_input
+                    .retain(|held_input| held_input != input);
+                if !self.gamepad_movement_held(*movement) {
+                    vec_input_events.push(InputEvent::Release(*movement));
                 }
             }
         }
 
-        false
+        vec_input_events
     }
 
-    pub fn update_input_buttondown(&mut self, btn: Button) {
-        if btn == Button::DPadLeft {
-            self.input.keydown_left = (true, true);
-            self.input.keydown_right = (false, false);
-        } else if btn == Button::DPadRight {
-            self.input.keydown_right = (true, true);
-            self.input.keydown_left = (false, false);
-        } else if btn == Button::DPadDown {
-            self.input.keydown_down = (true, true);
-        } else if btn == Button::East {
-            self.input.keydown_rotate_cw = (true, true);
-        } else if btn == Button::South {
-            self.input.keydown_rotate_ccw = (true, true);
-        } else if btn == Button::North {
-            self.input.keydown_board_cw = (true, true);
-        } else if btn == Button::West {
-            self.input.keydown_board_ccw = (true, true);
-        } else if btn == Button::Start {
-            self.input.keydown_start = (true, true);
-        }
+    // whether a gamepad button or stick direction that's still held is bound to `movement`
+    fn gamepad_movement_held(&self, movement: Movement) -> bool {
+        self.vec_held_buttons
+            .iter()
+            .map(|held_btn| GamepadInput::Button(*held_btn))
+            .chain(self.vec_held_axis_input.iter().copied())
+            .any(|held_input| self.gamepad_scheme.movement_from_input(held_input) == Some(movement))
     }
 
-    pub fn update_input_buttonup(&mut self, btn: Button) {
-        if btn == Button::DPadLeft {
-            // for auto-shift reasons
-            if self.input.keydown_left.0 {
-                self.das_countdown = DAS_THRESHOLD_BIG;
-                self.waiting_to_shift = false;
+    // returns whether the event changed anything
+    pub fn apply_input_event(&mut self, input_event: InputEvent) -> bool {
+        match input_event {
+            InputEvent::Press(movement) => self.press(movement),
+            InputEvent::Release(movement) => self.release(movement),
+            InputEvent::Start(pressed) => Self::set_pressed(&mut self.input.keydown_start, pressed),
+            InputEvent::Select(pressed) => {
+                Self::set_pressed(&mut self.input.keydown_select, pressed)
             }
-            self.input.keydown_left = (false, false);
-        } else if btn == Button::DPadRight {
-            // for auto-shift reasons
-            if self.input.keydown_right.0 {
-                self.das_countdown = DAS_THRESHOLD_BIG;
-                self.waiting_to_shift = false;
-            }
-            self.input.keydown_right = (false, false);
-        } else if btn == Button::DPadDown {
-            self.input.keydown_down = (false, false);
-        } else if btn == Button::East {
-            self.input.keydown_rotate_cw = (false, false);
-        } else if btn == Button::South {
-            self.input.keydown_rotate_ccw = (false, false);
-        } else if btn == Button::North {
-            self.input.keydown_board_cw = (false, false);
-        } else if btn == Button::West {
-            self.input.keydown_board_ccw = (false, false);
-        } else if btn == Button::Start {
-            self.input.keydown_start = (false, false);
         }
     }
 
-    pub fn update_input_axis(&mut self, axis: Axis, value: f32) {
-        if axis == Axis::LeftStickX {
-            // left and right
-            if !self.axis_wait_for_unpress_x && value < -DETECT_GAMEPAD_AXIS_THRESHOLD {
-                // press left
-                self.axis_wait_for_unpress_x = true;
-                self.input.keydown_left = (true, true);
-                self.input.keydown_right = (false, false);
-            } else if !self.axis_wait_for_unpress_x && value > DETECT_GAMEPAD_AXIS_THRESHOLD {
-                // press right
-                self.axis_wait_for_unpress_x = true;
-                self.input.keydown_right = (true, true);
-                self.input.keydown_left = (false, false);
-            } else if self.axis_wait_for_unpress_x
-                && value < UNDETECT_GAMEPAD_AXIS_THRESHOLD
-                && value > -UNDETECT_GAMEPAD_AXIS_THRESHOLD
-            {
-                // unpress left and right
-                self.das_countdown = DAS_THRESHOLD_BIG;
-                self.waiting_to_shift = false;
-                self.axis_wait_for_unpress_x = false;
-                self.input.keydown_left = (false, false);
-                self.input.keydown_right = (false, false);
-            }
-        } else if axis == Axis::LeftStickY {
-            // down
-            if !self.axis_wait_for_unpress_y && value < -DETECT_GAMEPAD_AXIS_THRESHOLD {
-                // press down
-                self.axis_wait_for_unpress_y = true;
-                self.input.keydown_down = (true, true);
-            } else if self.axis_wait_for_unpress_y && value > -UNDETECT_GAMEPAD_AXIS_THRESHOLD {
-                // unpress down
-                self.axis_wait_for_unpress_y = false;
-                self.input.keydown_down = (false, false);
-            }
+    fn set_pressed(keydown: &mut (bool, bool), pressed: bool) -> bool {
+        if pressed == keydown.0 {
+            return false;
+        }
+        *keydown = (pressed, pressed);
+        true
+    }
+
+    fn press(&mut self, movement: Movement) -> bool {
+        match movement {
+            Movement::Down => Self::set_pressed(&mut self.input.keydown_down, true),
+            Movement::Left => self.press_horizontal(true),
+            Movement::Up => Self::set_pressed(&mut self.input.keydown_up, true),
+            Movement::Right => self.press_horizontal(false),
+            Movement::RotateCw => Self::set_pressed(&mut self.input.keydown_rotate_cw, true),
+            Movement::RotateCcw => Self::set_pressed(&mut self.input.keydown_rotate_ccw, true),
+            Movement::BoardCw => Self::set_pressed(&mut self.input.keydown_board_cw, true),
+            Movement::BoardCcw => Self::set_pressed(&mut self.input.keydown_board_ccw, true),
+            _ => false,
+        }
+    }
+
+    fn release(&mut self, movement: Movement) -> bool {
+        match movement {
+            Movement::Down => Self::set_pressed(&mut self.input.keydown_down, false),
+            Movement::Left => self.release_horizontal(true),
+            Movement::Up => Self::set_pressed(&mut self.input.keydown_up, false),
+            Movement::Right => self.release_horizontal(false),
+            Movement::RotateCw => Self::set_pressed(&mut self.input.keydown_rotate_cw, false),
+            Movement::RotateCcw => Self::set_pressed(&mut self.input.keydown_rotate_ccw, false),
+            Movement::BoardCw => Self::set_pressed(&mut self.input.keydown_board_cw, false),
+            Movement::BoardCcw => Self::set_pressed(&mut self.input.keydown_board_ccw, false),
+            _ => false,
+        }
+    }
+
+    // left and right go through the player's socd policy, so keyboards and gamepads settle holding both the same way
+    fn press_horizontal(&mut self, left: bool) -> bool {
+        let (held, other_held) = if left {
+            (&mut self.held_left, self.held_right)
+        } else {
+            (&mut self.held_right, self.held_left)
+        };
+        if *held {
+            return false;
+        }
+        *held = true;
+        let other_active = self.horizontal_keydown(!left).0;
+        let (activate, stop_other) = match self.socd {
+            SocdPolicy::LastInputWins => (true, other_active),
+            SocdPolicy::FirstInputWins => (!other_active, false),
+            SocdPolicy::Neutral => (!other_held, other_active),
+        };
+        if stop_other {
+            self.stop_horizontal(!left);
+        }
+        if activate {
+            *self.horizontal_keydown(left) = (true, true);
         }
+        true
+    }
+
+    // whatever the policy, the other direction picks back up if it's still held
+    fn release_horizontal(&mut self, left: bool) -> bool {
+        let (held, other_held) = if left {
+            (&mut self.held_left, self.held_right)
+        } else {
+            (&mut self.held_right, self.held_left)
+        };
+        if !*held {
+            return false;
+        }
+        *held = false;
+        self.stop_horizontal(left);
+        if other_held && !self.horizontal_keydown(!left).0 {
+            *self.horizontal_keydown(!left) = (true, true);
+        }
+        true
+    }
+
+    fn horizontal_keydown(&mut self, left: bool) -> &mut (bool, bool) {
+        if left {
+            &mut self.input.keydown_left
+        } else {
+            &mut self.input.keydown_right
+        }
+    }
+
+    fn stop_horizontal(&mut self, left: bool) {
+        // for auto-shift reasons
+        if self.horizontal_keydown(left).0 {
+            self.das_countdown = self.das_threshold_big;
+            self.waiting_to_shift = false;
+        }
+        *self.horizontal_keydown(left) = (false, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    fn new_player() -> Player {
+        Player::new(0, (None, true), None, 4, &mut Pcg64Mcg::seed_from_u64(0))
+    }
+
+    // Left on both the arrow key and 'A'
+    fn new_keyboard_player() -> Player {
+        let mut k_ctrl_scheme = KeyboardControlScheme::default();
+        k_ctrl_scheme.add_pair(KeyCode::Left, Movement::Left);
+        k_ctrl_scheme.add_pair(KeyCode::A, Movement::Left);
+        let mut player = new_player();
+        player.control_scheme = (Some(k_ctrl_scheme), false);
+        player
+    }
+
+    fn key(player: &mut Player, keycode: KeyCode, pressed: bool) {
+        let input_event = player.input_event_from_keycode(keycode, pressed);
+        apply(player, input_event);
+    }
+
+    fn apply(player: &mut Player, input_event: Option<InputEvent>) {
+        if let Some(input_event) = input_event {
+            player.apply_input_event(input_event);
+        }
+    }
+
+    #[test]
+    fn gamepad_movement_held_by_button_and_stick() {
+        let mut player = new_player();
+        let input_event = player.input_event_from_button(Button::DPadLeft, true);
+        apply(&mut player, input_event);
+        for input_event in player.input_events_from_axis(Axis::LeftStickX, -1.0) {
+            player.apply_input_event(input_event);
+        }
+        // the stick is still held
+        let input_event = player.input_event_from_button(Button::DPadLeft, false);
+        assert!(input_event.is_none());
+        assert!(player.input.keydown_left.0);
+        for input_event in player.input_events_from_axis(Axis::LeftStickX, 0.0) {
+            player.apply_input_event(input_event);
+        }
+        assert!(!player.input.keydown_left.0);
+    }
+
+    #[test]
+    fn movement_held_by_two_keys_until_both_are_let_go() {
+        let mut player = new_keyboard_player();
+        key(&mut player, KeyCode::Left, true);
+        key(&mut player, KeyCode::A, true);
+        key(&mut player, KeyCode::Left, false);
+        assert!(player.input.keydown_left.0);
+        key(&mut player, KeyCode::A, false);
+        assert!(!player.input.keydown_left.0);
+    }
+
+    #[test]
+    fn reset_forgets_held_keys() {
+        let mut player = new_keyboard_player();
+        key(&mut player, KeyCode::Left, true);
+        // the keyup for Left got lost, say while the window didn't have focus
+        player.reset_input();
+        key(&mut player, KeyCode::A, true);
+        key(&mut player, KeyCode::A, false);
+        assert!(!player.input.keydown_left.0);
+    }
+
+    // hold Left, press Right, let go of Right
+    fn socd_left_then_right(socd: SocdPolicy) -> Vec<(bool, bool)> {
+        let mut player = new_player();
+        player.socd = socd;
+        let mut vec_states: Vec<(bool, bool)> = vec![];
+        for input_event in [
+            InputEvent::Press(Movement::Left),
+            InputEvent::Press(Movement::Right),
+            InputEvent::Release(Movement::Right),
+        ] {
+            player.apply_input_event(input_event);
+            vec_states.push((player.input.keydown_left.0, player.input.keydown_right.0));
+        }
+        vec_states
+    }
+
+    #[test]
+    fn socd_last_input_wins() {
+        assert_eq!(
+            socd_left_then_right(SocdPolicy::LastInputWins),
+            vec![(true, false), (false, true), (true, false)]
+        );
+    }
+
+    #[test]
+    fn socd_first_input_wins() {
+        assert_eq!(
+            socd_left_then_right(SocdPolicy::FirstInputWins),
+            vec![(true, false), (true, false), (true, false)]
+        );
+    }
+
+    #[test]
+    fn socd_neutral() {
+        assert_eq!(
+            socd_left_then_right(SocdPolicy::Neutral),
+            vec![(true, false), (false, false), (true, false)]
+        );
     }
 }
@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{GameMode, GameOptions, GameSettings};
+use crate::game::{DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE};
+use crate::inputs::{InputEvent, SocdPolicy};
+use crate::movement::Movement;
+
+use std::convert::TryFrom;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const REPLAY_VERSION: u32 = 1;
+static REPLAY_HEADER: &str = "tetrisn-t replay";
+
+// everything that can change how a game plays out besides the seed and the starting options;
+// each one is stamped with the frame it happened on (before that frame's game logic ran)
+#[derive(Copy, Clone, Serialize, Deserialize)]
+pub enum ReplayEvent {
+    Input(u8, InputEvent),
+    // every player's inputs are reset when the game is paused
+    ResetInputs,
+    // with the das thresholds and socd policy of whoever joined, since those come from their profile or controls
+    Join(u8, u8, SocdPolicy),
+    Elimination(bool),
+}
+
+// saved games carry the replay along so a continued game can still be replayed from the start
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub game_mode: GameMode,
+    pub num_players: u8,
+    pub starting_level: u8,
+    pub settings: GameSettings,
+    // das thresholds (big, little) of each starting player
+    pub vec_das: Vec<(u8, u8)>,
+    pub vec_socd: Vec<SocdPolicy>,
+    pub vec_events: Vec<(u32, ReplayEvent)>,
+}
+
+impl Replay {
+    pub fn new(game_options: &GameOptions, seed: u64) -> Self {
+        Self {
+            seed,
+            game_mode: game_options.game_mode,
+            num_players: game_options.num_players,
+            starting_level: game_options.starting_level,
+            settings: game_options.settings,
+            vec_das: game_options
+                .vec_profiles
+                .iter()
+                .take(game_options.num_players as usize)
+                .map(|profile| match profile {
+                    Some(profile) => (profile.das_threshold_big, profile.das_threshold_little),
+                    None => (DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE),
+                })
+                .collect(),
+            vec_socd: game_options
+                .vec_socd
+                .iter()
+                .take(game_options.num_players as usize)
+                .copied()
+                .collect(),
+            vec_events: vec![],
+        }
+    }
+
+    pub fn record(&mut self, frame: u32, event: ReplayEvent) {
+        self.vec_events.push((frame, event));
+    }
+
+    // writes the replay into `dir` with the current time in the name and returns where it went
+    pub fn save(&self, dir: &Path) -> Result<PathBuf, String> {
+        if let Err(e) = fs::create_dir_all(dir) {
+            return Err(format!("couldn't create {}: {}", dir.display(), e));
+        }
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let path = dir.join(format!("replay-{}.txt", secs));
+        match fs::write(&path, self.to_text()) {
+            Ok(()) => Ok(path),
+            Err(e) => Err(format!("couldn't write {}: {}", path.display(), e)),
+        }
+    }
+
+    // reads back a replay written by `save`
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) => return Err(format!("couldn't read {}: {}", path.display(), e)),
+        };
+        Self::from_text(&text).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(REPLAY_HEADER) {
+            return Err("not a replay".to_string());
+        }
+        let mut replay = Self {
+            seed: 0,
+            game_mode: GameMode::None,
+            num_players: 0,
+            starting_level: 0,
+            settings: GameSettings::default(),
+            vec_das: vec![],
+            vec_socd: vec![],
+            vec_events: vec![],
+        };
+        let mut version: u32 = 0;
+        for (line_num, line) in &mut lines {
+            if line == "events" {
+                break;
+            }
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["version", num] => version = parse_word(num, line_num)?,
+                ["seed", num] => replay.seed = parse_word(num, line_num)?,
+                ["mode", "Classic"] => replay.game_mode = GameMode::Classic,
+                ["mode", "Rotatris"] => replay.game_mode = GameMode::Rotatris,
+                ["players", num] => replay.num_players = parse_word(num, line_num)?,
+                ["level", num] => replay.starting_level = parse_word(num, line_num)?,
+                ["ghost_pieces", flag] => {
+                    replay.settings.ghost_pieces_state = parse_flag(flag, line_num)?
+                }
+                ["board_width_per_player", num] => {
+                    replay.settings.board_width_per_player = parse_word(num, line_num)?
+                }
+                ["board_width_constant", num] => {
+                    replay.settings.board_width_constant = parse_word(num, line_num)?
+                }
+                ["elimination", flag] => {
+                    replay.settings.elimination_state = parse_flag(flag, line_num)?
+                }
+                ["initial_rotation", flag] => {
+                    replay.settings.initial_rotation_state = parse_flag(flag, line_num)?
+                }
+                ["das", player, big, little] => {
+                    if parse_word::<usize>(player, line_num)? != replay.vec_das.len() {
+                        return Err(format!("line {}: das is out of order", line_num + 1));
+                    }
+                    replay
+                        .vec_das
+                        .push((parse_word(big, line_num)?, parse_word(little, line_num)?));
+                }
+                ["socd", player, policy] => {
+                    if parse_word::<usize>(player, line_num)? != replay.vec_socd.len() {
+                        return Err(format!("line {}: socd is out of order", line_num + 1));
+                    }
+                    replay.vec_socd.push(parse_socd(policy, line_num)?);
+                }
+                _ => return Err(format!("line {}: unknown \"{}\"", line_num + 1, line)),
+            }
+        }
+        if version != REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} can't be played back, only version {}",
+                version, REPLAY_VERSION
+            ));
+        }
+        if replay.game_mode == GameMode::None || replay.num_players == 0 {
+            return Err("missing the mode or number of players".to_string());
+        }
+        // replays from before profiles had das settings use the defaults
+        replay.vec_das.resize(
+            replay.num_players as usize,
+            (DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE),
+        );
+        replay
+            .vec_socd
+            .resize(replay.num_players as usize, SocdPolicy::default());
+        for (line_num, line) in lines {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+            let frame: u32 = parse_word(words[0], line_num)?;
+            let event = match &words[1..] {
+                ["input", player, "press", movement] => ReplayEvent::Input(
+                    parse_word(player, line_num)?,
+                    InputEvent::Press(parse_movement(movement, line_num)?),
+                ),
+                ["input", player, "release", movement] => ReplayEvent::Input(
+                    parse_word(player, line_num)?,
+                    InputEvent::Release(parse_movement(movement, line_num)?),
+                ),
+                ["input", player, "start", flag] => ReplayEvent::Input(
+                    parse_word(player, line_num)?,
+                    InputEvent::Start(parse_flag(flag, line_num)?),
+                ),
+                ["input", player, "select", flag] => ReplayEvent::Input(
+                    parse_word(player, line_num)?,
+                    InputEvent::Select(parse_flag(flag, line_num)?),
+                ),
+                ["reset"] => ReplayEvent::ResetInputs,
+                ["join", big, little] => ReplayEvent::Join(
+                    parse_word(big, line_num)?,
+                    parse_word(little, line_num)?,
+                    SocdPolicy::default(),
+                ),
+                ["join", big, little, policy] => ReplayEvent::Join(
+                    parse_word(big, line_num)?,
+                    parse_word(little, line_num)?,
+                    parse_socd(policy, line_num)?,
+                ),
+                ["elimination", flag] => ReplayEvent::Elimination(parse_flag(flag, line_num)?),
+                _ => return Err(format!("line {}: unknown \"{}\"", line_num + 1, line)),
+            };
+            replay.vec_events.push((frame, event));
+        }
+        Ok(replay)
+    }
+
+    fn to_text(&self) -> String {
+        let mut text = format!("{}\nversion {}\n", REPLAY_HEADER, REPLAY_VERSION);
+        text.push_str(&format!("seed {}\n", self.seed));
+        text.push_str(&format!("mode {:?}\n", self.game_mode));
+        text.push_str(&format!("players {}\n", self.num_players));
+        text.push_str(&format!("level {}\n", self.starting_level));
+        text.push_str(&format!(
+            "ghost_pieces {}\n",
+            self.settings.ghost_pieces_state as u8
+        ));
+        text.push_str(&format!(
+            "board_width_per_player {}\n",
+            self.settings.board_width_per_player
+        ));
+        text.push_str(&format!(
+            "board_width_constant {}\n",
+            self.settings.board_width_constant
+        ));
+        text.push_str(&format!(
+            "elimination {}\n",
+            self.settings.elimination_state as u8
+        ));
+        text.push_str(&format!(
+            "initial_rotation {}\n",
+            self.settings.initial_rotation_state as u8
+        ));
+        for (player, das) in self.vec_das.iter().enumerate() {
+            text.push_str(&format!("das {} {} {}\n", player, das.0, das.1));
+        }
+        for (player, socd) in self.vec_socd.iter().enumerate() {
+            text.push_str(&format!("socd {} {}\n", player, *socd as u8));
+        }
+        text.push_str("events\n");
+        for (frame, event) in self.vec_events.iter() {
+            let event_text = match event {
+                ReplayEvent::Input(player, InputEvent::Press(movement)) => {
+                    format!("input {} press {}", player, *movement as u8)
+                }
+                ReplayEvent::Input(player, InputEvent::Release(movement)) => {
+                    format!("input {} release {}", player, *movement as u8)
+                }
+                ReplayEvent::Input(player, InputEvent::Start(pressed)) => {
+                    format!("input {} start {}", player, *pressed as u8)
+                }
+                ReplayEvent::Input(player, InputEvent::Select(pressed)) => {
+                    format!("input {} select {}", player, *pressed as u8)
+                }
+                ReplayEvent::ResetInputs => "reset".to_string(),
+                ReplayEvent::Join(das_big, das_little, socd) => {
+                    format!("join {} {} {}", das_big, das_little, *socd as u8)
+                }
+                ReplayEvent::Elimination(on) => format!("elimination {}", *on as u8),
+            };
+            text.push_str(&format!("{} {}\n", frame, event_text));
+        }
+        text
+    }
+}
+
+fn parse_word<T: FromStr>(word: &str, line_num: usize) -> Result<T, String> {
+    word.parse()
+        .map_err(|_| format!("line {}: bad number \"{}\"", line_num + 1, word))
+}
+
+fn parse_flag(word: &str, line_num: usize) -> Result<bool, String> {
+    Ok(parse_word::<u8>(word, line_num)? != 0)
+}
+
+fn parse_socd(word: &str, line_num: usize) -> Result<SocdPolicy, String> {
+    SocdPolicy::from_u8(parse_word(word, line_num)?)
+        .ok_or_else(|| format!("line {}: unknown socd policy \"{}\"", line_num + 1, word))
+}
+
+fn parse_movement(word: &str, line_num: usize) -> Result<Movement, String> {
+    Movement::try_from(parse_word::<u8>(word, line_num)?)
+        .map_err(|e| format!("line {}: {}", line_num + 1, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays_read_back_what_was_written() {
+        let mut replay = Replay {
+            seed: 1234,
+            game_mode: GameMode::Classic,
+            num_players: 2,
+            starting_level: 9,
+            settings: GameSettings::default(),
+            vec_das: vec![(10, 3), (DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE)],
+            vec_socd: vec![SocdPolicy::Neutral, SocdPolicy::LastInputWins],
+            vec_events: vec![],
+        };
+        replay.settings.elimination_state = true;
+        replay.settings.initial_rotation_state = true;
+        replay.record(
+            3,
+            ReplayEvent::Input(1, InputEvent::Press(Movement::RotateCw)),
+        );
+        replay.record(
+            5,
+            ReplayEvent::Input(1, InputEvent::Release(Movement::RotateCw)),
+        );
+        replay.record(5, ReplayEvent::Input(0, InputEvent::Start(true)));
+        replay.record(6, ReplayEvent::ResetInputs);
+        replay.record(80, ReplayEvent::Join(20, 6, SocdPolicy::FirstInputWins));
+        replay.record(90, ReplayEvent::Elimination(false));
+
+        let loaded = Replay::from_text(&replay.to_text()).unwrap();
+        assert_eq!(loaded.seed, 1234);
+        assert_eq!(loaded.game_mode, GameMode::Classic);
+        assert_eq!(loaded.starting_level, 9);
+        assert!(loaded.settings == replay.settings);
+        assert_eq!(loaded.vec_das, replay.vec_das);
+        assert_eq!(loaded.vec_socd, replay.vec_socd);
+        assert_eq!(loaded.to_text(), replay.to_text());
+
+        assert!(Replay::from_text(
+            "tetrisn-t replay\nversion 1\nmode Classic\nplayers 1\nevents\n7 input 0 press 99\n"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use ggez::event::KeyCode;
+use ggez::graphics::{self, Color, DrawParam, Font, PxScale, Text, TextFragment};
+use ggez::mint::Point2;
+use ggez::Context;
+
+use crate::game::stats::{format_frames, GameStats, PlayerStats, SHAPE_NAMES};
+use crate::game::GameMode;
+use crate::inputs::{Input, MenuKeys};
+use crate::menu::menuhelpers::{MenuItem, MenuItemTrigger};
+use crate::menu::menuhelpers::{SUB_TEXT_SCALE_DOWN, TEXT_SCALE_DOWN};
+use crate::theme::Theme;
+
+pub const NUM_INITIALS: usize = 3;
+// the per-player split can get long, so it's a bit smaller than the rest
+const PLAYERS_TEXT_SCALE_DOWN: f32 = 35.0;
+
+enum ResultsMenuItemId {
+    Initials,
+    SaveReplay,
+    PlayAgain,
+    QuitToMenu,
+}
+
+// what the results screen needs to know about each player
+pub struct PlayerResult {
+    pub player_num: u8,
+    pub survival_frames: u32,
+}
+
+pub struct ResultsScreen {
+    // logic
+    pub input: Input,
+    selection: usize,
+    vec_menu_items: Vec<MenuItem>,
+    pub initials: [char; NUM_INITIALS],
+    initials_cursor: usize,
+    replay_saved: bool,
+    // drawing
+    game_over_text: Text,
+    summary_text: Text,
+    players_text: Text,
+    status_text: Text,
+    text_color: Color,
+    help_color: Color,
+    background_color: Color,
+    window_height: f32,
+}
+
+impl ResultsScreen {
+    pub fn new(initials: &str, theme: &Theme, window_dimensions: (f32, f32)) -> Self {
+        let mut arr_initials = ['A'; NUM_INITIALS];
+        for (initial, c) in arr_initials.iter_mut().zip(initials.chars()) {
+            if c.is_ascii_alphabetic() {
+                *initial = c.to_ascii_uppercase();
+            }
+        }
+        let mut vec_menu_items: Vec<MenuItem> = vec![
+            MenuItem::new_customvalue(
+                "Initials: ",
+                ResultsMenuItemId::Initials as u8,
+                &Self::initials_str(&arr_initials, None),
+                0,
+                26,
+                MenuItemTrigger::None,
+                theme,
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Save Replay",
+                ResultsMenuItemId::SaveReplay as u8,
+                MenuItemTrigger::SaveReplay,
+                theme,
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Play Again",
+                ResultsMenuItemId::PlayAgain as u8,
+                MenuItemTrigger::Restart,
+                theme,
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Quit to Menu",
+                ResultsMenuItemId::QuitToMenu as u8,
+                MenuItemTrigger::QuitToMenu,
+                theme,
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+        ];
+        vec_menu_items[0].set_select(true);
+        let mut results = Self {
+            // logic
+            input: Input::new(),
+            selection: 0,
+            vec_menu_items,
+            initials: arr_initials,
+            initials_cursor: 0,
+            replay_saved: false,
+            // drawing
+            game_over_text: Text::new(
+                TextFragment::new("Game Over!").color(theme.menu_text_color()),
+            ),
+            summary_text: Text::default(),
+            players_text: Text::default(),
+            status_text: Text::default(),
+            text_color: theme.menu_text_color(),
+            help_color: theme.menu_help_color(),
+            background_color: theme.menu_background_color(),
+            window_height: window_dimensions.1,
+        };
+        results.update_initials_text();
+        results
+    }
+
+    fn initials_str(initials: &[char; NUM_INITIALS], cursor: Option<usize>) -> String {
+        let mut initials_str = String::new();
+        for (idx, initial) in initials.iter().enumerate() {
+            if Some(idx) == cursor {
+                initials_str.push_str(&format!("[{}]", initial));
+            } else {
+                initials_str.push(*initial);
+            }
+        }
+        initials_str
+    }
+
+    pub fn get_initials(&self) -> String {
+        self.initials.iter().collect()
+    }
+
+    // builds all the text from the final state of the game
+    pub fn set_results(
+        &mut self,
+        game_mode: GameMode,
+        stats: &GameStats,
+        frames_played: u32,
+        vec_player_results: &mut [PlayerResult],
+        rank_by_survival: bool,
+    ) {
+        let total = stats.total();
+        let lines_name = match game_mode {
+            GameMode::Rotatris => "Rings",
+            _ => "Lines",
+        };
+        let mut summary = format!(
+            "Time: {}   Pieces: {}   PPS: {:.2}   Inputs: {}\n",
+            format_frames(frames_played),
+            total.pieces_placed,
+            total.pieces_per_second(frames_played),
+            total.total_inputs()
+        );
+        summary.push_str(&format!(
+            "{}: {}   Score: {}\n",
+            lines_name, total.lines, total.score
+        ));
+        summary.push_str(&format!(
+            "Singles: {}   Doubles: {}   Triples: {}   Tetrisn'ts: {}\n",
+            total.clears[0], total.clears[1], total.clears[2], total.clears[3]
+        ));
+        summary.push_str(&format!(
+            "Tetrisn't Rate: {:.0}%\n",
+            total.tetrisnt_rate() * 100.0
+        ));
+        summary.push_str(&Self::shape_counts_str(&total));
+        self.summary_text = Text::new(TextFragment::new(summary).color(self.text_color));
+
+        self.players_text = Text::default();
+        if vec_player_results.len() > 1 {
+            if rank_by_survival {
+                vec_player_results.sort_by_key(|result| std::cmp::Reverse(result.survival_frames));
+            }
+            for (place, result) in vec_player_results.iter().enumerate() {
+                let player_stats = &stats.vec_player_stats[result.player_num as usize];
+                let mut line = if rank_by_survival {
+                    format!(
+                        "{}. P{}  Survived {}",
+                        place + 1,
+                        result.player_num + 1,
+                        format_frames(result.survival_frames)
+                    )
+                } else {
+                    format!("P{}", result.player_num + 1)
+                };
+                line.push_str(&format!(
+                    "  Score {}  {} {}  Pieces {}  PPS {:.2}  Tetrisn'ts {}  Inputs {}\n",
+                    player_stats.score,
+                    lines_name,
+                    player_stats.lines,
+                    player_stats.pieces_placed,
+                    player_stats.pieces_per_second(frames_played),
+                    player_stats.clears[3],
+                    player_stats.total_inputs()
+                ));
+                self.players_text
+                    .add(TextFragment::new(line).color(self.text_color));
+            }
+        }
+        self.resize_event(self.window_height);
+    }
+
+    fn shape_counts_str(player_stats: &PlayerStats) -> String {
+        let mut shape_counts = String::new();
+        for (name, count) in SHAPE_NAMES.iter().zip(player_stats.shape_counts.iter()) {
+            shape_counts.push_str(&format!("{}: {}   ", name, count));
+        }
+        shape_counts.trim_end().to_string()
+    }
+
+    pub fn set_replay_status(&mut self, status: Result<String, String>) {
+        self.status_text = match status {
+            Ok(path) => {
+                self.replay_saved = true;
+                Text::new(
+                    TextFragment::new(format!("Replay saved to {}", path)).color(self.text_color),
+                )
+            }
+            Err(e) => Text::new(
+                TextFragment::new(format!("[!] Failed to save replay: {}", e))
+                    .color(self.help_color),
+            ),
+        };
+        self.status_text.set_font(
+            Font::default(),
+            PxScale::from(self.window_height / SUB_TEXT_SCALE_DOWN),
+        );
+    }
+
+    pub fn update(&mut self) -> MenuItemTrigger {
+        let mut trigger = MenuItemTrigger::None;
+        let on_initials =
+            self.vec_menu_items[self.selection].id == ResultsMenuItemId::Initials as u8;
+
+        if self.input.keydown_rotate_ccw.1 {
+            // escape was pressed
+            trigger = MenuItemTrigger::QuitToMenu;
+        } else if on_initials && (self.input.keydown_left.1 || self.input.keydown_right.1) {
+            // cycle the letter under the cursor
+            let letter = self.initials[self.initials_cursor] as u8 - b'A';
+            let letter = if self.input.keydown_right.1 {
+                (letter + 1) % 26
+            } else {
+                (letter + 25) % 26
+            };
+            self.initials[self.initials_cursor] = (b'A' + letter) as char;
+        } else if on_initials && self.input.keydown_start.1 {
+            // confirm the letter and move on to the next one
+            self.initials_cursor = (self.initials_cursor + 1) % NUM_INITIALS;
+        } else if self.input.keydown_start.1 {
+            trigger = self.vec_menu_items[self.selection].trigger.clone();
+        }
+
+        if self.input.keydown_down.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = (self.selection + 1) % self.vec_menu_items.len();
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if self.input.keydown_up.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = if self.selection == 0 {
+                self.vec_menu_items.len() - 1
+            } else {
+                self.selection - 1
+            };
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+        self.update_initials_text();
+        self.input.was_just_pressed_setfalse();
+
+        if trigger == MenuItemTrigger::SaveReplay && self.replay_saved {
+            return MenuItemTrigger::None;
+        }
+        trigger
+    }
+
+    // typing letters fills in the initials when they're selected, and those keys (and Backspace) don't also work the menu,
+    // so a menu key on a letter or on Backspace doesn't move away or leave the screen halfway through
+    pub fn key_down_event(&mut self, keycode: KeyCode, menu_keys: &MenuKeys) {
+        if self.vec_menu_items[self.selection].id == ResultsMenuItemId::Initials as u8 {
+            if keycode == KeyCode::Back {
+                self.initials_cursor = (self.initials_cursor + NUM_INITIALS - 1) % NUM_INITIALS;
+                self.update_initials_text();
+                return;
+            }
+            if let Some(letter) = Self::letter_from_keycode(keycode) {
+                self.initials[self.initials_cursor] = letter;
+                self.initials_cursor = (self.initials_cursor + 1) % NUM_INITIALS;
+                self.update_initials_text();
+                return;
+            }
+        }
+        self.input.update_menu_keydown(keycode, menu_keys);
+    }
+
+    fn letter_from_keycode(keycode: KeyCode) -> Option<char> {
+        let letter = format!("{:?}", keycode);
+        if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_alphabetic()) {
+            letter.chars().next()
+        } else {
+            None
+        }
+    }
+
+    fn update_initials_text(&mut self) {
+        for item in self.vec_menu_items.iter_mut() {
+            if item.id == ResultsMenuItemId::Initials as u8 {
+                item.text.fragments_mut()[1].text =
+                    Self::initials_str(&self.initials, Some(self.initials_cursor));
+            }
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) {
+        graphics::clear(ctx, self.background_color);
+        let window_dimensions = graphics::size(ctx);
+
+        self.draw_text(ctx, &self.game_over_text, 0.05, &window_dimensions);
+        self.draw_text(ctx, &self.summary_text, 0.22, &window_dimensions);
+        self.draw_text(ctx, &self.players_text, 0.5, &window_dimensions);
+        let num_menu_items_to_draw = self.vec_menu_items.len();
+        for (index, item) in self.vec_menu_items.iter().enumerate() {
+            self.draw_text(
+                ctx,
+                &item.text,
+                0.72 + 0.06 * index as f32,
+                &window_dimensions,
+            );
+        }
+        self.draw_text(
+            ctx,
+            &self.status_text,
+            0.72 + 0.06 * num_menu_items_to_draw as f32,
+            &window_dimensions,
+        );
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text_var: &Text,
+        vertical_position: f32,
+        window_dimensions: &(f32, f32),
+    ) {
+        let text_var_dimensions = text_var.dimensions(ctx);
+        graphics::draw(
+            ctx,
+            text_var,
+            DrawParam::new().dest(Point2::from_slice(&[
+                (window_dimensions.0 - text_var_dimensions.w) / 2.0,
+                (window_dimensions.1 - text_var_dimensions.h) * vertical_position,
+            ])),
+        )
+        .unwrap();
+    }
+
+    pub fn resize_event(&mut self, height: f32) {
+        self.window_height = height;
+        for item in self.vec_menu_items.iter_mut() {
+            item.resize(height);
+        }
+        self.game_over_text
+            .set_font(Font::default(), PxScale::from(height / TEXT_SCALE_DOWN));
+        self.summary_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.players_text.set_font(
+            Font::default(),
+            PxScale::from(height / PLAYERS_TEXT_SCALE_DOWN),
+        );
+        self.status_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::theme;
+
+    #[test]
+    fn backspace_back_key_fixes_initials_instead_of_quitting() {
+        let menu_keys = MenuKeys {
+            back: KeyCode::Back,
+            ..MenuKeys::default()
+        };
+        let mut results = ResultsScreen::new("ABC", &theme::CLASSIC, (800.0, 600.0));
+        results.key_down_event(KeyCode::X, &menu_keys);
+        results.key_down_event(KeyCode::Back, &menu_keys);
+        results.key_down_event(KeyCode::Y, &menu_keys);
+        assert_eq!(results.update(), MenuItemTrigger::None);
+        assert_eq!(results.get_initials(), "YBC");
+
+        // off the initials, the same key goes back like it should
+        results.input.update_menu_keydown(KeyCode::Down, &menu_keys);
+        results.update();
+        results.key_down_event(KeyCode::Back, &menu_keys);
+        assert_eq!(results.update(), MenuItemTrigger::QuitToMenu);
+    }
+}
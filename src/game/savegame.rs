@@ -0,0 +1,174 @@
+use rand_pcg::Pcg64Mcg;
+use serde::{Deserialize, Serialize};
+
+use crate::config::report_corrupt_file;
+use crate::game::board::{BoardDim, BoardHandler};
+use crate::game::piece::Shapes;
+use crate::game::player::Player;
+use crate::game::replay::Replay;
+use crate::game::stats::{GameStats, PlayerStats};
+use crate::game::GameSettings;
+use crate::inputs::{KeyboardControlScheme, SocdPolicy};
+use crate::movement::Movement;
+use crate::profiles::Profile;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// json instead of toml like the config files since nobody edits these by hand and the rng state is a u128
+pub static SAVEGAME_FILE: &str = "savegame.json";
+// the whole game state goes in as is, so a save only loads in the version that wrote it
+pub const SAVEGAME_VERSION: u32 = 1;
+
+// everything a game in progress needs to pick up exactly where it left off;
+// the graphics are all rebuilt from this when the game is continued
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub bh: BoardHandler,
+    pub num_players: u8,
+    pub vec_players: Vec<Player>,
+    // what each player's next piece box is showing, which is Shapes::None before their first piece
+    pub vec_next_piece: Vec<Shapes>,
+    pub level: u8,
+    pub starting_level: u8,
+    pub num_cleared_lines: u16,
+    pub score: u64,
+    pub gravity_direction: Movement,
+    pub elimination_flag: bool,
+    pub settings: GameSettings,
+    pub frame_count: u32,
+    pub vec_drop_in_controls: Vec<(KeyboardControlScheme, Option<Profile>, SocdPolicy)>,
+    pub vec_profile_results: Vec<(String, PlayerStats, u32)>,
+    pub board_width_per_player: BoardDim,
+    pub rng: Pcg64Mcg,
+    pub stats: GameStats,
+    pub replay: Replay,
+    pub initials: String,
+}
+
+pub fn exists(dir: &Path) -> bool {
+    dir.join(SAVEGAME_FILE).is_file()
+}
+
+pub fn save(dir: &Path, saved_game: &SavedGame) -> Result<PathBuf, String> {
+    let string = match serde_json::to_string(saved_game) {
+        Ok(string) => string,
+        Err(e) => return Err(format!("couldn't serialize the game: {}", e)),
+    };
+    if let Err(e) = fs::create_dir_all(dir) {
+        return Err(format!("couldn't create {}: {}", dir.display(), e));
+    }
+    let path = dir.join(SAVEGAME_FILE);
+    match fs::write(&path, string) {
+        Ok(()) => Ok(path),
+        Err(e) => Err(format!("couldn't write {}: {}", path.display(), e)),
+    }
+}
+
+// a save can only be continued once, so it's removed as it's loaded
+pub fn take(dir: &Path) -> Option<SavedGame> {
+    let path = dir.join(SAVEGAME_FILE);
+    let string = match fs::read_to_string(&path) {
+        Ok(string) => string,
+        Err(e) => {
+            println!("[!] failed to read {}: {}", path.display(), e);
+            return None;
+        }
+    };
+    let saved_game: SavedGame = match serde_json::from_str(&string) {
+        Ok(saved_game) => saved_game,
+        Err(e) => {
+            report_corrupt_file(&path, &e.to_string());
+            return None;
+        }
+    };
+    if saved_game.version != SAVEGAME_VERSION {
+        report_corrupt_file(
+            &path,
+            &format!(
+                "it's from save version {}, this build only continues version {}",
+                saved_game.version, SAVEGAME_VERSION
+            ),
+        );
+        return None;
+    }
+    if let Err(e) = fs::remove_file(&path) {
+        println!("[!] failed to remove {}: {}", path.display(), e);
+    }
+    Some(saved_game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TestDir;
+    use crate::game::tile::TileSkin;
+    use crate::game::{GameMode, GameOptions};
+    use crate::inputs::GamepadControlScheme;
+    use crate::theme::Theme;
+    use rand::{RngCore, SeedableRng};
+
+    #[test]
+    fn saved_games_continue_exactly_once() {
+        let dir = TestDir::new("savegame");
+        let game_options = GameOptions {
+            num_players: 2,
+            starting_level: 5,
+            game_mode: GameMode::Classic,
+            vec_controls: vec![(None, true), (None, true)],
+            vec_profiles: vec![None, None],
+            vec_gamepad_controls: vec![GamepadControlScheme::default(); 2],
+            vec_gamepad_ids: vec![None, None],
+            vec_socd: vec![SocdPolicy::default(); 2],
+            vec_drop_in_controls: vec![],
+            settings: GameSettings::default(),
+            seed: Some(7),
+            initials: "CAT".to_string(),
+            theme: Theme::default(),
+            tile_skin: TileSkin::default(),
+        };
+        let mut rng = Pcg64Mcg::seed_from_u64(7);
+        let mut bh = BoardHandler::new(14, 20, 2, GameMode::Classic);
+        let spawn_columns = bh.get_spawn_columns();
+        let vec_players: Vec<Player> = (0..2)
+            .map(|p| Player::new(p, (None, true), None, spawn_columns[p as usize], &mut rng))
+            .collect();
+        bh.attempt_piece_spawn(0, spawn_columns[0], vec_players[0].next_piece_shape);
+        let mut saved_game = SavedGame {
+            version: SAVEGAME_VERSION,
+            bh,
+            num_players: 2,
+            vec_players,
+            vec_next_piece: vec![Shapes::T, Shapes::None],
+            level: 5,
+            starting_level: 5,
+            num_cleared_lines: 12,
+            score: 4800,
+            gravity_direction: Movement::Down,
+            elimination_flag: false,
+            settings: GameSettings::default(),
+            frame_count: 600,
+            vec_drop_in_controls: vec![],
+            vec_profile_results: vec![],
+            board_width_per_player: 4,
+            rng,
+            stats: GameStats::new(2),
+            replay: Replay::new(&game_options, 7),
+            initials: "CAT".to_string(),
+        };
+        save(&dir, &saved_game).unwrap();
+        assert!(exists(&dir));
+        let mut loaded = take(&dir).unwrap();
+        assert!(!exists(&dir));
+        assert!(take(&dir).is_none());
+
+        assert_eq!(loaded.score, 4800);
+        assert_eq!(loaded.frame_count, 600);
+        assert_eq!(loaded.vec_players.len(), 2);
+        assert!(loaded.vec_next_piece[0] == Shapes::T);
+        assert!(loaded.bh.get_shape_from_player(0) == saved_game.bh.get_shape_from_player(0));
+        // the pieces keep coming in the same order they would have
+        assert_eq!(loaded.rng.next_u64(), saved_game.rng.next_u64());
+    }
+}
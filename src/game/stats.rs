@@ -0,0 +1,196 @@
+use serde::{Deserialize, Serialize};
+
+use crate::control::DESIRED_FPS;
+use crate::game::board::BoardDim;
+use crate::game::piece::Shapes;
+use crate::inputs::InputEvent;
+use crate::movement::Movement;
+
+pub const NUM_SHAPES: usize = 7;
+pub const SHAPE_NAMES: [&str; NUM_SHAPES] = ["I", "O", "T", "J", "S", "L", "Z"];
+
+// left, down, right, start, rotate ccw, rotate cw, board ccw, board cw; the order the input overlay shows them in
+pub const NUM_COUNTED_INPUTS: usize = 8;
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub pieces_placed: u32,
+    // indexed by `Shapes as usize`
+    pub shape_counts: [u32; NUM_SHAPES],
+    // singles, doubles, triples, tetrisn'ts
+    pub clears: [u32; 4],
+    pub lines: u32,
+    pub score: u64,
+    // presses that reached the player, indexed like NUM_COUNTED_INPUTS
+    #[serde(default)]
+    pub input_counts: [u32; NUM_COUNTED_INPUTS],
+}
+
+impl PlayerStats {
+    pub fn add_piece(&mut self, shape: Shapes) {
+        if shape == Shapes::None {
+            return;
+        }
+        self.pieces_placed += 1;
+        self.shape_counts[shape as usize] += 1;
+    }
+
+    pub fn add_clear(&mut self, lines: BoardDim, score: u32) {
+        if lines == 0 || lines > 4 {
+            println!("[!] tried to add a clear of {} lines to the stats", lines);
+            return;
+        }
+        self.clears[lines as usize - 1] += 1;
+        self.lines += lines as u32;
+        self.score += score as u64;
+    }
+
+    // only presses count; letting go and the piece's own repeats don't
+    pub fn add_input(&mut self, input_event: InputEvent) {
+        let index = match input_event {
+            InputEvent::Press(Movement::Left) => 0,
+            InputEvent::Press(Movement::Down) => 1,
+            InputEvent::Press(Movement::Right) => 2,
+            InputEvent::Start(true) => 3,
+            InputEvent::Press(Movement::RotateCcw) => 4,
+            InputEvent::Press(Movement::RotateCw) => 5,
+            InputEvent::Press(Movement::BoardCcw) => 6,
+            InputEvent::Press(Movement::BoardCw) => 7,
+            _ => return,
+        };
+        self.input_counts[index] += 1;
+    }
+
+    pub fn total_inputs(&self) -> u32 {
+        self.input_counts.iter().sum()
+    }
+
+    pub fn pieces_per_second(&self, frames_played: u32) -> f32 {
+        if frames_played == 0 {
+            return 0.0;
+        }
+        self.pieces_placed as f32 * DESIRED_FPS as f32 / frames_played as f32
+    }
+
+    // the share of lines that were cleared 4 at a time
+    pub fn tetrisnt_rate(&self) -> f32 {
+        if self.lines == 0 {
+            return 0.0;
+        }
+        (self.clears[3] * 4) as f32 / self.lines as f32
+    }
+
+    // what was added on top of `baseline`, for when someone takes over a lane partway through a game
+    pub fn since(&self, baseline: &PlayerStats) -> PlayerStats {
+        let mut stats = self.clone();
+        stats.pieces_placed -= baseline.pieces_placed;
+        for (count, baseline_count) in stats
+            .shape_counts
+            .iter_mut()
+            .zip(baseline.shape_counts.iter())
+        {
+            *count -= baseline_count;
+        }
+        for (count, baseline_count) in stats.clears.iter_mut().zip(baseline.clears.iter()) {
+            *count -= baseline_count;
+        }
+        for (count, baseline_count) in stats
+            .input_counts
+            .iter_mut()
+            .zip(baseline.input_counts.iter())
+        {
+            *count -= baseline_count;
+        }
+        stats.lines -= baseline.lines;
+        stats.score -= baseline.score;
+        stats
+    }
+
+    fn add(&mut self, other: &PlayerStats) {
+        self.pieces_placed += other.pieces_placed;
+        for (count, other_count) in self.shape_counts.iter_mut().zip(other.shape_counts.iter()) {
+            *count += other_count;
+        }
+        for (count, other_count) in self.clears.iter_mut().zip(other.clears.iter()) {
+            *count += other_count;
+        }
+        for (count, other_count) in self.input_counts.iter_mut().zip(other.input_counts.iter()) {
+            *count += other_count;
+        }
+        self.lines += other.lines;
+        self.score += other.score;
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GameStats {
+    pub vec_player_stats: Vec<PlayerStats>,
+}
+
+impl GameStats {
+    pub fn new(num_players: u8) -> Self {
+        Self {
+            vec_player_stats: vec![PlayerStats::default(); num_players as usize],
+        }
+    }
+
+    pub fn add_player(&mut self) {
+        self.vec_player_stats.push(PlayerStats::default());
+    }
+
+    pub fn total(&self) -> PlayerStats {
+        let mut total = PlayerStats::default();
+        for player_stats in self.vec_player_stats.iter() {
+            total.add(player_stats);
+        }
+        total
+    }
+}
+
+// m:ss from a number of frames
+pub fn format_frames(frames: u32) -> String {
+    let seconds = frames / DESIRED_FPS;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn totals_and_rates() {
+        let mut stats = GameStats::new(2);
+        stats.vec_player_stats[0].add_piece(Shapes::I);
+        stats.vec_player_stats[0].add_piece(Shapes::I);
+        stats.vec_player_stats[1].add_piece(Shapes::T);
+        stats.vec_player_stats[1].add_piece(Shapes::None);
+        stats.vec_player_stats[0].add_clear(4, 1200);
+        stats.vec_player_stats[1].add_clear(1, 40);
+        stats.vec_player_stats[1].add_clear(3, 300);
+        // not a real clear, shouldn't count
+        stats.vec_player_stats[1].add_clear(0, 0);
+        stats.vec_player_stats[0].add_input(InputEvent::Press(Movement::Left));
+        stats.vec_player_stats[0].add_input(InputEvent::Press(Movement::RotateCw));
+        stats.vec_player_stats[1].add_input(InputEvent::Start(true));
+        // letting go isn't another input
+        stats.vec_player_stats[1].add_input(InputEvent::Release(Movement::Left));
+        stats.vec_player_stats[1].add_input(InputEvent::Start(false));
+
+        let total = stats.total();
+        assert_eq!(total.pieces_placed, 3);
+        assert_eq!(total.shape_counts[Shapes::I as usize], 2);
+        assert_eq!(total.shape_counts[Shapes::T as usize], 1);
+        assert_eq!(total.clears, [1, 0, 1, 1]);
+        assert_eq!(total.lines, 8);
+        assert_eq!(total.score, 1540);
+        assert!((total.tetrisnt_rate() - 0.5).abs() < f32::EPSILON);
+        assert!((total.pieces_per_second(DESIRED_FPS * 3) - 1.0).abs() < f32::EPSILON);
+        assert_eq!(stats.vec_player_stats[1].tetrisnt_rate(), 0.0);
+        assert_eq!(total.input_counts, [1, 0, 0, 1, 0, 1, 0, 0]);
+        assert_eq!(total.total_inputs(), 3);
+
+        stats.add_player();
+        assert_eq!(stats.vec_player_stats.len(), 3);
+        assert_eq!(format_frames(DESIRED_FPS * 75), "1:15");
+    }
+}
@@ -1,17 +1,37 @@
 use ggez::{graphics, Context};
+use serde::{Deserialize, Serialize};
 
 use crate::game::board::BoardDim;
 use crate::game::Shapes;
+use crate::resources;
+use crate::theme::Theme;
+
+use std::path::PathBuf;
 
 pub const NUM_PIXEL_ROWS_PER_TILEGRAPHIC: u16 = 8u16;
 
-const DARK_GRAY: (u8, u8, u8, u8) = (20u8, 20u8, 20u8, 0xffu8);
-const GRAY: (u8, u8, u8, u8) = (60u8, 60u8, 60u8, 0xffu8);
 const WHITE: (u8, u8, u8, u8) = (255u8, 255u8, 255u8, 0xffu8);
 
-// average with black
-const PLAYER_TILE_DARKEN: [f32; 3] = [0.80, 0.70, 0.50];
+type PixelColorBuf = [(u8, u8, u8, u8);
+    NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize];
+
+// tile skins are PNG sprite sheets in a resource folder, one tile tall and cut into tiles from left to right;
+// each tile they have replaces the procedural one, and a sheet that's missing or the wrong size is left out
+
+// one tile per player color (the same order as the theme's player_colors); players past the sheet get the procedural tiles
+pub static PLAYER_SKIN_FILE: &str = "tiles_players.png";
+// the empty tile, then the active, clearing, clearing tetrisn't and ghost highlights
+pub static BOARD_SKIN_FILE: &str = "tiles_board.png";
+const NUM_BOARD_SKIN_TILES: u32 = 5;
+const EMPTY_SKIN_INDEX: usize = 0;
+const ACTIVE_HIGHLIGHT_SKIN_INDEX: usize = 1;
+const CLEAR_STANDARD_HIGHLIGHT_SKIN_INDEX: usize = 2;
+const CLEAR_TETRISNT_HIGHLIGHT_SKIN_INDEX: usize = 3;
+const GHOST_HIGHLIGHT_SKIN_INDEX: usize = 4;
+
+// the colors come from the theme; these show which pixels each entry of the theme's arrays goes to
 
+// player_tile_darken (average with black)
 // [0][1][1][2][2][1][1][0]
 // [1][-][-][-][-][-][-][1]
 // [1][-][-][-][-][-][-][1]
@@ -21,9 +41,7 @@ const PLAYER_TILE_DARKEN: [f32; 3] = [0.80, 0.70, 0.50];
 // [1][-][-][-][-][-][-][1]
 // [0][1][1][2][2][1][1][0]
 
-// average with white
-const PLAYER_TILE_BRIGHTEN: [f32; 3] = [0.40, 0.25, 0.10];
-
+// player_tile_brighten (average with white)
 // [-][-][-][-][-][-][-][-]
 // [-][-][-][2][2][-][-][-]
 // [-][-][2][1][1][2][-][-]
@@ -33,9 +51,7 @@ const PLAYER_TILE_BRIGHTEN: [f32; 3] = [0.40, 0.25, 0.10];
 // [-][-][-][2][2][-][-][-]
 // [-][-][-][-][-][-][-][-]
 
-// this one is actually opacity out of 0xff, since it's drawn over the top of a player's active piece's tiles
-const PLAYER_TILE_ACTIVE_HIGHLIGHT: [u8; 3] = [0x50, 0x09, 0x00];
-
+// active_highlight; this one is actually opacity out of 0xff, since it's drawn over the top of a player's active piece's tiles
 // [0][1][1][1][1][1][1][0]
 // [1][1][1][2][2][1][1][1]
 // [1][1][2][2][2][2][1][1]
@@ -45,9 +61,7 @@ const PLAYER_TILE_ACTIVE_HIGHLIGHT: [u8; 3] = [0x50, 0x09, 0x00];
 // [1][1][1][2][2][1][1][1]
 // [0][1][1][1][1][1][1][0]
 
-// this one is actually opacity out of 0xff, since it's drawn over the top of clearing tiles' sprites
-const CLEARING_TILE_STANDARD_HIGHLIGHT: [u8; 3] = [0x60, 0x10, 0x05];
-
+// clearing_standard_highlight; this one is actually opacity out of 0xff, since it's drawn over the top of clearing tiles' sprites
 // [0][1][1][1][1][1][1][0]
 // [1][1][1][2][2][1][1][1]
 // [1][1][2][2][2][2][1][1]
@@ -57,9 +71,7 @@ const CLEARING_TILE_STANDARD_HIGHLIGHT: [u8; 3] = [0x60, 0x10, 0x05];
 // [1][1][1][2][2][1][1][1]
 // [0][1][1][1][1][1][1][0]
 
-// this one is actually opacity out of 0xff, since it's drawn over the top of clearing tiles' sprites (when 4 lines are cleared at once)
-const CLEARING_TILE_TETRISNT_HIGHLIGHT: [u8; 3] = [0xa0, 0x30, 0x10];
-
+// clearing_tetrisnt_highlight; this one is actually opacity out of 0xff, since it's drawn over the top of clearing tiles' sprites (when 4 lines are cleared at once)
 // [0][1][1][1][1][1][1][0]
 // [1][1][1][2][2][1][1][1]
 // [1][1][2][2][2][2][1][1]
@@ -69,9 +81,7 @@ const CLEARING_TILE_TETRISNT_HIGHLIGHT: [u8; 3] = [0xa0, 0x30, 0x10];
 // [1][1][1][2][2][1][1][1]
 // [0][1][1][1][1][1][1][0]
 
-// this one is also opacity out of 0xff, since it's drawn over the empty tiles the piece would go to were it to go straight down
-const GHOST_TILE_HIGHLIGHT: [u8; 4] = [0x20, 0x04, 0x00, 0x00];
-
+// ghost_highlight; this one is also opacity out of 0xff, since it's drawn over the empty tiles the piece would go to were it to go straight down
 // [0][0][0][0][0][0][0][0]
 // [0][1][1][1][1][1][1][0]
 // [0][1][2][2][2][2][1][0]
@@ -81,21 +91,40 @@ const GHOST_TILE_HIGHLIGHT: [u8; 4] = [0x20, 0x04, 0x00, 0x00];
 // [0][1][1][1][1][1][1][0]
 // [0][0][0][0][0][0][0][0]
 
-// defined player colors, otherwise it uses a generated color using BASE_PLAYER_COLOR based on player number
-const NUM_PLAYERCOLORS: u8 = 7;
-const PLAYER_RGBA: [(u8, u8, u8, u8); NUM_PLAYERCOLORS as usize] = [
-    (69u8, 125u8, 225u8, 0xffu8),
-    (240u8, 40u8, 40u8, 0xffu8),
-    (80u8, 200u8, 60u8, 0xffu8),
-    (230u8, 230u8, 50u8, 0xffu8),
-    (220u8, 150u8, 70u8, 0xffu8),
-    (125u8, 125u8, 125u8, 0xffu8),
-    (230u8, 100u8, 210u8, 0xffu8),
+// defined player colors (per theme), otherwise it uses a generated color using the theme's base_player_color based on player number
+pub const NUM_PLAYERCOLORS: u8 = 7;
+
+// with tile patterns on, each player's tiles get one of these stamped over the middle 4x4 pixels (rows, leftmost pixel first),
+// so ownership doesn't come down to color alone
+const NUM_PLAYER_PATTERNS: usize = 16;
+const PLAYER_PATTERNS: [[u8; 4]; NUM_PLAYER_PATTERNS] = [
+    [0b0000, 0b0110, 0b0110, 0b0000], // dot
+    [0b1111, 0b1001, 0b1001, 0b1111], // ring
+    [0b0000, 0b1111, 0b1111, 0b0000], // horizontal bar
+    [0b0110, 0b0110, 0b0110, 0b0110], // vertical bar
+    [0b0001, 0b0010, 0b0100, 0b1000], // slash
+    [0b1000, 0b0100, 0b0010, 0b0001], // backslash
+    [0b1001, 0b0110, 0b0110, 0b1001], // x
+    [0b1010, 0b0101, 0b1010, 0b0101], // checkers
+    [0b1111, 0b1111, 0b0000, 0b0000], // top half
+    [0b1100, 0b1100, 0b1100, 0b1100], // left half
+    [0b1001, 0b0000, 0b0000, 0b1001], // corners
+    [0b1000, 0b1000, 0b1000, 0b1111], // L
+    [0b1111, 0b0110, 0b0110, 0b0110], // T
+    [0b1000, 0b1100, 0b1110, 0b1111], // triangle
+    [0b1111, 0b0000, 0b1111, 0b0000], // horizontal stripes
+    [0b1010, 0b1010, 0b1010, 0b1010], // vertical stripes
 ];
 
-const BASE_PLAYER_COLOR: (u8, u8, u8, u8) = (25u8, 80u8, 212u8, 0xffu8);
+// past the first NUM_PLAYER_PATTERNS players, the patterns come back with 1, 2, then 4 of these pixels marked too,
+// which covers MAX_NUM_PLAYERS
+const PATTERN_MARK_PIXELS: [(u16, u16); 4] = [(1, 1), (6, 6), (1, 6), (6, 1)];
+const NUM_PATTERN_MARKS: [usize; 4] = [0, 1, 2, 4];
+
+// how far a pattern pixel is pulled away from the player's color, toward black on light colors and toward white on dark ones
+const PATTERN_SHADE: f32 = 0.6;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize)]
 pub struct Tile {
     pub empty: bool,
     pub active: bool,
@@ -125,6 +154,95 @@ impl Default for Tile {
     }
 }
 
+#[derive(Clone, Default)]
+pub struct TileSkin {
+    // indexed by player color
+    vec_player_tiles: Vec<PixelColorBuf>,
+    // either empty or NUM_BOARD_SKIN_TILES long
+    vec_board_tiles: Vec<PixelColorBuf>,
+}
+
+impl TileSkin {
+    // the first copy of each sheet in `resource_dirs`
+    pub fn load(vec_dirs: &[(&'static str, PathBuf)]) -> Self {
+        let vec_player_tiles = Self::load_sheet(vec_dirs, PLAYER_SKIN_FILE, |num_tiles| {
+            num_tiles >= 1 && num_tiles <= u8::MAX as u32 + 1
+        })
+        .unwrap_or_default();
+        let vec_board_tiles = Self::load_sheet(vec_dirs, BOARD_SKIN_FILE, |num_tiles| {
+            num_tiles == NUM_BOARD_SKIN_TILES
+        })
+        .unwrap_or_default();
+        Self {
+            vec_player_tiles,
+            vec_board_tiles,
+        }
+    }
+
+    // the tiles of `file_name`, if it's there, one tile tall, and `num_tiles_ok` with how many tiles wide it is
+    fn load_sheet(
+        vec_dirs: &[(&'static str, PathBuf)],
+        file_name: &str,
+        num_tiles_ok: fn(u32) -> bool,
+    ) -> Option<Vec<PixelColorBuf>> {
+        // no sheet is the usual case, so that's not worth a message
+        let path = resources::find_resource(vec_dirs, file_name)?;
+        let sheet = match image::open(&path) {
+            Ok(sheet) => sheet.to_rgba8(),
+            Err(e) => {
+                println!(
+                    "[!] failed to read {}, using the built-in tiles: {}",
+                    path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+        let tile_size = NUM_PIXEL_ROWS_PER_TILEGRAPHIC as u32;
+        let (width, height) = sheet.dimensions();
+        if height != tile_size || width % tile_size != 0 || !num_tiles_ok(width / tile_size) {
+            println!(
+                "[!] {} is {}x{}, which isn't a row of {}x{} tiles it can use, using the built-in tiles",
+                path.display(),
+                width,
+                height,
+                tile_size,
+                tile_size
+            );
+            return None;
+        }
+        let mut vec_tiles: Vec<PixelColorBuf> = Vec::with_capacity((width / tile_size) as usize);
+        for tile_index in 0..width / tile_size {
+            let mut pixel_color_buf: PixelColorBuf = [(0, 0, 0, 0);
+                NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize];
+            for (pixel_index, pixel) in pixel_color_buf.iter_mut().enumerate() {
+                let rgba = sheet
+                    .get_pixel(
+                        tile_index * tile_size + pixel_index as u32 % tile_size,
+                        pixel_index as u32 / tile_size,
+                    )
+                    .0;
+                *pixel = (rgba[0], rgba[1], rgba[2], rgba[3]);
+            }
+            vec_tiles.push(pixel_color_buf);
+        }
+        println!(
+            "[+] Loaded {} tiles from {}",
+            vec_tiles.len(),
+            path.display()
+        );
+        Some(vec_tiles)
+    }
+
+    fn player_tile(&self, player: u8) -> Option<&PixelColorBuf> {
+        self.vec_player_tiles.get(player as usize)
+    }
+
+    fn board_tile(&self, index: usize) -> Option<&PixelColorBuf> {
+        self.vec_board_tiles.get(index)
+    }
+}
+
 pub struct TileGraphic {
     pub image: graphics::Image,
 }
@@ -152,18 +270,39 @@ impl TileGraphic {
         buf
     }
 
-    pub fn new_empty(ctx: &mut Context) -> Self {
+    fn opaque(rgb: [u8; 3]) -> (u8, u8, u8, u8) {
+        (rgb[0], rgb[1], rgb[2], 0xff)
+    }
+
+    fn from_skin(ctx: &mut Context, pixel_color_buf: &PixelColorBuf) -> Self {
+        Self {
+            image: graphics::Image::from_rgba8(
+                ctx,
+                NUM_PIXEL_ROWS_PER_TILEGRAPHIC,
+                NUM_PIXEL_ROWS_PER_TILEGRAPHIC,
+                &TileGraphic::pack_color_buf(pixel_color_buf),
+            )
+            .expect("Failed to create tile image from the tile skin"),
+        }
+    }
+
+    pub fn new_empty(ctx: &mut Context, theme: &Theme, skin: &TileSkin) -> Self {
+        if let Some(pixel_color_buf) = skin.board_tile(EMPTY_SKIN_INDEX) {
+            return Self::from_skin(ctx, pixel_color_buf);
+        }
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
         let mut pixel_color_buf: [(u8, u8, u8, u8);
             NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize] =
-            [GRAY;
+            [Self::opaque(theme.empty_tile);
                 NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize];
         for row_index in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
             for col_index in 0..NUM_PIXEL_ROWS_PER_TILEGRAPHIC {
                 pixel_color_buf
-                    [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = DARK_GRAY;
+                    [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] =
+                    Self::opaque(theme.empty_tile_edge);
                 pixel_color_buf
-                    [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] = DARK_GRAY;
+                    [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] =
+                    Self::opaque(theme.empty_tile_edge);
                 // flipped for symmetry
             }
         }
@@ -178,19 +317,35 @@ impl TileGraphic {
         }
     }
 
-    pub fn new_player(ctx: &mut Context, player: u8) -> Self {
+    // `pattern` stamps that pattern (usually the player number) over the tile
+    pub fn new_player(
+        ctx: &mut Context,
+        player: u8,
+        pattern: Option<u8>,
+        theme: &Theme,
+        skin: &TileSkin,
+    ) -> Self {
+        if let Some(skin_tile) = skin.player_tile(player) {
+            let mut pixel_color_buf = *skin_tile;
+            if let Some(pattern) = pattern {
+                let average_color = Self::average_color(&pixel_color_buf);
+                Self::stamp_pattern(&mut pixel_color_buf, average_color, pattern);
+            }
+            return Self::from_skin(ctx, &pixel_color_buf);
+        }
         let player_color: (u8, u8, u8, u8) = if player < NUM_PLAYERCOLORS {
-            PLAYER_RGBA[player as usize]
+            Self::opaque(theme.player_colors[player as usize])
         } else {
             // procedurally generate colors beyond NUM_PLAYERCOLORS via multiplication by the player number, then avoid u8 overflow
+            let base_player_color = theme.base_player_color;
             (
-                (((player - NUM_PLAYERCOLORS + 1) as usize * BASE_PLAYER_COLOR.0 as usize) % 0xff)
+                (((player - NUM_PLAYERCOLORS + 1) as usize * base_player_color[0] as usize) % 0xff)
                     as u8,
-                (((player - NUM_PLAYERCOLORS + 1) as usize * BASE_PLAYER_COLOR.1 as usize) % 0xff)
+                (((player - NUM_PLAYERCOLORS + 1) as usize * base_player_color[1] as usize) % 0xff)
                     as u8,
-                (((player - NUM_PLAYERCOLORS + 1) as usize * BASE_PLAYER_COLOR.2 as usize) % 0xff)
+                (((player - NUM_PLAYERCOLORS + 1) as usize * base_player_color[2] as usize) % 0xff)
                     as u8,
-                BASE_PLAYER_COLOR.3,
+                0xff,
             )
         };
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
@@ -203,9 +358,9 @@ impl TileGraphic {
             for col_index in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    ((1.0 - PLAYER_TILE_DARKEN[0]) * player_color.0 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[0]) * player_color.1 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[0]) * player_color.2 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[0]) * player_color.0 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[0]) * player_color.1 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[0]) * player_color.2 as f32) as u8,
                     0xff,
                 );
             }
@@ -220,17 +375,17 @@ impl TileGraphic {
             for col_index in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.0 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.1 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.2 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.0 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.1 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.2 as f32) as u8,
                     0xff,
                 );
                 // flipped for symmetry
                 pixel_color_buf
                     [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] = (
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.0 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.1 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[1]) * player_color.2 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.0 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.1 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[1]) * player_color.2 as f32) as u8,
                     0xff,
                 );
             }
@@ -240,17 +395,17 @@ impl TileGraphic {
             for col_index in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.0 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.1 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.2 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.0 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.1 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.2 as f32) as u8,
                     0xff,
                 );
                 // flipped for symmetry
                 pixel_color_buf
                     [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] = (
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.0 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.1 as f32) as u8,
-                    ((1.0 - PLAYER_TILE_DARKEN[2]) * player_color.2 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.0 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.1 as f32) as u8,
+                    ((1.0 - theme.player_tile_darken[2]) * player_color.2 as f32) as u8,
                     0xff,
                 );
             }
@@ -261,14 +416,14 @@ impl TileGraphic {
             for col_index in &[3, 4] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[0] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[0]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[0] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[0]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[0] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[0]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[0] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[0]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[0] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[0]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[0] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[0]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
@@ -279,28 +434,28 @@ impl TileGraphic {
             for col_index in &[3, 4] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
                 // flipped for symmetry
                 pixel_color_buf
                     [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[1] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[1]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[1] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[1]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
@@ -311,28 +466,28 @@ impl TileGraphic {
             for col_index in &[3, 4] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
                 // flipped for symmetry
                 pixel_color_buf
                     [(row_index + col_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
@@ -342,19 +497,22 @@ impl TileGraphic {
             for col_index in &[2, 5] {
                 pixel_color_buf
                     [(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] = (
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.0 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.0 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.0 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.0 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.1 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.1 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.1 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.1 as f32)
                         as u8,
-                    (PLAYER_TILE_BRIGHTEN[2] * WHITE.2 as f32
-                        + (1.0 - PLAYER_TILE_BRIGHTEN[2]) * player_color.2 as f32)
+                    (theme.player_tile_brighten[2] * WHITE.2 as f32
+                        + (1.0 - theme.player_tile_brighten[2]) * player_color.2 as f32)
                         as u8,
                     0xff,
                 );
             }
         }
+        if let Some(pattern) = pattern {
+            Self::stamp_pattern(&mut pixel_color_buf, player_color, pattern);
+        }
 
         Self {
             image: graphics::Image::from_rgba8(
@@ -367,7 +525,66 @@ impl TileGraphic {
         }
     }
 
-    pub fn new_active_highlight(ctx: &mut Context) -> Self {
+    // for picking a pattern shade that stands out on a skin's tile
+    fn average_color(pixel_color_buf: &PixelColorBuf) -> (u8, u8, u8, u8) {
+        let mut sums = (0usize, 0usize, 0usize);
+        for pixel in pixel_color_buf.iter() {
+            sums.0 += pixel.0 as usize;
+            sums.1 += pixel.1 as usize;
+            sums.2 += pixel.2 as usize;
+        }
+        let num_pixels = pixel_color_buf.len();
+        (
+            (sums.0 / num_pixels) as u8,
+            (sums.1 / num_pixels) as u8,
+            (sums.2 / num_pixels) as u8,
+            0xff,
+        )
+    }
+
+    fn stamp_pattern(
+        pixel_color_buf: &mut PixelColorBuf,
+        player_color: (u8, u8, u8, u8),
+        pattern: u8,
+    ) {
+        let light_flag =
+            299 * player_color.0 as u32 + 587 * player_color.1 as u32 + 114 * player_color.2 as u32
+                > 127 * 1000;
+        let shade = |channel: u8| -> u8 {
+            if light_flag {
+                ((1.0 - PATTERN_SHADE) * channel as f32) as u8
+            } else {
+                (PATTERN_SHADE * WHITE.0 as f32 + (1.0 - PATTERN_SHADE) * channel as f32) as u8
+            }
+        };
+        let pattern_color = (
+            shade(player_color.0),
+            shade(player_color.1),
+            shade(player_color.2),
+            0xff,
+        );
+        let rows = PLAYER_PATTERNS[pattern as usize % NUM_PLAYER_PATTERNS];
+        for (row_index, row) in rows.iter().enumerate() {
+            for col_index in 0..4 {
+                if row & (0b1000 >> col_index) != 0 {
+                    pixel_color_buf[(row_index + 2) * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize
+                        + col_index
+                        + 2] = pattern_color;
+                }
+            }
+        }
+        let num_marks =
+            NUM_PATTERN_MARKS[(pattern as usize / NUM_PLAYER_PATTERNS) % NUM_PATTERN_MARKS.len()];
+        for (row_index, col_index) in PATTERN_MARK_PIXELS.iter().take(num_marks) {
+            pixel_color_buf[(row_index * NUM_PIXEL_ROWS_PER_TILEGRAPHIC + col_index) as usize] =
+                pattern_color;
+        }
+    }
+
+    pub fn new_active_highlight(ctx: &mut Context, theme: &Theme, skin: &TileSkin) -> Self {
+        if let Some(pixel_color_buf) = skin.board_tile(ACTIVE_HIGHLIGHT_SKIN_INDEX) {
+            return Self::from_skin(ctx, pixel_color_buf);
+        }
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
         let mut pixel_color_buf: [(u8, u8, u8, u8);
             NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize] =
@@ -378,9 +595,9 @@ impl TileGraphic {
         for x in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[0];
+                    theme.active_highlight[0];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[0];
+                    theme.active_highlight[0];
             }
         }
 
@@ -388,9 +605,9 @@ impl TileGraphic {
         for x in 1..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1 {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[1];
+                    theme.active_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[1];
+                    theme.active_highlight[1];
             }
         }
 
@@ -403,9 +620,9 @@ impl TileGraphic {
         ] {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[1];
+                    theme.active_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[1];
+                    theme.active_highlight[1];
             }
         }
 
@@ -413,15 +630,15 @@ impl TileGraphic {
         for x in 3..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 3 {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[2];
+                    theme.active_highlight[2];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[2];
+                    theme.active_highlight[2];
             }
         }
         for x in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
             for y in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    PLAYER_TILE_ACTIVE_HIGHLIGHT[2];
+                    theme.active_highlight[2];
             }
         }
 
@@ -436,7 +653,10 @@ impl TileGraphic {
         }
     }
 
-    pub fn new_clear_standard_highlight(ctx: &mut Context) -> Self {
+    pub fn new_clear_standard_highlight(ctx: &mut Context, theme: &Theme, skin: &TileSkin) -> Self {
+        if let Some(pixel_color_buf) = skin.board_tile(CLEAR_STANDARD_HIGHLIGHT_SKIN_INDEX) {
+            return Self::from_skin(ctx, pixel_color_buf);
+        }
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
         let mut pixel_color_buf: [(u8, u8, u8, u8);
             NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize] =
@@ -447,9 +667,9 @@ impl TileGraphic {
         for x in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[0];
+                    theme.clearing_standard_highlight[0];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[0];
+                    theme.clearing_standard_highlight[0];
             }
         }
 
@@ -457,9 +677,9 @@ impl TileGraphic {
         for x in 1..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1 {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[1];
+                    theme.clearing_standard_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[1];
+                    theme.clearing_standard_highlight[1];
             }
         }
 
@@ -472,9 +692,9 @@ impl TileGraphic {
         ] {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[1];
+                    theme.clearing_standard_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[1];
+                    theme.clearing_standard_highlight[1];
             }
         }
 
@@ -482,15 +702,15 @@ impl TileGraphic {
         for x in 3..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 3 {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[2];
+                    theme.clearing_standard_highlight[2];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[2];
+                    theme.clearing_standard_highlight[2];
             }
         }
         for x in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
             for y in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_STANDARD_HIGHLIGHT[2];
+                    theme.clearing_standard_highlight[2];
             }
         }
 
@@ -505,7 +725,10 @@ impl TileGraphic {
         }
     }
 
-    pub fn new_clear_tetrisnt_highlight(ctx: &mut Context) -> Self {
+    pub fn new_clear_tetrisnt_highlight(ctx: &mut Context, theme: &Theme, skin: &TileSkin) -> Self {
+        if let Some(pixel_color_buf) = skin.board_tile(CLEAR_TETRISNT_HIGHLIGHT_SKIN_INDEX) {
+            return Self::from_skin(ctx, pixel_color_buf);
+        }
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
         let mut pixel_color_buf: [(u8, u8, u8, u8);
             NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize] =
@@ -516,9 +739,9 @@ impl TileGraphic {
         for x in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[0];
+                    theme.clearing_tetrisnt_highlight[0];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[0];
+                    theme.clearing_tetrisnt_highlight[0];
             }
         }
 
@@ -526,9 +749,9 @@ impl TileGraphic {
         for x in 1..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1 {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[1];
+                    theme.clearing_tetrisnt_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[1];
+                    theme.clearing_tetrisnt_highlight[1];
             }
         }
 
@@ -541,9 +764,9 @@ impl TileGraphic {
         ] {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[1];
+                    theme.clearing_tetrisnt_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[1];
+                    theme.clearing_tetrisnt_highlight[1];
             }
         }
 
@@ -551,15 +774,15 @@ impl TileGraphic {
         for x in 3..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 3 {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[2];
+                    theme.clearing_tetrisnt_highlight[2];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[2];
+                    theme.clearing_tetrisnt_highlight[2];
             }
         }
         for x in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
             for y in 2..NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    CLEARING_TILE_TETRISNT_HIGHLIGHT[2];
+                    theme.clearing_tetrisnt_highlight[2];
             }
         }
 
@@ -574,7 +797,10 @@ impl TileGraphic {
         }
     }
 
-    pub fn new_ghost_highlight(ctx: &mut Context) -> Self {
+    pub fn new_ghost_highlight(ctx: &mut Context, theme: &Theme, skin: &TileSkin) -> Self {
+        if let Some(pixel_color_buf) = skin.board_tile(GHOST_HIGHLIGHT_SKIN_INDEX) {
+            return Self::from_skin(ctx, pixel_color_buf);
+        }
         // create a buffer of (u8, u8, u8, u8), because rgba, big enough to hold each pixel
         let mut pixel_color_buf: [(u8, u8, u8, u8);
             NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize * NUM_PIXEL_ROWS_PER_TILEGRAPHIC as usize] =
@@ -585,9 +811,9 @@ impl TileGraphic {
         for x in 0..=NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1 {
             for y in &[0, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 1] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[0];
+                    theme.ghost_highlight[0];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[0];
+                    theme.ghost_highlight[0];
             }
         }
 
@@ -595,9 +821,9 @@ impl TileGraphic {
         for x in 1..=NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2 {
             for y in &[1, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 2] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[1];
+                    theme.ghost_highlight[1];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[1];
+                    theme.ghost_highlight[1];
             }
         }
 
@@ -605,9 +831,9 @@ impl TileGraphic {
         for x in 2..=NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 3 {
             for y in &[2, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 3] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[2];
+                    theme.ghost_highlight[2];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[2];
+                    theme.ghost_highlight[2];
             }
         }
 
@@ -615,9 +841,9 @@ impl TileGraphic {
         for x in 3..=NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 4 {
             for y in &[3, NUM_PIXEL_ROWS_PER_TILEGRAPHIC - 4] {
                 pixel_color_buf[(x + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * y) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[3];
+                    theme.ghost_highlight[3];
                 pixel_color_buf[(y + NUM_PIXEL_ROWS_PER_TILEGRAPHIC * x) as usize].3 =
-                    GHOST_TILE_HIGHLIGHT[3];
+                    theme.ghost_highlight[3];
             }
         }
 
@@ -662,3 +888,30 @@ impl TileGraphic {
         print!("\n");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TestDir;
+
+    #[test]
+    fn skin_sheets_are_cut_into_tiles_or_left_out() {
+        let dir = TestDir::new("skins");
+        // two player tiles, the second one's top right pixel marked
+        let mut player_sheet = image::RgbaImage::from_pixel(16, 8, image::Rgba([10, 20, 30, 255]));
+        player_sheet.put_pixel(15, 0, image::Rgba([200, 0, 0, 128]));
+        player_sheet
+            .save(dir.join(PLAYER_SKIN_FILE))
+            .expect("Failed to write player sheet");
+        // one tile short
+        image::RgbaImage::new(32, 8)
+            .save(dir.join(BOARD_SKIN_FILE))
+            .expect("Failed to write board sheet");
+        let skin = TileSkin::load(&[("test", dir.to_path_buf())]);
+        assert_eq!(skin.vec_player_tiles.len(), 2);
+        assert_eq!(skin.vec_player_tiles[0][7], (10, 20, 30, 255));
+        assert_eq!(skin.vec_player_tiles[1][7], (200, 0, 0, 128));
+        assert!(skin.player_tile(2).is_none());
+        assert!(skin.board_tile(EMPTY_SKIN_INDEX).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{check_version, report_corrupt_file, write_file, CONFIG_VERSION};
+use crate::game::GameMode;
+
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub static HIGH_SCORES_FILE: &str = "highscores.toml";
+pub const MAX_HIGH_SCORES: usize = 10;
+
+// games are only compared against games with the same mode, starting level and starting number of players
+#[derive(Serialize, Deserialize, PartialEq, Eq, Copy, Clone, Debug)]
+pub struct HighScoreKey {
+    pub game_mode: GameMode,
+    pub starting_level: u8,
+    pub num_players: u8,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HighScoreEntry {
+    pub initials: String,
+    // profiles that played in the game
+    #[serde(default)]
+    pub names: Vec<String>,
+    pub score: u64,
+    // rings in rotatris
+    pub lines: u16,
+    pub level: u8,
+    pub frames: u32,
+    // seconds since the unix epoch
+    pub date: u64,
+}
+
+impl HighScoreEntry {
+    pub fn new(
+        initials: String,
+        names: Vec<String>,
+        score: u64,
+        lines: u16,
+        level: u8,
+        frames: u32,
+    ) -> Self {
+        let date = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        Self {
+            initials,
+            names,
+            score,
+            lines,
+            level,
+            frames,
+            date,
+        }
+    }
+
+    // classic is all about score, rotatris is about how many rings you can clear
+    fn ranks_above(&self, other: &HighScoreEntry, game_mode: GameMode) -> bool {
+        match game_mode {
+            GameMode::Rotatris => (self.lines, self.score) > (other.lines, other.score),
+            _ => (self.score, self.lines) > (other.score, other.lines),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoreTable {
+    key: HighScoreKey,
+    entries: Vec<HighScoreEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HighScores {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    tables: Vec<HighScoreTable>,
+}
+
+impl Default for HighScores {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            tables: vec![],
+        }
+    }
+}
+
+impl HighScores {
+    pub fn load(dir: &Path) -> Self {
+        let path = dir.join(HIGH_SCORES_FILE);
+        let string = match fs::read_to_string(&path) {
+            Ok(string) => string,
+            Err(_) => return Self::default(),
+        };
+        match toml::from_str::<HighScores>(&string) {
+            Ok(high_scores) => {
+                check_version(&path, high_scores.version);
+                high_scores
+            }
+            Err(e) => {
+                report_corrupt_file(&path, &e.to_string());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, dir: &Path) {
+        match toml::to_string(self) {
+            Ok(string) => write_file(dir, HIGH_SCORES_FILE, &string),
+            Err(e) => println!("[!] failed to serialize high scores: {}", e),
+        }
+    }
+
+    // returns the place the entry got in its table, if it made it in at all
+    pub fn add(&mut self, key: HighScoreKey, entry: HighScoreEntry) -> Option<usize> {
+        let table_index = match self.tables.iter().position(|table| table.key == key) {
+            Some(index) => index,
+            None => {
+                self.tables.push(HighScoreTable {
+                    key,
+                    entries: vec![],
+                });
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[table_index].entries;
+        // ties go to whoever got there first
+        let place = entries
+            .iter()
+            .position(|other| entry.ranks_above(other, key.game_mode))
+            .unwrap_or(entries.len());
+        if place >= MAX_HIGH_SCORES {
+            return None;
+        }
+        entries.insert(place, entry);
+        entries.truncate(MAX_HIGH_SCORES);
+        self.version = CONFIG_VERSION;
+        Some(place)
+    }
+
+    pub fn get_entries(&self, key: HighScoreKey) -> &[HighScoreEntry] {
+        match self.tables.iter().find(|table| table.key == key) {
+            Some(table) => &table.entries,
+            None => &[],
+        }
+    }
+}
+
+// YYYY-MM-DD (UTC) from seconds since the unix epoch
+pub fn format_date(secs: u64) -> String {
+    // days to a civil date, from Howard Hinnant's date algorithms
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days / 146097;
+    let day_of_era = days - era * 146097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(score: u64, lines: u16) -> HighScoreEntry {
+        HighScoreEntry {
+            initials: "AAA".to_string(),
+            names: vec![],
+            score,
+            lines,
+            level: 0,
+            frames: 0,
+            date: 0,
+        }
+    }
+
+    #[test]
+    fn tables_rank_by_mode() {
+        let classic = HighScoreKey {
+            game_mode: GameMode::Classic,
+            starting_level: 0,
+            num_players: 1,
+        };
+        let rotatris = HighScoreKey {
+            game_mode: GameMode::Rotatris,
+            ..classic
+        };
+        let mut high_scores = HighScores::default();
+        for score in 0..MAX_HIGH_SCORES as u64 {
+            assert!(high_scores.add(classic, entry(score * 100, 0)).is_some());
+        }
+        assert_eq!(high_scores.add(classic, entry(0, 0)), None);
+        assert_eq!(high_scores.add(classic, entry(450, 0)), Some(5));
+        // a tie goes below the existing entry
+        assert_eq!(high_scores.add(classic, entry(900, 0)), Some(1));
+        let entries = high_scores.get_entries(classic);
+        assert_eq!(entries.len(), MAX_HIGH_SCORES);
+        assert_eq!(entries[0].score, 900);
+        assert_eq!(entries[MAX_HIGH_SCORES - 1].score, 200);
+
+        assert_eq!(high_scores.add(rotatris, entry(1000, 1)), Some(0));
+        assert_eq!(high_scores.add(rotatris, entry(10, 2)), Some(0));
+        assert_eq!(high_scores.get_entries(rotatris)[0].lines, 2);
+
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(951782400), "2000-02-29");
+    }
+}
@@ -1,7 +1,11 @@
 use crate::movement::Movement;
-use ggez::event::KeyCode;
+use ggez::event::{Axis, Button, KeyCode};
+use serde::{Deserialize, Serialize};
+
+use std::fmt;
 
 // (is pressed down, was pressed this frame)
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Input {
     pub keydown_left: (bool, bool),
     pub keydown_right: (bool, bool),
@@ -12,6 +16,7 @@ pub struct Input {
     pub keydown_board_cw: (bool, bool),
     pub keydown_board_ccw: (bool, bool),
     pub keydown_start: (bool, bool),
+    pub keydown_select: (bool, bool),
 }
 
 impl Input {
@@ -26,6 +31,7 @@ impl Input {
             keydown_board_cw: (false, false),
             keydown_board_ccw: (false, false),
             keydown_start: (false, false),
+            keydown_select: (false, false),
         }
     }
 
@@ -39,6 +45,7 @@ impl Input {
         self.keydown_board_cw.1 = false;
         self.keydown_board_ccw.1 = false;
         self.keydown_start.1 = false;
+        self.keydown_select.1 = false;
     }
 
     pub fn reset_all(&mut self) {
@@ -51,6 +58,112 @@ impl Input {
         self.keydown_board_cw = (false, false);
         self.keydown_board_ccw = (false, false);
         self.keydown_start = (false, false);
+        self.keydown_select = (false, false);
+    }
+
+    // menu navigation with the menu keys; by default arrows to move, 'G' for the gamepad toggle, Escape to go back, Space to select
+    pub fn update_menu_keydown(&mut self, keycode: KeyCode, menu_keys: &MenuKeys) {
+        if keycode == menu_keys.left {
+            if !self.keydown_left.0 {
+                self.keydown_left = (true, true);
+            }
+        } else if keycode == menu_keys.right {
+            if !self.keydown_right.0 {
+                self.keydown_right = (true, true);
+            }
+        } else if keycode == menu_keys.down {
+            if !self.keydown_down.0 {
+                self.keydown_down = (true, true);
+            }
+        } else if keycode == menu_keys.up {
+            if !self.keydown_up.0 {
+                self.keydown_up = (true, true);
+            }
+        } else if keycode == menu_keys.gamepad {
+            if !self.keydown_rotate_cw.0 {
+                self.keydown_rotate_cw = (true, true);
+            }
+        } else if keycode == menu_keys.back {
+            if !self.keydown_rotate_ccw.0 {
+                self.keydown_rotate_ccw = (true, true);
+            }
+        } else if menu_keys.is_select(keycode) && !self.keydown_start.0 {
+            self.keydown_start = (true, true);
+        }
+    }
+
+    pub fn update_menu_keyup(&mut self, keycode: KeyCode, menu_keys: &MenuKeys) {
+        if keycode == menu_keys.left {
+            self.keydown_left = (false, false);
+        } else if keycode == menu_keys.right {
+            self.keydown_right = (false, false);
+        } else if keycode == menu_keys.down {
+            self.keydown_down = (false, false);
+        } else if keycode == menu_keys.up {
+            self.keydown_up = (false, false);
+        } else if keycode == menu_keys.gamepad {
+            self.keydown_rotate_cw = (false, false);
+        } else if keycode == menu_keys.back {
+            self.keydown_rotate_ccw = (false, false);
+        } else if menu_keys.is_select(keycode) {
+            self.keydown_start = (false, false);
+        }
+    }
+
+    // menu navigation: D-pad to move, South/Start to select, East to go back, North for the gamepad toggle
+    pub fn update_menu_buttondown(&mut self, btn: Button) {
+        if btn == Button::DPadLeft {
+            self.keydown_left = (true, true);
+        } else if btn == Button::DPadRight {
+            self.keydown_right = (true, true);
+        } else if btn == Button::DPadDown {
+            self.keydown_down = (true, true);
+        } else if btn == Button::DPadUp {
+            self.keydown_up = (true, true);
+        } else if btn == Button::East {
+            self.keydown_rotate_ccw = (true, true);
+        } else if btn == Button::North {
+            self.keydown_rotate_cw = (true, true);
+        } else if btn == Button::South || btn == Button::Start {
+            self.keydown_start = (true, true);
+        }
+    }
+
+    pub fn update_menu_buttonup(&mut self, btn: Button) {
+        if btn == Button::DPadLeft {
+            self.keydown_left = (false, false);
+        } else if btn == Button::DPadRight {
+            self.keydown_right = (false, false);
+        } else if btn == Button::DPadDown {
+            self.keydown_down = (false, false);
+        } else if btn == Button::DPadUp {
+            self.keydown_up = (false, false);
+        } else if btn == Button::East {
+            self.keydown_rotate_ccw = (false, false);
+        } else if btn == Button::North {
+            self.keydown_rotate_cw = (false, false);
+        } else if btn == Button::South || btn == Button::Start {
+            self.keydown_start = (false, false);
+        }
+    }
+
+    // the left stick moves around menus like the D-pad; it has to come back near the center before it moves again
+    pub fn update_menu_axis(&mut self, axis: Axis, value: f32, sticks: &StickSettings) {
+        let value = sticks.read_axis(axis, value);
+        let (negative, positive) = match axis {
+            Axis::LeftStickX => (&mut self.keydown_left, &mut self.keydown_right),
+            // up is positive on the stick
+            Axis::LeftStickY => (&mut self.keydown_down, &mut self.keydown_up),
+            _ => return,
+        };
+        if value < -sticks.press_threshold() && !negative.0 && !positive.0 {
+            *negative = (true, true);
+        } else if value > sticks.press_threshold() && !negative.0 && !positive.0 {
+            *positive = (true, true);
+        } else if value.abs() < sticks.release_threshold() {
+            *negative = (false, false);
+            *positive = (false, false);
+        }
     }
 
     pub fn _debug_print_inputs(&self) {
@@ -81,10 +194,332 @@ impl Input {
             "Start: ({}, {})",
             self.keydown_start.0, self.keydown_start.1
         );
+        println!(
+            "Select: ({}, {})",
+            self.keydown_select.0, self.keydown_select.1
+        );
     }
 }
 
-#[derive(Clone, Default)]
+pub const NUM_MENU_KEYS: usize = 9;
+// the order the menu keys are listed and captured in
+pub const MENU_KEY_NAMES: [&str; NUM_MENU_KEYS] = [
+    "Left", "Right", "Up", "Down", "Select", "Back", "Gamepad", "Pause", "Restart",
+];
+const BACK_MENU_KEY: usize = 5;
+const PAUSE_MENU_KEY: usize = 7;
+
+// the keys that work everywhere instead of belonging to a player: menu navigation, and pausing and restarting in game
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MenuKeys {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub select: KeyCode,
+    pub back: KeyCode,
+    pub gamepad: KeyCode,
+    pub pause: KeyCode,
+    pub restart: KeyCode,
+}
+
+impl Default for MenuKeys {
+    fn default() -> Self {
+        Self {
+            left: KeyCode::Left,
+            right: KeyCode::Right,
+            up: KeyCode::Up,
+            down: KeyCode::Down,
+            select: KeyCode::Space,
+            back: KeyCode::Escape,
+            gamepad: KeyCode::G,
+            pause: KeyCode::Escape,
+            restart: KeyCode::F5,
+        }
+    }
+}
+
+impl MenuKeys {
+    pub fn keys(&self) -> [KeyCode; NUM_MENU_KEYS] {
+        [
+            self.left,
+            self.right,
+            self.up,
+            self.down,
+            self.select,
+            self.back,
+            self.gamepad,
+            self.pause,
+            self.restart,
+        ]
+    }
+
+    pub fn from_keys(keys: &[KeyCode; NUM_MENU_KEYS]) -> Self {
+        Self {
+            left: keys[0],
+            right: keys[1],
+            up: keys[2],
+            down: keys[3],
+            select: keys[4],
+            back: keys[5],
+            gamepad: keys[6],
+            pause: keys[7],
+            restart: keys[8],
+        }
+    }
+
+    // Return (and the keypad's Enter) always select too, unless they're some other menu key,
+    // so there's a way through the menus whatever the select key was set to
+    pub fn is_select(&self, keycode: KeyCode) -> bool {
+        keycode == self.select
+            || ((keycode == KeyCode::Return || keycode == KeyCode::NumpadEnter)
+                && !self.keys().contains(&keycode))
+    }
+
+    // whether the key can be the menu key at `index` next to the ones before it;
+    // back only counts in menus and pause only in game, so those two can share a key
+    pub fn fits(earlier_keys: &[KeyCode], index: usize, keycode: KeyCode) -> bool {
+        earlier_keys.iter().enumerate().all(|(other, earlier_key)| {
+            *earlier_key != keycode
+                || (index == PAUSE_MENU_KEY && other == BACK_MENU_KEY)
+                || (index == BACK_MENU_KEY && other == PAUSE_MENU_KEY)
+        })
+    }
+
+    pub fn is_usable(&self) -> bool {
+        let keys = self.keys();
+        (0..NUM_MENU_KEYS).all(|index| Self::fits(&keys[..index], index, keys[index]))
+    }
+}
+
+// a single change to a player's input, whether it came from a keyboard, a gamepad button or an axis;
+// these are what replays record
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum InputEvent {
+    Press(Movement),
+    Release(Movement),
+    Start(bool),
+    Select(bool),
+}
+
+pub const NUM_SOCD_POLICIES: u8 = 3;
+
+// what a player's left and right do while both are held (SOCD, simultaneous opposite cardinal directions),
+// which hitbox-style controllers and keyboards can both do
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum SocdPolicy {
+    // the newer direction wins, and letting go of it goes back to the other one
+    #[default]
+    LastInputWins,
+    // the direction held first keeps going until it's let go
+    FirstInputWins,
+    // holding both is the same as holding neither
+    Neutral,
+}
+
+impl SocdPolicy {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(SocdPolicy::LastInputWins),
+            1 => Some(SocdPolicy::FirstInputWins),
+            2 => Some(SocdPolicy::Neutral),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SocdPolicy::LastInputWins => "Last Input Wins",
+            SocdPolicy::FirstInputWins => "First Input Wins",
+            SocdPolicy::Neutral => "Neutral",
+        }
+    }
+}
+
+// e.g. both the arrow keys and WASD for the same player
+pub const MAX_KEYS_PER_MOVEMENT: usize = 3;
+
+pub const NUM_KEYBOARD_PRESETS: usize = 4;
+
+// the keys of each preset are for these movements, in this order
+const KEYBOARD_PRESET_MOVEMENTS: [Movement; 7] = [
+    Movement::Left,
+    Movement::Right,
+    Movement::Down,
+    Movement::RotateCw,
+    Movement::RotateCcw,
+    Movement::BoardCw,
+    Movement::BoardCcw,
+];
+
+// ready-made keyboard schemes; the first two and the numpad don't share any keys, so three players can set up in a row
+pub const KEYBOARD_PRESETS: [(&str, [KeyCode; 7]); NUM_KEYBOARD_PRESETS] = [
+    (
+        "Arrows + Z/X",
+        [
+            KeyCode::Left,
+            KeyCode::Right,
+            KeyCode::Down,
+            KeyCode::X,
+            KeyCode::Z,
+            KeyCode::V,
+            KeyCode::C,
+        ],
+    ),
+    (
+        "WASD + J/K",
+        [
+            KeyCode::A,
+            KeyCode::D,
+            KeyCode::S,
+            KeyCode::K,
+            KeyCode::J,
+            KeyCode::I,
+            KeyCode::U,
+        ],
+    ),
+    (
+        "Numpad",
+        [
+            KeyCode::Numpad4,
+            KeyCode::Numpad6,
+            KeyCode::Numpad5,
+            KeyCode::Numpad9,
+            KeyCode::Numpad7,
+            KeyCode::Numpad3,
+            KeyCode::Numpad1,
+        ],
+    ),
+    (
+        "IJKL + Q/E",
+        [
+            KeyCode::J,
+            KeyCode::L,
+            KeyCode::K,
+            KeyCode::E,
+            KeyCode::Q,
+            KeyCode::Key3,
+            KeyCode::Key1,
+        ],
+    ),
+];
+
+// the number, letter and numpad keys as (row, first column, keys), with the numpad off to the right,
+// so a scheme on them can be shifted over to other keys in the same shape
+const KEYBOARD_GRID: [(i8, i8, &[KeyCode]); 7] = [
+    (
+        0,
+        0,
+        &[
+            KeyCode::Key1,
+            KeyCode::Key2,
+            KeyCode::Key3,
+            KeyCode::Key4,
+            KeyCode::Key5,
+            KeyCode::Key6,
+            KeyCode::Key7,
+            KeyCode::Key8,
+            KeyCode::Key9,
+            KeyCode::Key0,
+            KeyCode::Minus,
+            KeyCode::Equals,
+        ],
+    ),
+    (
+        1,
+        0,
+        &[
+            KeyCode::Q,
+            KeyCode::W,
+            KeyCode::E,
+            KeyCode::R,
+            KeyCode::T,
+            KeyCode::Y,
+            KeyCode::U,
+            KeyCode::I,
+            KeyCode::O,
+            KeyCode::P,
+            KeyCode::LBracket,
+            KeyCode::RBracket,
+        ],
+    ),
+    (
+        2,
+        0,
+        &[
+            KeyCode::A,
+            KeyCode::S,
+            KeyCode::D,
+            KeyCode::F,
+            KeyCode::G,
+            KeyCode::H,
+            KeyCode::J,
+            KeyCode::K,
+            KeyCode::L,
+            KeyCode::Semicolon,
+            KeyCode::Apostrophe,
+        ],
+    ),
+    (
+        3,
+        0,
+        &[
+            KeyCode::Z,
+            KeyCode::X,
+            KeyCode::C,
+            KeyCode::V,
+            KeyCode::B,
+            KeyCode::N,
+            KeyCode::M,
+            KeyCode::Comma,
+            KeyCode::Period,
+            KeyCode::Slash,
+        ],
+    ),
+    (
+        1,
+        16,
+        &[KeyCode::Numpad7, KeyCode::Numpad8, KeyCode::Numpad9],
+    ),
+    (
+        2,
+        16,
+        &[KeyCode::Numpad4, KeyCode::Numpad5, KeyCode::Numpad6],
+    ),
+    (
+        3,
+        16,
+        &[KeyCode::Numpad1, KeyCode::Numpad2, KeyCode::Numpad3],
+    ),
+];
+
+// how far a scheme can be shifted, in keys
+const MAX_SHIFT_DISTANCE: i8 = 20;
+
+fn grid_position(keycode: KeyCode) -> Option<(i8, i8)> {
+    for (row, first_column, keys) in KEYBOARD_GRID.iter() {
+        if let Some(index) = keys.iter().position(|key| *key == keycode) {
+            return Some((*row, first_column + index as i8));
+        }
+    }
+
+    None
+}
+
+fn grid_keycode(row: i8, column: i8) -> Option<KeyCode> {
+    for (grid_row, first_column, keys) in KEYBOARD_GRID.iter() {
+        if *grid_row == row && column >= *first_column {
+            if let Some(keycode) = keys.get((column - first_column) as usize) {
+                return Some(*keycode);
+            }
+        }
+    }
+
+    None
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct KeyboardControlScheme {
     pub vec_keycode_movement_pair: Vec<(KeyCode, Movement)>,
 }
@@ -113,48 +548,426 @@ impl KeyboardControlScheme {
         self.vec_keycode_movement_pair.is_empty()
     }
 
-    pub fn new_classic(
-        left: KeyCode,
-        right: KeyCode,
-        down: KeyCode,
-        rotate_cw: KeyCode,
-        rotate_ccw: KeyCode,
-    ) -> Self {
+    // how many different movements have at least one key; a restart key is optional, so it doesn't count
+    pub fn num_movements(&self) -> usize {
+        let mut vec_movements: Vec<Movement> = vec![];
+        for pair in self.vec_keycode_movement_pair.iter() {
+            if pair.1 != Movement::Restart && !vec_movements.contains(&pair.1) {
+                vec_movements.push(pair.1);
+            }
+        }
+        vec_movements.len()
+    }
+
+    // in the order they were bound
+    pub fn keycodes_from_movement(&self, m: Movement) -> Vec<KeyCode> {
+        self.vec_keycode_movement_pair
+            .iter()
+            .filter(|pair| pair.1 == m)
+            .map(|pair| pair.0)
+            .collect()
+    }
+
+    // the same keys without the ones for the given movements (like the board rotations in classic)
+    pub fn without_movements(&self, arr_movements: &[Movement]) -> Self {
         Self {
-            vec_keycode_movement_pair: vec![
-                (left, Movement::Left),
-                (right, Movement::Right),
-                (down, Movement::Down),
-                (rotate_cw, Movement::RotateCw),
-                (rotate_ccw, Movement::RotateCcw),
-            ],
+            vec_keycode_movement_pair: self
+                .vec_keycode_movement_pair
+                .iter()
+                .filter(|pair| !arr_movements.contains(&pair.1))
+                .copied()
+                .collect(),
+        }
+    }
+
+    pub fn movement_from_keycode(&self, k: KeyCode) -> Option<Movement> {
+        for pair in self.vec_keycode_movement_pair.iter() {
+            if pair.0 == k {
+                return Some(pair.1);
+            }
+        }
+
+        None
+    }
+
+    pub fn add_pair(&mut self, k: KeyCode, m: Movement) {
+        self.vec_keycode_movement_pair.push((k, m));
+    }
+
+    // a preset's keys for the given movements, leaving out the ones that are taken
+    pub fn from_preset(preset: usize, arr_movements: &[Movement], arr_taken: &[KeyCode]) -> Self {
+        let mut scheme = Self::default();
+        for (keycode, movement) in KEYBOARD_PRESETS[preset]
+            .1
+            .iter()
+            .zip(KEYBOARD_PRESET_MOVEMENTS.iter())
+        {
+            if arr_movements.contains(movement) && !arr_taken.contains(keycode) {
+                scheme.add_pair(*keycode, *movement);
+            }
+        }
+        scheme
+    }
+
+    // the same scheme with every key moved the same way across the keyboard, onto keys that aren't taken;
+    // the closest shift wins, trying left and right before up and down. None if a key isn't on the
+    // number, letter or numpad keys, or there's nowhere free to go
+    pub fn shifted(&self, arr_taken: &[KeyCode]) -> Option<Self> {
+        let mut vec_positions: Vec<((i8, i8), Movement)> = vec![];
+        for (keycode, movement) in self.vec_keycode_movement_pair.iter() {
+            vec_positions.push((grid_position(*keycode)?, *movement));
+        }
+        for distance in 1..=MAX_SHIFT_DISTANCE {
+            for rows in 0..=distance {
+                let columns = distance - rows;
+                for (row_shift, column_shift) in [
+                    (rows, columns),
+                    (rows, -columns),
+                    (-rows, columns),
+                    (-rows, -columns),
+                ] {
+                    let mut scheme = Self::default();
+                    for ((row, column), movement) in vec_positions.iter() {
+                        match grid_keycode(row + row_shift, column + column_shift) {
+                            Some(keycode) if !arr_taken.contains(&keycode) => {
+                                scheme.add_pair(keycode, *movement)
+                            }
+                            _ => break,
+                        }
+                    }
+                    if scheme.len() == self.len() {
+                        return Some(scheme);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+// a gamepad button, or a stick (or trigger) pushed one way
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+pub enum GamepadInput {
+    Button(Button),
+    AxisPositive(Axis),
+    AxisNegative(Axis),
+}
+
+impl GamepadInput {
+    // which way the axis is pushed, if it's pushed past the threshold
+    pub fn from_axis(axis: Axis, value: f32, threshold: f32) -> Option<Self> {
+        if value > threshold {
+            Some(Self::AxisPositive(axis))
+        } else if value < -threshold {
+            Some(Self::AxisNegative(axis))
+        } else {
+            None
+        }
+    }
+
+    // how far the axis is pushed in this input's direction, if this input is on that axis
+    pub fn axis_value(&self, axis: Axis, value: f32) -> Option<f32> {
+        match *self {
+            Self::AxisPositive(a) if a == axis => Some(value),
+            Self::AxisNegative(a) if a == axis => Some(-value),
+            _ => None,
+        }
+    }
+
+    // one of the sticks (not a trigger) pushed one way
+    pub fn is_stick(&self) -> bool {
+        matches!(
+            self,
+            Self::AxisPositive(
+                Axis::LeftStickX | Axis::LeftStickY | Axis::RightStickX | Axis::RightStickY
+            ) | Self::AxisNegative(
+                Axis::LeftStickX | Axis::LeftStickY | Axis::RightStickX | Axis::RightStickY
+            )
+        )
+    }
+
+    // Start and Select always pause and leave, so they can't be bound to a movement
+    pub fn is_bindable(&self) -> bool {
+        !matches!(
+            self,
+            Self::Button(Button::Start)
+                | Self::Button(Button::Select)
+                | Self::Button(Button::Unknown)
+        ) && !matches!(
+            self,
+            Self::AxisPositive(Axis::Unknown) | Self::AxisNegative(Axis::Unknown)
+        )
+    }
+}
+
+impl fmt::Display for GamepadInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Button(btn) => write!(f, "{:?}", btn),
+            Self::AxisPositive(axis) => write!(f, "{:?}+", axis),
+            Self::AxisNegative(axis) => write!(f, "{:?}-", axis),
         }
     }
+}
 
-    pub fn new_rotatris(
-        left: KeyCode,
-        right: KeyCode,
-        down: KeyCode,
-        rotate_cw: KeyCode,
-        rotate_ccw: KeyCode,
-        rotate_board_cw: KeyCode,
-        rotate_board_ccw: KeyCode,
-    ) -> Self {
+// in percent of the way out
+pub const DEFAULT_DEADZONE: u8 = 20;
+pub const MAX_DEADZONE: u8 = 60;
+pub const DEFAULT_HYSTERESIS: u8 = 30;
+pub const MIN_HYSTERESIS: u8 = 5;
+pub const MAX_HYSTERESIS: u8 = 35;
+
+// how a gamepad's sticks are read: a stick counts as centered inside the deadzone, and only counts as pushed
+// once it's past the deadzone plus the hysteresis, so one resting near the edge (or drifting) doesn't flicker
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StickSettings {
+    pub deadzone: u8,
+    pub hysteresis: u8,
+    pub invert_x: bool,
+    pub invert_y: bool,
+}
+
+impl Default for StickSettings {
+    fn default() -> Self {
         Self {
-            vec_keycode_movement_pair: vec![
-                (left, Movement::Left),
-                (right, Movement::Right),
-                (down, Movement::Down),
-                (rotate_cw, Movement::RotateCw),
-                (rotate_ccw, Movement::RotateCcw),
-                (rotate_board_cw, Movement::BoardCw),
-                (rotate_board_ccw, Movement::BoardCcw),
+            deadzone: DEFAULT_DEADZONE,
+            hysteresis: DEFAULT_HYSTERESIS,
+            invert_x: false,
+            invert_y: false,
+        }
+    }
+}
+
+impl StickSettings {
+    pub fn press_threshold(&self) -> f32 {
+        (self.deadzone + self.hysteresis) as f32 / 100.0
+    }
+
+    pub fn release_threshold(&self) -> f32 {
+        self.deadzone as f32 / 100.0
+    }
+
+    // inverted axes are flipped before anything else looks at them
+    pub fn read_axis(&self, axis: Axis, value: f32) -> f32 {
+        match axis {
+            Axis::LeftStickX | Axis::RightStickX if self.invert_x => -value,
+            Axis::LeftStickY | Axis::RightStickY if self.invert_y => -value,
+            _ => value,
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // anything a hand-edited file got out of range goes back to its default; returns whether anything did
+    pub fn fix_up(&mut self) -> bool {
+        let mut fixed_flag = false;
+        if self.deadzone > MAX_DEADZONE {
+            self.deadzone = DEFAULT_DEADZONE;
+            fixed_flag = true;
+        }
+        if !(MIN_HYSTERESIS..=MAX_HYSTERESIS).contains(&self.hysteresis) {
+            self.hysteresis = DEFAULT_HYSTERESIS;
+            fixed_flag = true;
+        }
+        fixed_flag
+    }
+}
+
+pub const NUM_MOVEMENT_STICKS: u8 = 3;
+
+// which stick (if any) Left, Right and Down are on
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum MovementStick {
+    Left,
+    Right,
+    Off,
+}
+
+impl MovementStick {
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            0 => Some(MovementStick::Left),
+            1 => Some(MovementStick::Right),
+            2 => Some(MovementStick::Off),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MovementStick::Left => "Left Stick",
+            MovementStick::Right => "Right Stick",
+            MovementStick::Off => "Off",
+        }
+    }
+
+    // (x, y)
+    fn axes(&self) -> Option<(Axis, Axis)> {
+        match self {
+            MovementStick::Left => Some((Axis::LeftStickX, Axis::LeftStickY)),
+            MovementStick::Right => Some((Axis::RightStickX, Axis::RightStickY)),
+            MovementStick::Off => None,
+        }
+    }
+}
+
+// the right stick pushed right or left turns the board in rotatris
+const BOARD_STICK_PAIRS: [(GamepadInput, Movement); 2] = [
+    (
+        GamepadInput::AxisPositive(Axis::RightStickX),
+        Movement::BoardCw,
+    ),
+    (
+        GamepadInput::AxisNegative(Axis::RightStickX),
+        Movement::BoardCcw,
+    ),
+];
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct GamepadControlScheme {
+    pub vec_input_movement_pair: Vec<(GamepadInput, Movement)>,
+    #[serde(default)]
+    pub sticks: StickSettings,
+}
+
+// the layout gamepads had before they could be remapped; the left stick works like the D-pad
+impl Default for GamepadControlScheme {
+    fn default() -> Self {
+        Self {
+            vec_input_movement_pair: vec![
+                (GamepadInput::Button(Button::DPadLeft), Movement::Left),
+                (GamepadInput::Button(Button::DPadRight), Movement::Right),
+                (GamepadInput::Button(Button::DPadDown), Movement::Down),
+                (GamepadInput::AxisNegative(Axis::LeftStickX), Movement::Left),
+                (
+                    GamepadInput::AxisPositive(Axis::LeftStickX),
+                    Movement::Right,
+                ),
+                (GamepadInput::AxisNegative(Axis::LeftStickY), Movement::Down),
+                (GamepadInput::Button(Button::East), Movement::RotateCw),
+                (GamepadInput::Button(Button::South), Movement::RotateCcw),
+                (GamepadInput::Button(Button::North), Movement::BoardCw),
+                (GamepadInput::Button(Button::West), Movement::BoardCcw),
             ],
+            sticks: StickSettings::default(),
         }
     }
+}
 
-    pub fn keycode_from_movement(&self, m: Movement) -> Option<KeyCode> {
-        for pair in self.vec_keycode_movement_pair.iter() {
+impl GamepadControlScheme {
+    pub fn new_empty() -> Self {
+        Self {
+            vec_input_movement_pair: vec![],
+            sticks: StickSettings::default(),
+        }
+    }
+
+    // the same stick settings with a different layout, e.g. the default one
+    pub fn with_sticks(self, sticks: StickSettings) -> Self {
+        Self { sticks, ..self }
+    }
+
+    pub fn has_default_layout(&self) -> bool {
+        self.vec_input_movement_pair == Self::default().vec_input_movement_pair
+    }
+
+    // going by which stick Left is on
+    pub fn movement_stick(&self) -> MovementStick {
+        for pair in self.vec_input_movement_pair.iter() {
+            match pair {
+                (GamepadInput::AxisNegative(Axis::LeftStickX), Movement::Left) => {
+                    return MovementStick::Left
+                }
+                (GamepadInput::AxisNegative(Axis::RightStickX), Movement::Left) => {
+                    return MovementStick::Right
+                }
+                _ => {}
+            }
+        }
+
+        MovementStick::Off
+    }
+
+    // moves Left, Right and Down off of whatever stick they were on and onto this one, taking it from anything else;
+    // returns false (and leaves the layout alone) if that would leave a movement with nothing bound to it
+    pub fn set_movement_stick(&mut self, movement_stick: MovementStick) -> bool {
+        let mut vec_pairs: Vec<(GamepadInput, Movement)> = self
+            .vec_input_movement_pair
+            .iter()
+            .filter(|(input, movement)| {
+                !(input.is_stick()
+                    && [Movement::Left, Movement::Right, Movement::Down].contains(movement))
+            })
+            .copied()
+            .collect();
+        if let Some((x, y)) = movement_stick.axes() {
+            let stick_pairs = [
+                (GamepadInput::AxisNegative(x), Movement::Left),
+                (GamepadInput::AxisPositive(x), Movement::Right),
+                (GamepadInput::AxisNegative(y), Movement::Down),
+            ];
+            vec_pairs.retain(|(input, _)| !stick_pairs.iter().any(|pair| pair.0 == *input));
+            vec_pairs.extend(stick_pairs.iter());
+        }
+        self.replace_pairs(vec_pairs)
+    }
+
+    pub fn has_board_stick(&self) -> bool {
+        BOARD_STICK_PAIRS
+            .iter()
+            .all(|pair| self.vec_input_movement_pair.contains(pair))
+    }
+
+    // the right stick can't turn the board while it's moving the piece
+    pub fn set_board_stick(&mut self, on: bool) -> bool {
+        if on && self.movement_stick() == MovementStick::Right {
+            return false;
+        }
+        let mut vec_pairs: Vec<(GamepadInput, Movement)> = self
+            .vec_input_movement_pair
+            .iter()
+            .filter(|pair| {
+                if on {
+                    !BOARD_STICK_PAIRS.iter().any(|board| board.0 == pair.0)
+                } else {
+                    !BOARD_STICK_PAIRS.contains(pair)
+                }
+            })
+            .copied()
+            .collect();
+        if on {
+            vec_pairs.extend(BOARD_STICK_PAIRS.iter());
+        }
+        self.replace_pairs(vec_pairs)
+    }
+
+    fn replace_pairs(&mut self, vec_pairs: Vec<(GamepadInput, Movement)>) -> bool {
+        let num_movements = self.num_movements();
+        let old_pairs = std::mem::replace(&mut self.vec_input_movement_pair, vec_pairs);
+        if self.num_movements() < num_movements {
+            self.vec_input_movement_pair = old_pairs;
+            return false;
+        }
+        true
+    }
+
+    // how many different movements have something bound to them
+    pub fn num_movements(&self) -> usize {
+        let mut vec_movements: Vec<Movement> = vec![];
+        for pair in self.vec_input_movement_pair.iter() {
+            if !vec_movements.contains(&pair.1) {
+                vec_movements.push(pair.1);
+            }
+        }
+        vec_movements.len()
+    }
+
+    pub fn input_from_movement(&self, m: Movement) -> Option<GamepadInput> {
+        for pair in self.vec_input_movement_pair.iter() {
             if pair.1 == m {
                 return Some(pair.0);
             }
@@ -163,9 +976,9 @@ impl KeyboardControlScheme {
         None
     }
 
-    pub fn movement_from_keycode(&self, k: KeyCode) -> Option<Movement> {
-        for pair in self.vec_keycode_movement_pair.iter() {
-            if pair.0 == k {
+    pub fn movement_from_input(&self, input: GamepadInput) -> Option<Movement> {
+        for pair in self.vec_input_movement_pair.iter() {
+            if pair.0 == input {
                 return Some(pair.1);
             }
         }
@@ -173,7 +986,100 @@ impl KeyboardControlScheme {
         None
     }
 
-    pub fn add_pair(&mut self, k: KeyCode, m: Movement) {
-        self.vec_keycode_movement_pair.push((k, m));
+    pub fn add_pair(&mut self, input: GamepadInput, m: Movement) {
+        self.vec_input_movement_pair.push((input, m));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLASSIC_MOVEMENTS: [Movement; 5] = [
+        Movement::Left,
+        Movement::Right,
+        Movement::Down,
+        Movement::RotateCw,
+        Movement::RotateCcw,
+    ];
+
+    #[test]
+    fn presets_skip_taken_keys() {
+        let scheme = KeyboardControlScheme::from_preset(0, &CLASSIC_MOVEMENTS, &[]);
+        assert_eq!(scheme.len(), 5);
+        assert_eq!(
+            scheme.movement_from_keycode(KeyCode::X),
+            Some(Movement::RotateCw)
+        );
+        // no board rotations in classic
+        assert_eq!(scheme.movement_from_keycode(KeyCode::V), None);
+
+        // IJKL shares J and K with WASD + J/K
+        let wasd = KeyboardControlScheme::from_preset(1, &KEYBOARD_PRESET_MOVEMENTS, &[]);
+        let taken: Vec<KeyCode> = wasd
+            .vec_keycode_movement_pair
+            .iter()
+            .map(|pair| pair.0)
+            .collect();
+        let ijkl = KeyboardControlScheme::from_preset(3, &KEYBOARD_PRESET_MOVEMENTS, &taken);
+        assert_eq!(ijkl.len(), 5);
+        assert!(ijkl.keycodes_from_movement(Movement::Left).is_empty());
+        assert!(ijkl.keycodes_from_movement(Movement::Down).is_empty());
+    }
+
+    #[test]
+    fn shifted_copies() {
+        let wasd = KeyboardControlScheme::from_preset(1, &CLASSIC_MOVEMENTS, &[]);
+        let taken: Vec<KeyCode> = wasd
+            .vec_keycode_movement_pair
+            .iter()
+            .map(|pair| pair.0)
+            .collect();
+        // one key over either way lands on S and D or off the keyboard, so it goes down a row
+        let shifted = wasd
+            .shifted(&taken)
+            .expect("[!] WASD should have somewhere to go");
+        assert_eq!(
+            shifted.keycodes_from_movement(Movement::Left),
+            vec![KeyCode::Z]
+        );
+        assert_eq!(
+            shifted.keycodes_from_movement(Movement::Down),
+            vec![KeyCode::X]
+        );
+        assert_eq!(
+            shifted.keycodes_from_movement(Movement::Right),
+            vec![KeyCode::C]
+        );
+        assert_eq!(
+            shifted.keycodes_from_movement(Movement::RotateCcw),
+            vec![KeyCode::M]
+        );
+
+        // the numpad has no room to move over, so it moves down a row
+        let numpad = KeyboardControlScheme::from_preset(2, &[Movement::Left, Movement::Right], &[]);
+        let shifted = numpad
+            .shifted(&[KeyCode::Numpad4, KeyCode::Numpad6])
+            .expect("[!] the numpad should have somewhere to go");
+        assert_eq!(
+            shifted.keycodes_from_movement(Movement::Left),
+            vec![KeyCode::Numpad1]
+        );
+
+        // arrow keys aren't on the grid
+        let arrows = KeyboardControlScheme::from_preset(0, &CLASSIC_MOVEMENTS, &[]);
+        assert!(arrows.shifted(&[]).is_none());
+    }
+
+    #[test]
+    fn restart_key_is_optional() {
+        let mut scheme = KeyboardControlScheme::from_preset(0, &CLASSIC_MOVEMENTS, &[]);
+        scheme.add_pair(KeyCode::R, Movement::Restart);
+        assert_eq!(
+            scheme.movement_from_keycode(KeyCode::R),
+            Some(Movement::Restart)
+        );
+        // it isn't one of the movements classic needs
+        assert_eq!(scheme.num_movements(), CLASSIC_MOVEMENTS.len());
     }
 }
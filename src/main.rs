@@ -2,18 +2,22 @@ use ggez::graphics;
 use ggez::input::gamepad::gilrs;
 use ggez::ContextBuilder;
 
-// file systems stuff
-use ggez::filesystem::resources_dir;
 use std::env;
-use std::fs::File;
-use std::path;
 
 // tetrisn-t files
 mod control;
 use control::Control;
 
+mod cli;
+use cli::CliOptions;
+
+mod config;
 mod game;
+mod highscores;
 mod menu;
+mod profiles;
+mod resources;
+mod theme;
 
 mod inputs;
 mod movement;
@@ -21,36 +25,30 @@ mod movement;
 use ggez::input::gamepad::GilrsGamepadContext;
 
 fn main() {
-    let mut context = ContextBuilder::new("Tetrisn-t", "Catcow")
-        .window_setup(ggez::conf::WindowSetup::default().title("Tetrisn't"));
-
-    // file systems stuff
-    if let Ok(manifest_dir) = env::var("CARGO_MANIFEST_DIR") {
-        let mut path = path::PathBuf::from(manifest_dir);
-        path.push("resources");
-        context = context.add_resource_path(path);
+    let cli_options = match CliOptions::parse(env::args().skip(1)) {
+        Ok(cli_options) => cli_options,
+        Err(e) => {
+            println!("[!] {}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if cli_options.help_flag {
+        println!("{}", cli::USAGE);
+        return;
     }
 
+    // resources are looked for in resources::resource_dirs, not through ggez's filesystem
+    let context = ContextBuilder::new("Tetrisn-t", "Catcow")
+        .window_setup(ggez::conf::WindowSetup::default().title("Tetrisn't"));
+
     let (mut ctx, event_loop) = context.build().expect("[!] Failed to build context");
 
     // custom controller setup stuffs
     let mut gilrs_builder = gilrs::GilrsBuilder::new().add_included_mappings(false);
 
-    match resources_dir(&ctx).join("gamecontrollerdb.txt").as_path().to_str() {
-        Some(path) => {
-            match std::fs::read_to_string(path) {
-                Ok(string) => {
-                    gilrs_builder = gilrs_builder.add_mappings(&string);
-                },
-                Err(_) => {
-                    println!("[!] couldn't read contents of {} to string; creating file; no custom controller layouts will function", path);
-                    if let Err(e) = File::create(resources_dir(&ctx).join("gamecontrollerdb.txt")) {
-                        println!("[!] failed to create file {}: {}", resources_dir(&ctx).join("gamecontrollerdb.txt").display(), e);
-                    }
-                },
-            }
-        },
-        None => println!("[!] couldn't obtain path for gamecontrollerdb.txt; no custom controller layouts will function"),
+    let vec_resource_dirs = resources::resource_dirs(&ctx, cli_options.resources.as_deref());
+    if let Some(string) = resources::load_gamepad_mappings(&vec_resource_dirs) {
+        gilrs_builder = gilrs_builder.add_mappings(&string);
     }
     ctx.gamepad_context = Box::new(GilrsGamepadContext::from(gilrs_builder.build().unwrap()));
 
@@ -62,7 +60,7 @@ fn main() {
     graphics::set_default_filter(&mut ctx, graphics::FilterMode::Nearest);
 
     // create an instance of the event handler
-    let control = Control::new(&mut ctx);
+    let control = Control::new(&mut ctx, &cli_options, &vec_resource_dirs);
 
     // loop that controls the ProgramState
     ggez::event::run(ctx, event_loop, control)
@@ -1,67 +1,119 @@
-use ggez::event::KeyCode;
+use ggez::event::{Axis, Button, GamepadId, KeyCode};
 use ggez::graphics;
 use ggez::Context;
 
+use crate::config;
 use crate::control::ProgramState;
+use crate::game::savegame;
 use crate::game::GameMode;
-use crate::inputs::Input;
+use crate::highscores::HighScores;
+use crate::inputs::{Input, MenuKeys};
+use crate::profiles::save_profiles;
 
 mod choosemode;
+mod highscores;
 mod inputconfig;
+mod lobby;
 pub mod menuhelpers;
+mod profiles;
 mod settings;
 mod start;
 use choosemode::ChooseModeMenu;
+use highscores::HighScoresMenu;
 use inputconfig::InputConfigMenu;
-use menuhelpers::GRAY;
+use lobby::LobbyMenu;
 use menuhelpers::{MenuGameOptions, MenuItemTrigger, MenuState};
+use profiles::ProfilesMenu;
 use settings::SettingsMenu;
 use start::StartMenu;
 
+use std::path::PathBuf;
+
 // we just have all the menu stuffs loaded into here because they're all connected and it's not much memory anyways
 pub struct Menu {
     // logic
     input: Input,
-    num_required_keycode_movement_pairs: usize,
     // states
     state: MenuState,
     choose_mode_menu: ChooseModeMenu,
     start_menu: StartMenu,
     settings_menu: SettingsMenu,
     input_config_menu: InputConfigMenu,
+    high_scores_menu: HighScoresMenu,
+    profiles_menu: ProfilesMenu,
+    lobby_menu: LobbyMenu,
     // window size
     window_dimensions: (f32, f32),
+    config_dir: PathBuf,
 }
 
 impl Menu {
     pub fn new(ctx: &mut Context, game_options: &MenuGameOptions) -> Self {
         let window_dimensions = graphics::size(ctx);
+        let saved_game_flag = savegame::exists(&config::data_dir(ctx));
         Self {
             input: Input::new(),
-            num_required_keycode_movement_pairs: game_options.game_mode.num_required_inputs(),
-            state: match game_options.game_mode {
-                GameMode::None => MenuState::ChooseMode,
-                _ => MenuState::Start,
+            // a saved game waiting to be continued is offered before anything else
+            state: if saved_game_flag || game_options.game_mode == GameMode::None {
+                MenuState::ChooseMode
+            } else {
+                MenuState::Start
             },
-            choose_mode_menu: ChooseModeMenu::new(game_options.game_mode, window_dimensions),
+            choose_mode_menu: ChooseModeMenu::new(
+                game_options.game_mode,
+                saved_game_flag,
+                game_options.theme(),
+                window_dimensions,
+            ),
             start_menu: StartMenu::new(game_options, window_dimensions),
-            settings_menu: SettingsMenu::new(&game_options.settings, window_dimensions),
+            settings_menu: SettingsMenu::new(game_options, window_dimensions),
             input_config_menu: InputConfigMenu::new(game_options, window_dimensions),
+            high_scores_menu: HighScoresMenu::new(
+                HighScores::load(&config::data_dir(ctx)),
+                game_options,
+                window_dimensions,
+            ),
+            profiles_menu: ProfilesMenu::new(game_options, window_dimensions),
+            lobby_menu: LobbyMenu::new(game_options, window_dimensions),
             window_dimensions,
+            config_dir: config::config_dir(ctx),
         }
     }
 
-    pub fn update(&mut self, game_options: &mut MenuGameOptions) -> Option<ProgramState> {
+    // the options are saved as soon as they change so they're there next time
+    pub fn update(
+        &mut self,
+        ctx: &mut Context,
+        game_options: &mut MenuGameOptions,
+    ) -> Option<ProgramState> {
+        let saved_options = config::SavedOptions::from(&*game_options);
+        let menu_keys = game_options.menu_keys;
+        let theme_index = game_options.theme_index;
+        let new_state = self.update_state(game_options);
+        // keys held from before the menu keys changed would otherwise never be let go
+        if game_options.menu_keys != menu_keys {
+            self.input.reset_all();
+        }
+        if game_options.theme_index != theme_index {
+            self.retheme(ctx, game_options);
+        }
+        if config::SavedOptions::from(&*game_options) != saved_options {
+            config::save_options(&self.config_dir, game_options);
+        }
+        new_state
+    }
+
+    fn update_state(&mut self, game_options: &mut MenuGameOptions) -> Option<ProgramState> {
         match self.state {
-            MenuState::ChooseMode => {
-                if let MenuItemTrigger::SubMenu(menu_state) =
-                    self.choose_mode_menu.update(&self.input)
-                {
+            MenuState::ChooseMode => match self.choose_mode_menu.update(&self.input) {
+                MenuItemTrigger::ContinueGame => return Some(ProgramState::Continue),
+                MenuItemTrigger::SubMenu(MenuState::HighScores) => {
+                    self.state = MenuState::HighScores;
+                }
+                MenuItemTrigger::SubMenu(menu_state) => {
                     self.state = menu_state;
                     if game_options.game_mode != self.choose_mode_menu.game_mode {
                         game_options.game_mode = self.choose_mode_menu.game_mode;
-                        self.num_required_keycode_movement_pairs =
-                            game_options.game_mode.num_required_inputs();
                         self.start_menu.set_game_mode(
                             self.choose_mode_menu.game_mode,
                             game_options,
@@ -71,19 +123,24 @@ impl Menu {
                             .update_game_mode(self.window_dimensions, game_options);
                     }
                 }
-            }
+                _ => {}
+            },
             MenuState::Start => {
                 let trigger: MenuItemTrigger = self.start_menu.update(&self.input, game_options);
                 match trigger {
                     MenuItemTrigger::StartGame => {
-                        if self.ensure_enough_controls(game_options) {
-                            return Some(ProgramState::Game);
-                        } else {
+                        if !game_options.enough_controls() {
                             self.start_menu.not_enough_controls_flag = true;
+                        } else if LobbyMenu::is_needed(game_options) {
+                            // gamepad players pick their gamepads first
+                            self.lobby_menu = LobbyMenu::new(game_options, self.window_dimensions);
+                            self.state = MenuState::Lobby;
+                        } else {
+                            return Some(ProgramState::Game);
                         }
                     }
                     MenuItemTrigger::SubMenu(menu_state) => {
-                        // Settings menu or InputConfig menu
+                        // Settings, InputConfig or Profiles menu
                         self.start_menu.not_enough_controls_flag = false;
                         self.state = menu_state;
                     }
@@ -95,99 +152,129 @@ impl Menu {
                 }
             }
             MenuState::Settings => {
-                if self
-                    .settings_menu
-                    .update(&self.input, &mut game_options.settings)
-                    == MenuItemTrigger::Back
-                {
+                if self.settings_menu.update(&self.input, game_options) == MenuItemTrigger::Back {
                     self.state = MenuState::Start;
                 }
             }
+            MenuState::HighScores => {
+                if self.high_scores_menu.update(&self.input) == MenuItemTrigger::Back {
+                    self.state = MenuState::ChooseMode;
+                }
+            }
             MenuState::InputConfig => {
                 if self.input_config_menu.update(&self.input, game_options) {
+                    config::save_controls(
+                        &self.config_dir,
+                        &game_options.arr_controls,
+                        &game_options.arr_gamepad_controls,
+                        &game_options.arr_profile_names,
+                        &game_options.arr_socd,
+                        &game_options.menu_keys,
+                    );
+                    // rebinding a slot with a profile changes the profile's controls
+                    save_profiles(&self.config_dir, &game_options.profiles);
+                    self.state = MenuState::Start;
+                }
+            }
+            MenuState::Profiles => {
+                if self.profiles_menu.update(&self.input, game_options) == MenuItemTrigger::Back {
+                    save_profiles(&self.config_dir, &game_options.profiles);
+                    // deleting a profile takes it out of its slot
+                    config::save_controls(
+                        &self.config_dir,
+                        &game_options.arr_controls,
+                        &game_options.arr_gamepad_controls,
+                        &game_options.arr_profile_names,
+                        &game_options.arr_socd,
+                        &game_options.menu_keys,
+                    );
+                    self.input_config_menu.update_profile_text(game_options);
                     self.state = MenuState::Start;
                 }
             }
+            MenuState::Lobby => match self.lobby_menu.update(&self.input, game_options) {
+                MenuItemTrigger::StartGame => return Some(ProgramState::Game),
+                MenuItemTrigger::Back => self.state = MenuState::Start,
+                _ => {}
+            },
         }
 
         self.input.was_just_pressed_setfalse();
         None
     }
 
-    fn ensure_enough_controls(&self, game_options: &MenuGameOptions) -> bool {
-        let mut ctrls_count = 0;
-        for ctrls in game_options.arr_controls.iter() {
-            if ctrls.1 || (ctrls.0).len() >= self.num_required_keycode_movement_pairs {
-                ctrls_count += 1;
-            }
-        }
-        ctrls_count >= game_options.num_players
+    // the colors are baked into the menus' texts when they're made, so every other menu is made again;
+    // the settings menu is the one being used, so it only gets recolored
+    fn retheme(&mut self, ctx: &mut Context, game_options: &MenuGameOptions) {
+        let menu = Menu::new(ctx, game_options);
+        self.choose_mode_menu = menu.choose_mode_menu;
+        self.start_menu = menu.start_menu;
+        self.input_config_menu = menu.input_config_menu;
+        self.high_scores_menu = menu.high_scores_menu;
+        self.profiles_menu = menu.profiles_menu;
+        self.lobby_menu = menu.lobby_menu;
+        self.settings_menu.set_theme(game_options.theme());
     }
 
-    pub fn key_down_event(&mut self, keycode: KeyCode, _repeat: bool) {
+    pub fn key_down_event(&mut self, keycode: KeyCode, _repeat: bool, menu_keys: &MenuKeys) {
         self.input_config_menu.most_recently_pressed_key = Some(keycode);
-        if keycode == KeyCode::Left {
-            if !self.input.keydown_left.0 {
-                self.input.keydown_left = (true, true);
-            }
-        } else if keycode == KeyCode::Right {
-            if !self.input.keydown_right.0 {
-                self.input.keydown_right = (true, true);
-            }
-        } else if keycode == KeyCode::Down {
-            if !self.input.keydown_down.0 {
-                self.input.keydown_down = (true, true);
-            }
-        } else if keycode == KeyCode::Up {
-            if !self.input.keydown_up.0 {
-                self.input.keydown_up = (true, true);
-            }
-        } else if keycode == KeyCode::G {
-            if !self.input.keydown_rotate_cw.0 {
-                self.input.keydown_rotate_cw = (true, true);
-            }
-        } else if keycode == KeyCode::Escape {
-            if !self.input.keydown_rotate_ccw.0 {
-                self.input.keydown_rotate_ccw = (true, true);
-            }
-        } else if (keycode == KeyCode::Space
-            || keycode == KeyCode::Return
-            || keycode == KeyCode::NumpadEnter)
-            && !self.input.keydown_start.0
-        {
-            self.input.keydown_start = (true, true);
+        if self.state == MenuState::Profiles && self.profiles_menu.key_down_event(keycode) {
+            return;
         }
+        self.input.update_menu_keydown(keycode, menu_keys);
     }
 
-    pub fn key_up_event(&mut self, keycode: KeyCode) {
-        if keycode == KeyCode::Left {
-            self.input.keydown_left = (false, false);
-        } else if keycode == KeyCode::Right {
-            self.input.keydown_right = (false, false);
-        } else if keycode == KeyCode::Down {
-            self.input.keydown_down = (false, false);
-        } else if keycode == KeyCode::Up {
-            self.input.keydown_up = (false, false);
-        } else if keycode == KeyCode::G {
-            self.input.keydown_rotate_cw = (false, false);
-        } else if keycode == KeyCode::Escape {
-            self.input.keydown_rotate_ccw = (false, false);
-        } else if keycode == KeyCode::Space
-            || keycode == KeyCode::Return
-            || keycode == KeyCode::NumpadEnter
-        {
-            self.input.keydown_start = (false, false);
+    pub fn key_up_event(&mut self, keycode: KeyCode, menu_keys: &MenuKeys) {
+        self.input.update_menu_keyup(keycode, menu_keys);
+    }
+
+    // any gamepad can drive the menus, except while one is being remapped or gamepads are picking their spots in the lobby
+    pub fn gamepad_button_down_event(&mut self, ctx: &Context, btn: Button, id: GamepadId) {
+        if self.state == MenuState::InputConfig && self.input_config_menu.is_remapping_gamepad() {
+            self.input_config_menu.gamepad_button_down_event(btn);
+        } else if self.state == MenuState::Lobby {
+            self.lobby_menu.gamepad_button_down_event(ctx, btn, id);
+        } else {
+            self.input.update_menu_buttondown(btn);
+        }
+    }
+
+    pub fn gamepad_button_up_event(&mut self, btn: Button) {
+        self.input.update_menu_buttonup(btn);
+    }
+
+    // sticks are read with the settings of whoever claimed the gamepad in the lobby
+    pub fn gamepad_axis_event(
+        &mut self,
+        axis: Axis,
+        value: f32,
+        id: GamepadId,
+        game_options: &MenuGameOptions,
+    ) {
+        if self.state == MenuState::InputConfig {
+            self.input_config_menu
+                .gamepad_axis_event(axis, value, game_options);
+            if self.input_config_menu.is_remapping_gamepad() {
+                return;
+            }
+        }
+        if self.state != MenuState::Lobby {
+            self.input
+                .update_menu_axis(axis, value, &game_options.gamepad_sticks(id));
         }
     }
 
     pub fn draw(&mut self, ctx: &mut Context, game_options: &MenuGameOptions) {
-        graphics::clear(ctx, GRAY);
+        graphics::clear(ctx, game_options.theme().menu_background_color());
 
         match self.state {
             MenuState::ChooseMode => self.choose_mode_menu.draw(ctx),
             MenuState::Start => self.start_menu.draw(ctx),
             MenuState::Settings => self.settings_menu.draw(ctx),
             MenuState::InputConfig => self.input_config_menu.draw(ctx, game_options),
+            MenuState::HighScores => self.high_scores_menu.draw(ctx),
+            MenuState::Profiles => self.profiles_menu.draw(ctx, game_options),
+            MenuState::Lobby => self.lobby_menu.draw(ctx),
         }
     }
 
@@ -197,5 +284,8 @@ impl Menu {
         self.start_menu.resize_event(window_dims.1);
         self.settings_menu.resize_event(window_dims.1);
         self.input_config_menu.resize_event(window_dims.1);
+        self.high_scores_menu.resize_event(window_dims.1);
+        self.profiles_menu.resize_event(window_dims.1);
+        self.lobby_menu.resize_event(window_dims.1);
     }
 }
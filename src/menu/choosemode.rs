@@ -6,9 +6,12 @@ use crate::game::GameMode;
 use crate::inputs::Input;
 use crate::menu::menuhelpers::TEXT_SCALE_DOWN;
 use crate::menu::menuhelpers::{MenuItem, MenuItemTrigger, MenuState};
+use crate::theme::Theme;
 
 enum ChooseModeMenuItemId {
+    Continue,
     Mode,
+    HighScores,
 }
 
 pub struct ChooseModeMenu {
@@ -20,8 +23,14 @@ pub struct ChooseModeMenu {
 }
 
 impl ChooseModeMenu {
-    pub fn new(game_mode: GameMode, window_dimensions: (f32, f32)) -> Self {
-        let mut vec_menu_items: Vec<MenuItem> = Vec::with_capacity(1);
+    // `saved_game_flag` puts "Continue" at the top for picking a saved game back up
+    pub fn new(
+        game_mode: GameMode,
+        saved_game_flag: bool,
+        theme: &Theme,
+        window_dimensions: (f32, f32),
+    ) -> Self {
+        let mut vec_menu_items: Vec<MenuItem> = Vec::with_capacity(3);
         vec_menu_items.push(MenuItem::new_customvalue(
             "Mode: ",
             ChooseModeMenuItemId::Mode as u8,
@@ -33,6 +42,7 @@ impl ChooseModeMenu {
             },
             2,
             MenuItemTrigger::SubMenu(MenuState::Start),
+            theme,
             window_dimensions.1,
             TEXT_SCALE_DOWN,
         ));
@@ -42,6 +52,27 @@ impl ChooseModeMenu {
             game_mode
         };
         vec_menu_items[0].text.fragments_mut()[1].text = format!("{:?}", game_mode_for_text);
+        vec_menu_items.push(MenuItem::new_novalue(
+            "High Scores",
+            ChooseModeMenuItemId::HighScores as u8,
+            MenuItemTrigger::SubMenu(MenuState::HighScores),
+            theme,
+            window_dimensions.1,
+            TEXT_SCALE_DOWN,
+        ));
+        if saved_game_flag {
+            vec_menu_items.insert(
+                0,
+                MenuItem::new_novalue(
+                    "Continue",
+                    ChooseModeMenuItemId::Continue as u8,
+                    MenuItemTrigger::ContinueGame,
+                    theme,
+                    window_dimensions.1,
+                    TEXT_SCALE_DOWN,
+                ),
+            );
+        }
         vec_menu_items[0].set_select(true);
         Self {
             // logic
@@ -55,10 +86,8 @@ impl ChooseModeMenu {
     }
 
     pub fn update(&mut self, input: &Input) -> MenuItemTrigger {
-        if input.keydown_left.1
-            || input.keydown_right.1
-            || input.keydown_down.1
-            || input.keydown_up.1
+        if (input.keydown_left.1 || input.keydown_right.1)
+            && self.vec_menu_items[self.selection].id == ChooseModeMenuItemId::Mode as u8
         {
             self.vec_menu_items[self.selection].change_val(true);
             self.game_mode = GameMode::from(self.vec_menu_items[self.selection].value as usize);
@@ -66,6 +95,22 @@ impl ChooseModeMenu {
                 format!("{:?}", self.game_mode);
         }
 
+        if input.keydown_down.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = (self.selection + 1) % self.vec_menu_items.len();
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if input.keydown_up.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = if self.selection == 0 {
+                self.vec_menu_items.len() - 1
+            } else {
+                self.selection - 1
+            };
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
         if input.keydown_start.1 {
             return self.vec_menu_items[self.selection].trigger.clone();
         }
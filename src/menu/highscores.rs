@@ -0,0 +1,254 @@
+use ggez::graphics::{self, DrawParam, Font, PxScale, Text, TextFragment};
+use ggez::mint::Point2;
+use ggez::Context;
+
+use crate::game::stats::format_frames;
+use crate::game::GameMode;
+use crate::highscores::{format_date, HighScoreKey, HighScores};
+use crate::inputs::Input;
+use crate::menu::menuhelpers::{MenuGameOptions, MenuItem, MenuItemTrigger};
+use crate::menu::menuhelpers::{MAX_NUM_PLAYERS, MAX_STARTING_LEVEL, SUB_TEXT_SCALE_DOWN};
+
+// ten rows plus a header have to fit under the filters
+const TABLE_TEXT_SCALE_DOWN: f32 = 32.0;
+
+enum HighScoresMenuItemId {
+    Back,
+    Mode,
+    StartingLevel,
+    NumPlayers,
+}
+
+pub struct HighScoresMenu {
+    // logic
+    selection: usize,
+    high_scores: HighScores,
+    vec_menu_items: Vec<MenuItem>,
+    // drawing
+    table_text: Text,
+    text_color: graphics::Color,
+    window_height: f32,
+}
+
+impl HighScoresMenu {
+    pub fn new(
+        high_scores: HighScores,
+        game_options: &MenuGameOptions,
+        window_dimensions: (f32, f32),
+    ) -> Self {
+        // start out looking at the table for whatever was played last
+        let game_mode = match game_options.game_mode {
+            GameMode::None => GameMode::Classic,
+            game_mode => game_mode,
+        };
+        let mut vec_menu_items: Vec<MenuItem> = vec![
+            MenuItem::new_novalue(
+                "Back",
+                HighScoresMenuItemId::Back as u8,
+                MenuItemTrigger::Back,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "Mode: ",
+                HighScoresMenuItemId::Mode as u8,
+                &format!("{:?}", game_mode),
+                match game_mode {
+                    GameMode::Rotatris => 1,
+                    _ => 0,
+                },
+                2,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_numericalvalue(
+                "Starting Level: ",
+                HighScoresMenuItemId::StartingLevel as u8,
+                game_options.starting_level,
+                0,
+                MAX_STARTING_LEVEL + 1,
+                0,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_numericalvalue(
+                "Players: ",
+                HighScoresMenuItemId::NumPlayers as u8,
+                game_options.num_players,
+                1,
+                MAX_NUM_PLAYERS,
+                0,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+        ];
+        vec_menu_items[0].set_select(true);
+        let mut high_scores_menu = Self {
+            // logic
+            selection: 0,
+            high_scores,
+            vec_menu_items,
+            // drawing
+            table_text: Text::default(),
+            text_color: game_options.theme().menu_text_color(),
+            window_height: window_dimensions.1,
+        };
+        high_scores_menu.update_table_text();
+        high_scores_menu
+    }
+
+    pub fn update(&mut self, input: &Input) -> MenuItemTrigger {
+        if input.keydown_rotate_ccw.1 {
+            // escape was pressed
+            return MenuItemTrigger::Back;
+        }
+
+        if input.keydown_right.1 || input.keydown_left.1 {
+            let item = &mut self.vec_menu_items[self.selection];
+            item.change_val(input.keydown_right.1);
+            if item.id == HighScoresMenuItemId::Mode as u8 {
+                item.text.fragments_mut()[1].text =
+                    format!("{:?}", GameMode::from(item.value as usize));
+            }
+            self.update_table_text();
+        }
+
+        if input.keydown_down.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = (self.selection + 1) % self.vec_menu_items.len();
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if input.keydown_up.1 {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = if self.selection == 0 {
+                self.vec_menu_items.len() - 1
+            } else {
+                self.selection - 1
+            };
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if input.keydown_start.1 {
+            return self.vec_menu_items[self.selection].trigger.clone();
+        }
+
+        MenuItemTrigger::None
+    }
+
+    fn get_value(&self, id: HighScoresMenuItemId) -> u8 {
+        let id = id as u8;
+        for item in self.vec_menu_items.iter() {
+            if item.id == id {
+                return item.value;
+            }
+        }
+        unreachable!("Failed to get a filter value in Menu::HighScores");
+    }
+
+    fn get_key(&self) -> HighScoreKey {
+        let game_mode = GameMode::from(self.get_value(HighScoresMenuItemId::Mode) as usize);
+        HighScoreKey {
+            game_mode,
+            starting_level: self.get_value(HighScoresMenuItemId::StartingLevel),
+            // rotatris is always single player
+            num_players: if game_mode == GameMode::Rotatris {
+                1
+            } else {
+                self.get_value(HighScoresMenuItemId::NumPlayers)
+            },
+        }
+    }
+
+    // rotatris is ranked by rings, so that column comes first there
+    fn update_table_text(&mut self) {
+        let key = self.get_key();
+        let entries = self.high_scores.get_entries(key);
+        let mut table = if key.game_mode == GameMode::Rotatris {
+            String::from("#    Name    Rings    Score    Level    Time    Date\n")
+        } else {
+            String::from("#    Name    Score    Lines    Level    Time    Date\n")
+        };
+        if entries.is_empty() {
+            table.push_str("\nNo scores yet\n");
+        }
+        for (place, entry) in entries.iter().enumerate() {
+            let (first, second) = if key.game_mode == GameMode::Rotatris {
+                (entry.lines as u64, entry.score)
+            } else {
+                (entry.score, entry.lines as u64)
+            };
+            table.push_str(&format!(
+                "{}.    {}    {}    {}    {}    {}    {}\n",
+                place + 1,
+                // profile names when the players had them, otherwise the initials typed in at the end
+                if entry.names.is_empty() {
+                    entry.initials.clone()
+                } else {
+                    entry.names.join(", ")
+                },
+                first,
+                second,
+                entry.level,
+                format_frames(entry.frames),
+                format_date(entry.date)
+            ));
+        }
+        self.table_text = Text::new(TextFragment::new(table).color(self.text_color));
+        self.table_text.set_font(
+            Font::default(),
+            PxScale::from(self.window_height / TABLE_TEXT_SCALE_DOWN),
+        );
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) {
+        let window_dimensions = graphics::size(ctx);
+
+        for (index, item) in self.vec_menu_items.iter().enumerate() {
+            self.draw_text(
+                ctx,
+                &item.text,
+                0.04 + 0.07 * index as f32,
+                &window_dimensions,
+            );
+        }
+        self.draw_text(ctx, &self.table_text, 0.85, &window_dimensions);
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text_var: &Text,
+        vertical_position: f32,
+        window_dimensions: &(f32, f32),
+    ) {
+        let text_var_dimensions = text_var.dimensions(ctx);
+        graphics::draw(
+            ctx,
+            text_var,
+            DrawParam::new().dest(Point2::from_slice(&[
+                (window_dimensions.0 - text_var_dimensions.w) / 2.0,
+                (window_dimensions.1 - text_var_dimensions.h) * vertical_position,
+            ])),
+        )
+        .unwrap();
+    }
+
+    pub fn resize_event(&mut self, height: f32) {
+        self.window_height = height;
+        for item in self.vec_menu_items.iter_mut() {
+            item.resize(height);
+        }
+        self.table_text.set_font(
+            Font::default(),
+            PxScale::from(height / TABLE_TEXT_SCALE_DOWN),
+        );
+    }
+}
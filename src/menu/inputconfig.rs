@@ -1,28 +1,55 @@
-use ggez::event::KeyCode;
+use ggez::event::{Axis, Button, KeyCode};
 use ggez::graphics::{self, DrawParam, Font, PxScale, Text, TextFragment};
 use ggez::mint::Point2;
 use ggez::Context;
 
+use crate::control::DESIRED_FPS;
 use crate::game::GameMode;
-use crate::inputs::{Input, KeyboardControlScheme};
+use crate::inputs::{GamepadControlScheme, GamepadInput, Input, KeyboardControlScheme};
+use crate::inputs::{MenuKeys, MAX_KEYS_PER_MOVEMENT, MENU_KEY_NAMES, NUM_MENU_KEYS};
+use crate::inputs::{MovementStick, StickSettings, NUM_MOVEMENT_STICKS};
+use crate::inputs::{SocdPolicy, NUM_SOCD_POLICIES};
+use crate::inputs::{KEYBOARD_PRESETS, NUM_KEYBOARD_PRESETS};
+use crate::inputs::{MAX_DEADZONE, MAX_HYSTERESIS, MIN_HYSTERESIS};
 use crate::movement::Movement;
 use crate::movement::CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER;
 
 use crate::menu::menuhelpers::GAME_MODE_UNEXPECTEDLY_NONE;
 use crate::menu::menuhelpers::MAX_NUM_PLAYERS;
 use crate::menu::menuhelpers::{MenuGameOptions, MenuItem, MenuItemTrigger};
-use crate::menu::menuhelpers::{DARK_GRAY, HELP_RED, LIGHT_GRAY};
 use crate::menu::menuhelpers::{SUB_TEXT_SCALE_DOWN, TEXT_SCALE_DOWN};
 
 use std::convert::TryFrom;
 
 const MAX_NON_START_INPUTS_PER_PLAYER: usize = 8;
 
+// how long new menu keys have to be confirmed with the new select key before the old ones come back
+const MENU_KEYS_CONFIRM_SECONDS: u32 = 10;
+
 enum InputConfigMenuItemId {
     Back,
     PlayerNum,
+    Profile,
+    QuickSetup,
+    Socd,
+    Sticks,
+    MenuKeys,
+}
+
+enum StickItemId {
+    Deadzone,
+    Hysteresis,
+    InvertX,
+    InvertY,
+    MovementStick,
+    BoardStick,
 }
 
+const NUM_STICK_ITEMS: usize = 6;
+
+// deadzone and hysteresis go up and down in steps of this many percent
+const STICK_PERCENT_STEP: u8 = 5;
+
 enum InputConfigControlsId {
     Left,
     Right,
@@ -31,11 +58,27 @@ enum InputConfigControlsId {
     RotateCcw,
     BoardRotateCw,
     BoardRotateCcw,
+    Restart,
 }
 
+static NO_PROFILE_STR: &str = "None";
+
 static KEY_UNEXPECTEDLY_NONE: &str =
     "[!] KeyCode of most recently pressed key is unexpectedly None";
 
+static SCHEME_UNEXPECTEDLY_NONE: &str =
+    "[!] KeyboardControlScheme from before adding keys is unexpectedly None";
+
+static GAMEPAD_SCHEME_UNEXPECTEDLY_NONE: &str =
+    "[!] GamepadControlScheme from before remapping is unexpectedly None";
+
+static MENU_KEYS_UNEXPECTEDLY_NONE: &str =
+    "[!] MenuKeys from before the change is unexpectedly None";
+
+static SOCD_VALUE_OUT_OF_RANGE: &str = "[!] SOCD menu item value is unexpectedly out of range";
+
+static MOVEMENT_STICK_OUT_OF_RANGE: &str = "[!] Movement stick value is unexpectedly out of range";
+
 pub struct InputConfigMenu {
     // logic
     selection: usize,
@@ -44,31 +87,73 @@ pub struct InputConfigMenu {
     sub_selection_keyboard_flag: bool,
     pub most_recently_pressed_key: Option<KeyCode>,
     vec_used_keycode: Vec<KeyCode>,
+    // set while going over the movements again to give them more keys ('Tab'), so 'Escape' can undo it
+    scheme_before_adding_keys: Option<KeyboardControlScheme>,
     keycode_conflict_flag: bool,
+    sub_selection_gamepad_flag: bool,
+    // set while a gamepad is being remapped, so 'Escape' can put its old layout back
+    gamepad_scheme_before_remap: Option<GamepadControlScheme>,
+    most_recently_pressed_gamepad_input: Option<GamepadInput>,
+    // sticks have to come back to the center before they count again
+    vec_held_axis_input: Vec<GamepadInput>,
+    gamepad_conflict_flag: bool,
+    profile_conflict_flag: bool,
+    sub_selection_menu_keys_flag: bool,
+    vec_captured_menu_keys: Vec<KeyCode>,
+    // set until the new menu keys are confirmed, so they can go back if they don't work out
+    menu_keys_before_change: Option<MenuKeys>,
+    menu_keys_countdown: u32,
+    menu_keys_reverted_flag: bool,
+    sub_selection_quick_setup: usize,
+    sub_selection_quick_setup_flag: bool,
+    // the player whose keys the last row copies, shifted onto free keys
+    copy_from_slot: Option<usize>,
+    quick_setup_failed_flag: bool,
+    // set while asking for keys for the movements a preset couldn't fill, since their keys were taken
+    filling_gaps_flag: bool,
+    sub_selection_sticks: usize,
+    sub_selection_sticks_flag: bool,
+    stick_conflict_flag: bool,
+    // the last raw value of each stick axis, so drift can be seen: left x, left y, right x, right y
+    arr_stick_readout: [f32; 4],
     // text
     vec_menu_items_main: Vec<MenuItem>,
     // subtext
     vec_menu_items_keycode: Vec<MenuItem>,
     input_uninitialized_text: Text,
     keycode_conflict_text: Text,
+    gamepad_conflict_text: Text,
+    profile_conflict_text: Text,
+    socd_text: Text,
     is_gamepad_text: Text,
+    add_keys_text: Text,
+    adding_keys_text: Text,
+    restart_key_text: Text,
+    vec_menu_items_menu_keys: Vec<MenuItem>,
+    menu_keys_capture_text: Text,
+    menu_keys_confirm_text: Text,
+    menu_keys_reverted_text: Text,
+    vec_menu_items_quick_setup: Vec<MenuItem>,
+    quick_setup_help_text: Text,
+    quick_setup_failed_text: Text,
+    filling_gaps_text: Text,
+    vec_menu_items_sticks: Vec<MenuItem>,
+    sticks_help_text: Text,
+    sticks_editing_text: Text,
+    stick_conflict_text: Text,
+    stick_readout_text: Text,
 }
 
 impl InputConfigMenu {
     pub fn new(game_options: &MenuGameOptions, window_dimensions: (f32, f32)) -> Self {
-        let mut vec_used_keycode: Vec<KeyCode> = vec![];
-        // gather what the starting used keycodes should be
-        for ctrls in game_options.arr_controls.iter() {
-            for key_move_pair in (ctrls.0).vec_keycode_movement_pair.iter() {
-                vec_used_keycode.push(key_move_pair.0);
-            }
-        }
+        let vec_used_keycode: Vec<KeyCode> = Self::used_keycodes(game_options);
         // main MenuItems
         let mut vec_menu_items_main: Vec<MenuItem> = vec![
             MenuItem::new_novalue(
                 "Back",
                 InputConfigMenuItemId::Back as u8,
                 MenuItemTrigger::Back,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -80,6 +165,55 @@ impl InputConfigMenu {
                 MAX_NUM_PLAYERS,
                 1,
                 MenuItemTrigger::SubSelection,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "Profile: ",
+                InputConfigMenuItemId::Profile as u8,
+                game_options.arr_profile_names[0]
+                    .as_deref()
+                    .unwrap_or(NO_PROFILE_STR),
+                0,
+                1,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Quick Setup",
+                InputConfigMenuItemId::QuickSetup as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "SOCD: ",
+                InputConfigMenuItemId::Socd as u8,
+                game_options.arr_socd[0].name(),
+                game_options.arr_socd[0] as u8,
+                NUM_SOCD_POLICIES,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Gamepad Sticks",
+                InputConfigMenuItemId::Sticks as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Menu Keys",
+                InputConfigMenuItemId::MenuKeys as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -103,31 +237,167 @@ impl InputConfigMenu {
                 window_dimensions,
             ),
         }
-        let input_uninitialized_text = Text::new(
-            TextFragment::new("No Controls\nKeyboard: Space/Enter\nGamepad: 'G'").color(HELP_RED),
-        );
+        let text_color = game_options.theme().menu_text_color();
+        let help_color = game_options.theme().menu_help_color();
+        let input_uninitialized_text = Text::new(TextFragment::new("").color(help_color));
         let keycode_conflict_text =
-            Text::new(TextFragment::new("[!] Redundant KeyCode; ignoring").color(HELP_RED));
-        let is_gamepad_text = Text::new(
-            TextFragment::new("Set to Gamepad\n\n\nSee README for help")
-                .color(ggez::graphics::Color::BLACK),
+            Text::new(TextFragment::new("[!] Redundant KeyCode; ignoring").color(help_color));
+        let gamepad_conflict_text = Text::new(
+            TextFragment::new("[!] Button already bound or reserved; ignoring").color(help_color),
+        );
+        let profile_conflict_text =
+            Text::new(TextFragment::new("[!] Took keys from another player").color(help_color));
+        let socd_text = Text::new(TextFragment::new("").color(text_color));
+        let is_gamepad_text = Text::new(TextFragment::new("").color(text_color));
+        let add_keys_text =
+            Text::new(TextFragment::new("'Tab' to add more keys").color(text_color));
+        let adding_keys_text = Text::new(TextFragment::new("").color(text_color));
+        let restart_key_text = Text::new(
+            TextFragment::new("A key to restart the game with, or 'Tab' for none")
+                .color(text_color),
+        );
+        let mut vec_menu_items_menu_keys: Vec<MenuItem> = Vec::with_capacity(NUM_MENU_KEYS);
+        for (index, (name, keycode)) in MENU_KEY_NAMES
+            .iter()
+            .zip(game_options.menu_keys.keys().iter())
+            .enumerate()
+        {
+            vec_menu_items_menu_keys.push(MenuItem::new_keycodevalue(
+                &format!("{}: ", name),
+                index as u8,
+                &[*keycode],
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ));
+        }
+        let menu_keys_capture_text =
+            Text::new(TextFragment::new("Press a key for each, in order").color(text_color));
+        let menu_keys_confirm_text = Text::new(TextFragment::new("").color(help_color));
+        let menu_keys_reverted_text = Text::new(
+            TextFragment::new("[!] The new menu keys weren't confirmed; kept the old ones")
+                .color(help_color),
         );
-        Self {
+        let mut vec_menu_items_quick_setup: Vec<MenuItem> =
+            Vec::with_capacity(NUM_KEYBOARD_PRESETS + 1);
+        for (index, (name, _)) in KEYBOARD_PRESETS.iter().enumerate() {
+            vec_menu_items_quick_setup.push(MenuItem::new_novalue(
+                name,
+                index as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ));
+        }
+        vec_menu_items_quick_setup.push(MenuItem::new_customvalue(
+            "Shifted Copy of: ",
+            NUM_KEYBOARD_PRESETS as u8,
+            NO_PROFILE_STR,
+            0,
+            1,
+            MenuItemTrigger::None,
+            game_options.theme(),
+            window_dimensions.1,
+            SUB_TEXT_SCALE_DOWN,
+        ));
+        let quick_setup_help_text = Text::new(
+            TextFragment::new("Space to fill in this player's keys in one go").color(text_color),
+        );
+        let quick_setup_failed_text = Text::new(
+            TextFragment::new("[!] Those keys can't be shifted onto free keys").color(help_color),
+        );
+        let filling_gaps_text = Text::new(TextFragment::new("").color(help_color));
+        let mut vec_menu_items_sticks: Vec<MenuItem> = Vec::with_capacity(NUM_STICK_ITEMS);
+        for (index, title) in [
+            "Deadzone: ",
+            "Hysteresis: ",
+            "Invert X: ",
+            "Invert Y: ",
+            "Movement Stick: ",
+            "Board Stick (Rotatris): ",
+        ]
+        .iter()
+        .enumerate()
+        {
+            vec_menu_items_sticks.push(MenuItem::new_customvalue(
+                title,
+                index as u8,
+                "",
+                0,
+                1,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ));
+        }
+        let sticks_help_text = Text::new(TextFragment::new("").color(text_color));
+        let sticks_editing_text = Text::new(TextFragment::new("").color(text_color));
+        let stick_conflict_text = Text::new(TextFragment::new("").color(help_color));
+        let stick_readout_text = Text::new(TextFragment::new("").color(text_color));
+        let mut input_config_menu = Self {
             selection: 0,
             player_num: 0,
             sub_selection_keyboard: 0,
             sub_selection_keyboard_flag: false,
             most_recently_pressed_key: None,
             vec_used_keycode,
+            scheme_before_adding_keys: None,
             keycode_conflict_flag: false,
+            sub_selection_gamepad_flag: false,
+            gamepad_scheme_before_remap: None,
+            most_recently_pressed_gamepad_input: None,
+            vec_held_axis_input: vec![],
+            gamepad_conflict_flag: false,
+            profile_conflict_flag: false,
+            sub_selection_menu_keys_flag: false,
+            vec_captured_menu_keys: Vec::with_capacity(NUM_MENU_KEYS),
+            menu_keys_before_change: None,
+            menu_keys_countdown: 0,
+            menu_keys_reverted_flag: false,
+            sub_selection_quick_setup: 0,
+            sub_selection_quick_setup_flag: false,
+            copy_from_slot: None,
+            quick_setup_failed_flag: false,
+            filling_gaps_flag: false,
+            sub_selection_sticks: 0,
+            sub_selection_sticks_flag: false,
+            stick_conflict_flag: false,
+            arr_stick_readout: [0.0; 4],
             // text
             vec_menu_items_main,
             // subtext
             vec_menu_items_keycode,
             input_uninitialized_text,
             keycode_conflict_text,
+            gamepad_conflict_text,
+            profile_conflict_text,
+            socd_text,
             is_gamepad_text,
-        }
+            add_keys_text,
+            adding_keys_text,
+            restart_key_text,
+            vec_menu_items_menu_keys,
+            menu_keys_capture_text,
+            menu_keys_confirm_text,
+            menu_keys_reverted_text,
+            vec_menu_items_quick_setup,
+            quick_setup_help_text,
+            quick_setup_failed_text,
+            filling_gaps_text,
+            vec_menu_items_sticks,
+            sticks_help_text,
+            sticks_editing_text,
+            stick_conflict_text,
+            stick_readout_text,
+        };
+        input_config_menu.update_menu_key_texts(&game_options.menu_keys);
+        input_config_menu.update_socd_text(game_options);
+        input_config_menu.update_all_sub_text_strings(game_options);
+        input_config_menu.update_stick_readout_text();
+        input_config_menu
     }
 
     pub fn update_game_mode(
@@ -137,8 +407,8 @@ impl InputConfigMenu {
     ) {
         self.vec_menu_items_keycode.clear();
         let game_mode = game_options.game_mode;
-        game_options.reset_controls();
-        self.vec_used_keycode.clear();
+        game_options.clear_incomplete_controls();
+        self.vec_used_keycode = Self::used_keycodes(game_options);
         match game_mode {
             GameMode::None => unreachable!("{}", GAME_MODE_UNEXPECTEDLY_NONE),
             GameMode::Classic => Self::setup_classic_mode_subtext(
@@ -152,6 +422,17 @@ impl InputConfigMenu {
                 window_dimensions,
             ),
         }
+        self.update_all_sub_text_strings(game_options);
+    }
+
+    fn used_keycodes(game_options: &MenuGameOptions) -> Vec<KeyCode> {
+        let mut vec_used_keycode: Vec<KeyCode> = vec![];
+        for ctrls in game_options.arr_controls.iter() {
+            for key_move_pair in (ctrls.0).vec_keycode_movement_pair.iter() {
+                vec_used_keycode.push(key_move_pair.0);
+            }
+        }
+        vec_used_keycode
     }
 
     fn setup_classic_mode_subtext(
@@ -161,6 +442,7 @@ impl InputConfigMenu {
     ) {
         Self::setup_left_right_down_subtext(vec_to_add_to, game_options, window_dimensions);
         Self::setup_rotate_piece_subtext(vec_to_add_to, game_options, window_dimensions);
+        Self::setup_restart_subtext(vec_to_add_to, game_options, window_dimensions);
     }
 
     fn setup_rotatris_mode_subtext(
@@ -171,6 +453,7 @@ impl InputConfigMenu {
         Self::setup_left_right_down_subtext(vec_to_add_to, game_options, window_dimensions);
         Self::setup_rotate_piece_subtext(vec_to_add_to, game_options, window_dimensions);
         Self::setup_rotate_board_subtext(vec_to_add_to, game_options, window_dimensions);
+        Self::setup_restart_subtext(vec_to_add_to, game_options, window_dimensions);
     }
 
     fn setup_left_right_down_subtext(
@@ -181,24 +464,27 @@ impl InputConfigMenu {
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "Left:     ",
             InputConfigControlsId::Left as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::Left),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::Left),
             MenuItemTrigger::KeyLeft,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "Right:    ",
             InputConfigControlsId::Right as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::Right),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::Right),
             MenuItemTrigger::KeyRight,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "Down:     ",
             InputConfigControlsId::Down as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::Down),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::Down),
             MenuItemTrigger::KeyDown,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
@@ -212,16 +498,18 @@ impl InputConfigMenu {
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "RotateCw:  ",
             InputConfigControlsId::RotateCw as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::RotateCw),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::RotateCw),
             MenuItemTrigger::KeyRotateCw,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "RotateCcw: ",
             InputConfigControlsId::RotateCcw as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::RotateCcw),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::RotateCcw),
             MenuItemTrigger::KeyRotateCcw,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
@@ -235,34 +523,76 @@ impl InputConfigMenu {
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "BoardCw:  ",
             InputConfigControlsId::BoardRotateCw as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::BoardCw),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::BoardCw),
             MenuItemTrigger::KeyBoardCw,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
         vec_to_add_to.push(MenuItem::new_keycodevalue(
             "BoardCcw: ",
             InputConfigControlsId::BoardRotateCcw as u8,
-            (game_options.arr_controls[0].0).keycode_from_movement(Movement::BoardCcw),
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::BoardCcw),
             MenuItemTrigger::KeyBoardCcw,
+            game_options.theme(),
+            window_dimensions.1,
+            SUB_TEXT_SCALE_DOWN,
+        ));
+    }
+
+    // always the last row, since it's the only one that can be left without a key
+    fn setup_restart_subtext(
+        vec_to_add_to: &mut Vec<MenuItem>,
+        game_options: &MenuGameOptions,
+        window_dimensions: (f32, f32),
+    ) {
+        vec_to_add_to.push(MenuItem::new_keycodevalue(
+            "Restart:   ",
+            InputConfigControlsId::Restart as u8,
+            &(game_options.arr_controls[0].0).keycodes_from_movement(Movement::Restart),
+            MenuItemTrigger::KeyRestart,
+            game_options.theme(),
             window_dimensions.1,
             SUB_TEXT_SCALE_DOWN,
         ));
     }
 
     pub fn update(&mut self, input: &Input, game_options: &mut MenuGameOptions) -> bool {
-        if !self.sub_selection_keyboard_flag {
+        if self.sub_selection_menu_keys_flag {
+            self.capture_menu_key(game_options);
+            return false;
+        }
+        if self.menu_keys_before_change.is_some() {
+            self.confirm_menu_keys(game_options);
+            return false;
+        }
+        if self.sub_selection_quick_setup_flag {
+            self.update_quick_setup(input, game_options);
+            return false;
+        }
+        if self.sub_selection_sticks_flag {
+            self.update_sticks(input, game_options);
+            return false;
+        }
+        if !self.sub_selection_keyboard_flag && !self.sub_selection_gamepad_flag {
             // NOT the input box
 
-            if input.keydown_right.1 {
-                self.vec_menu_items_main[self.selection].change_val(true);
-                self.player_num = self.get_player_num();
-                self.update_all_sub_text_strings(game_options);
-            }
-
-            if input.keydown_left.1 {
-                self.vec_menu_items_main[self.selection].change_val(false);
-                self.player_num = self.get_player_num();
+            if input.keydown_right.1 || input.keydown_left.1 {
+                if self.vec_menu_items_main[self.selection].id
+                    == InputConfigMenuItemId::Profile as u8
+                {
+                    self.cycle_profile(game_options, input.keydown_right.1);
+                } else if self.vec_menu_items_main[self.selection].id
+                    == InputConfigMenuItemId::Socd as u8
+                {
+                    self.cycle_socd(game_options, input.keydown_right.1);
+                } else {
+                    self.vec_menu_items_main[self.selection].change_val(input.keydown_right.1);
+                    self.player_num = self.get_player_num();
+                    self.profile_conflict_flag = false;
+                    self.update_profile_text(game_options);
+                    self.update_socd_text(game_options);
+                }
                 self.update_all_sub_text_strings(game_options);
             }
 
@@ -284,9 +614,23 @@ impl InputConfigMenu {
                 self.most_recently_pressed_key = None;
             }
 
-            // special case, set player's controls to gamepad ('G' was pressed)
+            // special case, set player's controls to gamepad ('G' was pressed), or remap the gamepad if it already is
             if input.keydown_rotate_cw.1
                 && self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::SubSelection
+                && game_options.arr_controls[self.player_num as usize].1
+                && (game_options.arr_controls[self.player_num as usize].0).is_empty()
+            {
+                self.most_recently_pressed_gamepad_input = None;
+                let sticks = game_options.arr_gamepad_controls[self.player_num as usize].sticks;
+                self.gamepad_scheme_before_remap =
+                    Some(game_options.arr_gamepad_controls[self.player_num as usize].clone());
+                game_options.arr_gamepad_controls[self.player_num as usize] =
+                    GamepadControlScheme::new_empty().with_sticks(sticks);
+                self.sub_selection_gamepad_flag = true;
+                self.update_all_sub_text_strings(game_options);
+                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(true);
+            } else if input.keydown_rotate_cw.1
+                && self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::SubSelection
             {
                 game_options.arr_controls[self.player_num as usize].1 = true;
                 self.remove_from_used_keycodes(
@@ -294,11 +638,54 @@ impl InputConfigMenu {
                 );
                 game_options.arr_controls[self.player_num as usize].0 =
                     KeyboardControlScheme::default();
+                self.save_to_profile(game_options);
+                self.update_all_sub_text_strings(game_options);
+            }
+
+            // 'Tab' goes over the movements again, adding a key to each one that has room
+            if self.most_recently_pressed_key == Some(KeyCode::Tab)
+                && self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::SubSelection
+                && !(game_options.arr_controls[self.player_num as usize].0).is_empty()
+            {
+                self.most_recently_pressed_key = None;
+                if let Some(row) = self.next_open_row(0) {
+                    self.scheme_before_adding_keys =
+                        Some(game_options.arr_controls[self.player_num as usize].0.copy());
+                    self.sub_selection_keyboard = row;
+                    self.sub_selection_keyboard_flag = true;
+                    self.vec_menu_items_keycode[row].set_select(true);
+                }
             }
 
             // 'Space' or 'Return' was pressed
             if input.keydown_start.1 {
-                if self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::Back {
+                if self.vec_menu_items_main[self.selection].id
+                    == InputConfigMenuItemId::MenuKeys as u8
+                {
+                    self.most_recently_pressed_key = None;
+                    self.menu_keys_reverted_flag = false;
+                    self.vec_captured_menu_keys.clear();
+                    self.sub_selection_menu_keys_flag = true;
+                    self.vec_menu_items_menu_keys[0].set_select(true);
+                } else if self.vec_menu_items_main[self.selection].id
+                    == InputConfigMenuItemId::QuickSetup as u8
+                {
+                    self.most_recently_pressed_key = None;
+                    self.quick_setup_failed_flag = false;
+                    self.copy_from_slot = None;
+                    self.cycle_copy_from_slot(game_options, true);
+                    self.sub_selection_quick_setup = 0;
+                    self.sub_selection_quick_setup_flag = true;
+                    self.vec_menu_items_quick_setup[0].set_select(true);
+                } else if self.vec_menu_items_main[self.selection].id
+                    == InputConfigMenuItemId::Sticks as u8
+                {
+                    self.stick_conflict_flag = false;
+                    self.sub_selection_sticks = 0;
+                    self.sub_selection_sticks_flag = true;
+                    self.vec_menu_items_sticks[0].set_select(true);
+                } else if self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::Back
+                {
                     self.sub_selection_keyboard = 0;
                     return true;
                 } else if self.vec_menu_items_main[self.selection].trigger
@@ -327,6 +714,9 @@ impl InputConfigMenu {
                     game_options.arr_controls[self.player_num as usize].0 =
                         KeyboardControlScheme::default();
                     game_options.arr_controls[self.player_num as usize].1 = false;
+                    // the profile keeps its controls, it's just not sitting here anymore
+                    game_options.arr_profile_names[self.player_num as usize] = None;
+                    self.update_profile_text(game_options);
                     self.most_recently_pressed_key = None;
                 } else {
                     return true;
@@ -337,7 +727,20 @@ impl InputConfigMenu {
 
             // first check if the KeyCode is 'Escape', and if it is, just delete the layout entry and go out of the subselection section
             // second check if the KeyCode was already used. If it was, set the error message flag to true
-            if input.keydown_rotate_ccw.1 {
+            if input.keydown_rotate_ccw.1 && self.scheme_before_adding_keys.is_some() {
+                // escape while adding keys only takes back the added ones (or the whole quick setup)
+                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
+                self.keycode_conflict_flag = false;
+                self.filling_gaps_flag = false;
+                self.sub_selection_keyboard = 0;
+                self.sub_selection_keyboard_flag = false;
+                game_options.arr_controls[self.player_num as usize].0 = self
+                    .scheme_before_adding_keys
+                    .take()
+                    .expect(SCHEME_UNEXPECTEDLY_NONE);
+                self.vec_used_keycode = Self::used_keycodes(game_options);
+                self.update_all_sub_text_strings(game_options);
+            } else if input.keydown_rotate_ccw.1 {
                 // escape
                 self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
                 self.keycode_conflict_flag = false;
@@ -349,6 +752,25 @@ impl InputConfigMenu {
                 }
                 game_options.arr_controls[self.player_num as usize].0 =
                     KeyboardControlScheme::default();
+                // a profile goes back to the controls it had
+                self.apply_profile(game_options);
+                self.update_all_sub_text_strings(game_options);
+            } else if self.scheme_before_adding_keys.is_some()
+                && !self.filling_gaps_flag
+                && self.most_recently_pressed_key == Some(KeyCode::Tab)
+            {
+                // this movement doesn't get another key
+                self.keycode_conflict_flag = false;
+                self.next_key_to_add(game_options);
+            } else if self.most_recently_pressed_key == Some(KeyCode::Tab)
+                && self.is_restart_row(self.sub_selection_keyboard)
+            {
+                // no restart key; the rest are done
+                self.keycode_conflict_flag = false;
+                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
+                self.sub_selection_keyboard = 0;
+                self.sub_selection_keyboard_flag = false;
+                self.save_to_profile(game_options);
             } else if self
                 .vec_used_keycode
                 .contains(&self.most_recently_pressed_key.expect(KEY_UNEXPECTEDLY_NONE))
@@ -368,8 +790,22 @@ impl InputConfigMenu {
                     )
                     .expect(CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER),
                 );
-                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_keycode(Some(key));
+                let keycodes = (game_options.arr_controls[self.player_num as usize].0)
+                    .keycodes_from_movement(
+                        Movement::try_from(
+                            self.vec_menu_items_keycode[self.sub_selection_keyboard]
+                                .trigger
+                                .clone(),
+                        )
+                        .expect(CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER),
+                    );
+                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_keycodes(&keycodes);
                 self.vec_used_keycode.push(key);
+                if self.scheme_before_adding_keys.is_some() {
+                    self.next_key_to_add(game_options);
+                    self.most_recently_pressed_key = None;
+                    return false;
+                }
                 self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
                 if self.sub_selection_keyboard < self.vec_menu_items_keycode.len() - 1 {
                     self.sub_selection_keyboard += 1;
@@ -377,6 +813,57 @@ impl InputConfigMenu {
                 } else {
                     self.sub_selection_keyboard = 0;
                     self.sub_selection_keyboard_flag = false;
+                    self.save_to_profile(game_options);
+                }
+            }
+            self.most_recently_pressed_key = None;
+        } else if self.sub_selection_gamepad_flag {
+            // remapping a gamepad; the keyboard's 'Escape' still backs out
+            let slot = self.player_num as usize;
+            if input.keydown_rotate_ccw.1 {
+                self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
+                self.gamepad_conflict_flag = false;
+                self.sub_selection_keyboard = 0;
+                self.sub_selection_gamepad_flag = false;
+                // back to the layout it had before
+                game_options.arr_gamepad_controls[slot] = self
+                    .gamepad_scheme_before_remap
+                    .take()
+                    .expect(GAMEPAD_SCHEME_UNEXPECTEDLY_NONE);
+                self.update_all_sub_text_strings(game_options);
+            } else if let Some(gamepad_input) = self.most_recently_pressed_gamepad_input.take() {
+                if !gamepad_input.is_bindable()
+                    || game_options.arr_gamepad_controls[slot]
+                        .movement_from_input(gamepad_input)
+                        .is_some()
+                {
+                    self.gamepad_conflict_flag = true;
+                } else {
+                    self.gamepad_conflict_flag = false;
+                    game_options.arr_gamepad_controls[slot].add_pair(
+                        gamepad_input,
+                        Movement::try_from(
+                            self.vec_menu_items_keycode[self.sub_selection_keyboard]
+                                .trigger
+                                .clone(),
+                        )
+                        .expect(CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER),
+                    );
+                    self.vec_menu_items_keycode[self.sub_selection_keyboard]
+                        .text
+                        .fragments_mut()[1]
+                        .text = gamepad_input.to_string();
+                    self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
+                    // gamepads restart by holding Select, so the restart row is skipped
+                    if !self.is_restart_row(self.sub_selection_keyboard + 1) {
+                        self.sub_selection_keyboard += 1;
+                        self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(true);
+                    } else {
+                        self.sub_selection_keyboard = 0;
+                        self.sub_selection_gamepad_flag = false;
+                        self.gamepad_scheme_before_remap = None;
+                        self.save_to_profile(game_options);
+                    }
                 }
             }
             self.most_recently_pressed_key = None;
@@ -384,6 +871,403 @@ impl InputConfigMenu {
         false
     }
 
+    // every menu key takes the next key pressed, so there's no backing out halfway with keys missing;
+    // a key that's already taken is ignored
+    fn capture_menu_key(&mut self, game_options: &mut MenuGameOptions) {
+        let keycode = match self.most_recently_pressed_key.take() {
+            Some(keycode) => keycode,
+            None => return,
+        };
+        let index = self.vec_captured_menu_keys.len();
+        if !MenuKeys::fits(&self.vec_captured_menu_keys, index, keycode) {
+            self.keycode_conflict_flag = true;
+            return;
+        }
+        self.keycode_conflict_flag = false;
+        self.vec_captured_menu_keys.push(keycode);
+        self.vec_menu_items_menu_keys[index].set_keycodes(&[keycode]);
+        self.vec_menu_items_menu_keys[index].set_select(false);
+        if index + 1 < NUM_MENU_KEYS {
+            self.vec_menu_items_menu_keys[index + 1].set_select(true);
+            return;
+        }
+        // the new keys work right away, but only stay once the new select key is pressed
+        self.sub_selection_menu_keys_flag = false;
+        let mut keys = [keycode; NUM_MENU_KEYS];
+        keys.copy_from_slice(&self.vec_captured_menu_keys);
+        self.menu_keys_before_change = Some(game_options.menu_keys);
+        game_options.menu_keys = MenuKeys::from_keys(&keys);
+        self.menu_keys_countdown = MENU_KEYS_CONFIRM_SECONDS * DESIRED_FPS;
+        self.update_menu_key_texts(&game_options.menu_keys);
+    }
+
+    // new menu keys that nobody confirms (because they can't be reached, say) go back on their own
+    fn confirm_menu_keys(&mut self, game_options: &mut MenuGameOptions) {
+        if self.most_recently_pressed_key.take() == Some(game_options.menu_keys.select) {
+            self.menu_keys_before_change = None;
+            println!("[+] Using the new menu keys");
+            return;
+        }
+        self.menu_keys_countdown -= 1;
+        if self.menu_keys_countdown == 0 {
+            game_options.menu_keys = self
+                .menu_keys_before_change
+                .take()
+                .expect(MENU_KEYS_UNEXPECTEDLY_NONE);
+            self.menu_keys_reverted_flag = true;
+            println!("[!] The new menu keys weren't confirmed, going back to the old ones");
+            for (item, keycode) in self
+                .vec_menu_items_menu_keys
+                .iter_mut()
+                .zip(game_options.menu_keys.keys().iter())
+            {
+                item.set_keycodes(&[*keycode]);
+            }
+        }
+        self.update_menu_key_texts(&game_options.menu_keys);
+    }
+
+    // hints that name menu keys follow them around
+    fn update_menu_key_texts(&mut self, menu_keys: &MenuKeys) {
+        self.input_uninitialized_text.fragments_mut()[0].text = format!(
+            "No Controls\nKeyboard: {:?}/Enter\nGamepad: '{:?}' or Y (North)",
+            menu_keys.select, menu_keys.gamepad
+        );
+        self.is_gamepad_text.fragments_mut()[0].text = format!(
+            "Set to Gamepad ('{:?}' or Y again to remap)",
+            menu_keys.gamepad
+        );
+        self.adding_keys_text.fragments_mut()[0].text =
+            format!("Adding keys: 'Tab' skips, '{:?}' undoes", menu_keys.back);
+        self.quick_setup_help_text.fragments_mut()[0].text = format!(
+            "{:?} to fill in this player's keys in one go",
+            menu_keys.select
+        );
+        self.sticks_help_text.fragments_mut()[0].text = format!(
+            "For this player's gamepad; {:?} to change",
+            menu_keys.select
+        );
+        self.sticks_editing_text.fragments_mut()[0].text = format!(
+            "Left/Right to change, {:?} or {:?} when done",
+            menu_keys.select, menu_keys.back
+        );
+        self.filling_gaps_text.fragments_mut()[0].text = format!(
+            "[!] Some of those keys are taken; press keys for the rest ('{:?}' undoes)",
+            menu_keys.back
+        );
+        let seconds_left = self.menu_keys_countdown.div_ceil(DESIRED_FPS);
+        self.menu_keys_confirm_text.fragments_mut()[0].text = format!(
+            "Press {:?} to keep these keys ({}s)",
+            menu_keys.select, seconds_left
+        );
+    }
+
+    // the first movement from this row on that can still take another key, or that has none at all while filling gaps;
+    // filling gaps leaves the restart key alone, since it's optional
+    fn next_open_row(&self, first_row: usize) -> Option<usize> {
+        let max_keys = if self.filling_gaps_flag {
+            1
+        } else {
+            MAX_KEYS_PER_MOVEMENT
+        };
+        (first_row..self.vec_menu_items_keycode.len()).find(|row| {
+            self.vec_menu_items_keycode[*row].vec_keycodes.len() < max_keys
+                && !(self.filling_gaps_flag && self.is_restart_row(*row))
+        })
+    }
+
+    fn is_restart_row(&self, row: usize) -> bool {
+        self.vec_menu_items_keycode
+            .get(row)
+            .is_some_and(|item| item.trigger == MenuItemTrigger::KeyRestart)
+    }
+
+    // moves on while adding keys, and saves them once every movement had its turn
+    fn next_key_to_add(&mut self, game_options: &mut MenuGameOptions) {
+        self.vec_menu_items_keycode[self.sub_selection_keyboard].set_select(false);
+        match self.next_open_row(self.sub_selection_keyboard + 1) {
+            Some(row) => {
+                self.sub_selection_keyboard = row;
+                self.vec_menu_items_keycode[row].set_select(true);
+            }
+            None => {
+                self.sub_selection_keyboard = 0;
+                self.sub_selection_keyboard_flag = false;
+                self.scheme_before_adding_keys = None;
+                self.filling_gaps_flag = false;
+                self.save_to_profile(game_options);
+            }
+        }
+    }
+
+    pub fn is_remapping_gamepad(&self) -> bool {
+        self.sub_selection_gamepad_flag
+    }
+
+    // gamepads are only listened to here while one is being remapped
+    pub fn gamepad_button_down_event(&mut self, btn: Button) {
+        if self.sub_selection_gamepad_flag {
+            self.most_recently_pressed_gamepad_input = Some(GamepadInput::Button(btn));
+        }
+    }
+
+    // the raw stick values always show on the sticks item; remapping goes by the slot's stick settings
+    pub fn gamepad_axis_event(&mut self, axis: Axis, value: f32, game_options: &MenuGameOptions) {
+        let readout_index = match axis {
+            Axis::LeftStickX => Some(0),
+            Axis::LeftStickY => Some(1),
+            Axis::RightStickX => Some(2),
+            Axis::RightStickY => Some(3),
+            _ => None,
+        };
+        if let Some(index) = readout_index {
+            self.arr_stick_readout[index] = value;
+            self.update_stick_readout_text();
+        }
+        if !self.sub_selection_gamepad_flag {
+            return;
+        }
+        let sticks = game_options.arr_gamepad_controls[self.player_num as usize].sticks;
+        let value = sticks.read_axis(axis, value);
+        if let Some(gamepad_input) = GamepadInput::from_axis(axis, value, sticks.press_threshold())
+        {
+            if !self.vec_held_axis_input.contains(&gamepad_input) {
+                self.vec_held_axis_input.push(gamepad_input);
+                self.most_recently_pressed_gamepad_input = Some(gamepad_input);
+            }
+        } else if GamepadInput::from_axis(axis, value, sticks.release_threshold()).is_none() {
+            self.vec_held_axis_input
+                .retain(|held_input| held_input.axis_value(axis, 0.0).is_none());
+        }
+    }
+
+    // Up/Down pick a preset (or the shifted copy), Left/Right pick whose keys to copy, and select fills in the keys
+    fn update_quick_setup(&mut self, input: &Input, game_options: &mut MenuGameOptions) {
+        let num_rows = self.vec_menu_items_quick_setup.len();
+        if input.keydown_rotate_ccw.1 {
+            self.leave_quick_setup();
+            return;
+        }
+        if input.keydown_down.1 || input.keydown_up.1 {
+            self.quick_setup_failed_flag = false;
+            self.vec_menu_items_quick_setup[self.sub_selection_quick_setup].set_select(false);
+            self.sub_selection_quick_setup = if input.keydown_down.1 {
+                (self.sub_selection_quick_setup + 1) % num_rows
+            } else {
+                (self.sub_selection_quick_setup + num_rows - 1) % num_rows
+            };
+            self.vec_menu_items_quick_setup[self.sub_selection_quick_setup].set_select(true);
+        }
+        if (input.keydown_right.1 || input.keydown_left.1)
+            && self.sub_selection_quick_setup == NUM_KEYBOARD_PRESETS
+        {
+            self.quick_setup_failed_flag = false;
+            self.cycle_copy_from_slot(game_options, input.keydown_right.1);
+        }
+        if !input.keydown_start.1 {
+            return;
+        }
+
+        let slot = self.player_num as usize;
+        let vec_movements: Vec<Movement> = self
+            .vec_menu_items_keycode
+            .iter()
+            .map(|item| {
+                Movement::try_from(item.trigger.clone())
+                    .expect(CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER)
+            })
+            .collect();
+        // everyone else's keys are off limits
+        let mut vec_taken: Vec<KeyCode> = vec![];
+        for (other_slot, ctrls) in game_options.arr_controls.iter().enumerate() {
+            if other_slot != slot {
+                vec_taken.extend(ctrls.0.vec_keycode_movement_pair.iter().map(|pair| pair.0));
+            }
+        }
+        let new_scheme = if self.sub_selection_quick_setup < NUM_KEYBOARD_PRESETS {
+            KeyboardControlScheme::from_preset(
+                self.sub_selection_quick_setup,
+                &vec_movements,
+                &vec_taken,
+            )
+        } else {
+            // the copy can't sit on this player's old keys either, or it wouldn't be much of a shift
+            let source_slot = match self.copy_from_slot {
+                Some(source_slot) => source_slot,
+                None => return,
+            };
+            vec_taken.extend(
+                game_options.arr_controls[slot]
+                    .0
+                    .vec_keycode_movement_pair
+                    .iter()
+                    .map(|pair| pair.0),
+            );
+            match game_options.arr_controls[source_slot].0.shifted(&vec_taken) {
+                Some(scheme) => scheme,
+                None => {
+                    self.quick_setup_failed_flag = true;
+                    return;
+                }
+            }
+        };
+        self.leave_quick_setup();
+        self.scheme_before_adding_keys = Some(game_options.arr_controls[slot].0.copy());
+        game_options.arr_controls[slot].0 = new_scheme;
+        self.vec_used_keycode = Self::used_keycodes(game_options);
+        self.update_all_sub_text_strings(game_options);
+        // movements whose preset keys were taken get asked for, like when adding keys
+        self.filling_gaps_flag = true;
+        match self.next_open_row(0) {
+            Some(row) => {
+                self.sub_selection_keyboard = row;
+                self.sub_selection_keyboard_flag = true;
+                self.vec_menu_items_keycode[row].set_select(true);
+            }
+            None => {
+                self.filling_gaps_flag = false;
+                self.scheme_before_adding_keys = None;
+                self.save_to_profile(game_options);
+            }
+        }
+    }
+
+    fn leave_quick_setup(&mut self) {
+        self.vec_menu_items_quick_setup[self.sub_selection_quick_setup].set_select(false);
+        self.sub_selection_quick_setup = 0;
+        self.sub_selection_quick_setup_flag = false;
+        self.quick_setup_failed_flag = false;
+    }
+
+    // only other players with keys can be copied
+    fn cycle_copy_from_slot(&mut self, game_options: &MenuGameOptions, rightward_press: bool) {
+        let num_slots = game_options.arr_controls.len();
+        let mut slot = self.copy_from_slot.unwrap_or(self.player_num as usize);
+        self.copy_from_slot = None;
+        for _ in 0..num_slots {
+            slot = if rightward_press {
+                (slot + 1) % num_slots
+            } else {
+                (slot + num_slots - 1) % num_slots
+            };
+            if slot != self.player_num as usize && !game_options.arr_controls[slot].0.is_empty() {
+                self.copy_from_slot = Some(slot);
+                break;
+            }
+        }
+        self.vec_menu_items_quick_setup[NUM_KEYBOARD_PRESETS]
+            .text
+            .fragments_mut()[1]
+            .text = match self.copy_from_slot {
+            Some(slot) => format!("Player {}", slot + 1),
+            None => NO_PROFILE_STR.to_string(),
+        };
+    }
+
+    fn update_stick_readout_text(&mut self) {
+        let readout = self.arr_stick_readout;
+        self.stick_readout_text.fragments_mut()[0].text = format!(
+            "Left Stick: {:+.2} {:+.2}    Right Stick: {:+.2} {:+.2}",
+            readout[0], readout[1], readout[2], readout[3]
+        );
+    }
+
+    // Up/Down pick a setting and Left/Right change it; the settings go to the profile on the way out
+    fn update_sticks(&mut self, input: &Input, game_options: &mut MenuGameOptions) {
+        if input.keydown_start.1 || input.keydown_rotate_ccw.1 {
+            self.vec_menu_items_sticks[self.sub_selection_sticks].set_select(false);
+            self.sub_selection_sticks = 0;
+            self.sub_selection_sticks_flag = false;
+            self.stick_conflict_flag = false;
+            self.save_to_profile(game_options);
+            return;
+        }
+        if input.keydown_down.1 || input.keydown_up.1 {
+            self.vec_menu_items_sticks[self.sub_selection_sticks].set_select(false);
+            self.sub_selection_sticks = if input.keydown_down.1 {
+                (self.sub_selection_sticks + 1) % NUM_STICK_ITEMS
+            } else {
+                (self.sub_selection_sticks + NUM_STICK_ITEMS - 1) % NUM_STICK_ITEMS
+            };
+            self.vec_menu_items_sticks[self.sub_selection_sticks].set_select(true);
+        }
+        if !input.keydown_right.1 && !input.keydown_left.1 {
+            return;
+        }
+        let rightward_press = input.keydown_right.1;
+        let g_ctrl_scheme = &mut game_options.arr_gamepad_controls[self.player_num as usize];
+        self.stick_conflict_flag = false;
+        match self.sub_selection_sticks {
+            x if x == StickItemId::Deadzone as usize => {
+                g_ctrl_scheme.sticks.deadzone = Self::step_percent(
+                    g_ctrl_scheme.sticks.deadzone,
+                    0,
+                    MAX_DEADZONE,
+                    rightward_press,
+                );
+            }
+            x if x == StickItemId::Hysteresis as usize => {
+                g_ctrl_scheme.sticks.hysteresis = Self::step_percent(
+                    g_ctrl_scheme.sticks.hysteresis,
+                    MIN_HYSTERESIS,
+                    MAX_HYSTERESIS,
+                    rightward_press,
+                );
+            }
+            x if x == StickItemId::InvertX as usize => {
+                g_ctrl_scheme.sticks.invert_x = !g_ctrl_scheme.sticks.invert_x;
+            }
+            x if x == StickItemId::InvertY as usize => {
+                g_ctrl_scheme.sticks.invert_y = !g_ctrl_scheme.sticks.invert_y;
+            }
+            x if x == StickItemId::MovementStick as usize => {
+                // a choice that would leave a movement unbound is skipped
+                let mut choice = g_ctrl_scheme.movement_stick() as u8;
+                for _ in 1..NUM_MOVEMENT_STICKS {
+                    choice = if rightward_press {
+                        (choice + 1) % NUM_MOVEMENT_STICKS
+                    } else {
+                        (choice + NUM_MOVEMENT_STICKS - 1) % NUM_MOVEMENT_STICKS
+                    };
+                    if g_ctrl_scheme.set_movement_stick(
+                        MovementStick::from_u8(choice).expect(MOVEMENT_STICK_OUT_OF_RANGE),
+                    ) {
+                        break;
+                    }
+                }
+            }
+            x if x == StickItemId::BoardStick as usize => {
+                let on = !g_ctrl_scheme.has_board_stick();
+                if !g_ctrl_scheme.set_board_stick(on) {
+                    self.stick_conflict_text.fragments_mut()[0].text = if on {
+                        "[!] The right stick is moving the piece; ignoring"
+                    } else {
+                        "[!] Board rotation needs a button first; ignoring"
+                    }
+                    .to_string();
+                    self.stick_conflict_flag = true;
+                }
+            }
+            _ => {}
+        }
+        self.update_all_sub_text_strings(game_options);
+    }
+
+    // steps of STICK_PERCENT_STEP, going around at the ends
+    fn step_percent(value: u8, min: u8, max: u8, rightward_press: bool) -> u8 {
+        if rightward_press {
+            if value >= max {
+                min
+            } else {
+                (value + STICK_PERCENT_STEP).min(max)
+            }
+        } else if value <= min {
+            max
+        } else {
+            value.saturating_sub(STICK_PERCENT_STEP).max(min)
+        }
+    }
+
     fn get_player_num(&self) -> u8 {
         for item in self.vec_menu_items_main.iter() {
             if item.id == InputConfigMenuItemId::PlayerNum as u8 {
@@ -393,6 +1277,122 @@ impl InputConfigMenu {
         unreachable!();
     }
 
+    // profiles already sitting in another slot are skipped; None is always an option
+    fn cycle_profile(&mut self, game_options: &mut MenuGameOptions, rightward_press: bool) {
+        let slot = self.player_num as usize;
+        let num_choices = game_options.profiles.len() + 1;
+        let mut choice = match &game_options.arr_profile_names[slot] {
+            Some(name) => {
+                game_options
+                    .profiles
+                    .iter()
+                    .position(|profile| &profile.name == name)
+                    .unwrap_or(num_choices - 1)
+                    + 1
+            }
+            None => 0,
+        };
+        loop {
+            choice = if rightward_press {
+                (choice + 1) % num_choices
+            } else {
+                (choice + num_choices - 1) % num_choices
+            };
+            if choice == 0
+                || !game_options
+                    .arr_profile_names
+                    .contains(&Some(game_options.profiles[choice - 1].name.clone()))
+            {
+                break;
+            }
+        }
+        self.profile_conflict_flag = false;
+        if choice == 0 {
+            // whoever sits down without a profile keeps the controls that were here
+            game_options.arr_profile_names[slot] = None;
+        } else {
+            game_options.arr_profile_names[slot] =
+                Some(game_options.profiles[choice - 1].name.clone());
+            self.apply_profile(game_options);
+        }
+        self.update_profile_text(game_options);
+        self.update_socd_text(game_options);
+    }
+
+    // the policy belongs to the slot, and to the profile sitting in it
+    fn cycle_socd(&mut self, game_options: &mut MenuGameOptions, rightward_press: bool) {
+        let item = &mut self.vec_menu_items_main[self.selection];
+        item.change_val(rightward_press);
+        game_options.arr_socd[self.player_num as usize] =
+            SocdPolicy::from_u8(item.value).expect(SOCD_VALUE_OUT_OF_RANGE);
+        self.save_to_profile(game_options);
+        self.update_socd_text(game_options);
+    }
+
+    // the slot takes the controls of its profile; other slots lose any keys the profile needs
+    fn apply_profile(&mut self, game_options: &mut MenuGameOptions) {
+        let slot = self.player_num as usize;
+        let profile = match game_options.slot_profile(slot) {
+            Some(profile) => profile.clone(),
+            None => return,
+        };
+        if !profile.has_controls() {
+            // a new profile starts out with whatever controls the slot already has
+            self.save_to_profile(game_options);
+            return;
+        }
+        if game_options.apply_slot_profile(slot) {
+            self.profile_conflict_flag = true;
+        }
+        self.vec_used_keycode = Self::used_keycodes(game_options);
+    }
+
+    fn save_to_profile(&self, game_options: &mut MenuGameOptions) {
+        let slot = self.player_num as usize;
+        let ctrls = game_options.arr_controls[slot].clone();
+        let g_ctrl_scheme = game_options.arr_gamepad_controls[slot].clone();
+        let socd = game_options.arr_socd[slot];
+        if let Some(name) = &game_options.arr_profile_names[slot] {
+            if let Some(profile) = game_options
+                .profiles
+                .iter_mut()
+                .find(|profile| &profile.name == name)
+            {
+                profile.set_controls(&ctrls, &g_ctrl_scheme, socd);
+            }
+        }
+    }
+
+    pub fn update_profile_text(&mut self, game_options: &MenuGameOptions) {
+        let name = game_options.arr_profile_names[self.player_num as usize]
+            .as_deref()
+            .unwrap_or(NO_PROFILE_STR)
+            .to_string();
+        for item in self.vec_menu_items_main.iter_mut() {
+            if item.id == InputConfigMenuItemId::Profile as u8 {
+                item.text.fragments_mut()[1].text = name.clone();
+            }
+        }
+    }
+
+    fn update_socd_text(&mut self, game_options: &MenuGameOptions) {
+        let socd = game_options.arr_socd[self.player_num as usize];
+        for item in self.vec_menu_items_main.iter_mut() {
+            if item.id == InputConfigMenuItemId::Socd as u8 {
+                item.value = socd as u8;
+                item.text.fragments_mut()[1].text = socd.name().to_string();
+            }
+        }
+        self.socd_text.fragments_mut()[0].text = match socd {
+            SocdPolicy::LastInputWins => {
+                "Left + Right: the newer one wins, letting go of it goes back to the other"
+            }
+            SocdPolicy::FirstInputWins => "Left + Right: the one held first wins",
+            SocdPolicy::Neutral => "Left + Right: neither, the piece stays put",
+        }
+        .to_string();
+    }
+
     fn remove_from_used_keycodes(&mut self, k_ctrl_scheme: &KeyboardControlScheme) {
         for k_m_pair in k_ctrl_scheme.vec_keycode_movement_pair.iter() {
             let mut items_removed = 0;
@@ -411,21 +1411,59 @@ impl InputConfigMenu {
         }
     }
 
+    // a gamepad slot without keys shows its gamepad layout instead
+    fn shows_gamepad(&self, game_options: &MenuGameOptions) -> bool {
+        let ctrls = &game_options.arr_controls[self.player_num as usize];
+        self.sub_selection_gamepad_flag
+            || (!self.sub_selection_keyboard_flag && ctrls.1 && ctrls.0.is_empty())
+    }
+
     fn update_all_sub_text_strings(&mut self, game_options: &MenuGameOptions) {
+        let gamepad_flag = self.shows_gamepad(game_options);
         for item in self.vec_menu_items_keycode.iter_mut() {
             let desired_movement = Movement::try_from(item.trigger.clone())
                 .expect(CONVERSION_FAILED_MOVEMENT_FROM_MENUITEMTRIGGER);
-            item.set_keycode(None);
-            for kmp in (game_options.arr_controls[self.player_num as usize].0)
-                .vec_keycode_movement_pair
-                .iter()
-            {
-                if kmp.1 == desired_movement {
-                    item.set_keycode(Some(kmp.0));
-                    break;
+            item.set_keycodes(&[]);
+            if gamepad_flag && desired_movement == Movement::Restart {
+                item.text.fragments_mut()[1].text = "Select (hold)".to_string();
+                continue;
+            }
+            if gamepad_flag {
+                if let Some(gamepad_input) = game_options.arr_gamepad_controls
+                    [self.player_num as usize]
+                    .input_from_movement(desired_movement)
+                {
+                    item.text.fragments_mut()[1].text = gamepad_input.to_string();
                 }
+                continue;
             }
+            item.set_keycodes(
+                &(game_options.arr_controls[self.player_num as usize].0)
+                    .keycodes_from_movement(desired_movement),
+            );
         }
+        let g_ctrl_scheme = &game_options.arr_gamepad_controls[self.player_num as usize];
+        let sticks: StickSettings = g_ctrl_scheme.sticks;
+        let arr_stick_strings: [String; NUM_STICK_ITEMS] = [
+            format!("{}%", sticks.deadzone),
+            format!("{}%", sticks.hysteresis),
+            Self::onoff_string(sticks.invert_x),
+            Self::onoff_string(sticks.invert_y),
+            g_ctrl_scheme.movement_stick().name().to_string(),
+            Self::onoff_string(g_ctrl_scheme.has_board_stick()),
+        ];
+        for (item, string) in self
+            .vec_menu_items_sticks
+            .iter_mut()
+            .zip(arr_stick_strings.iter())
+        {
+            item.text.fragments_mut()[1].text = string.clone();
+        }
+    }
+
+    // the same as the other menus' on/off values
+    fn onoff_string(on: bool) -> String {
+        if on { "on" } else { "off" }.to_string()
     }
 
     pub fn draw(&mut self, ctx: &mut Context, game_options: &MenuGameOptions) {
@@ -437,7 +1475,7 @@ impl InputConfigMenu {
             self.draw_text(
                 ctx,
                 &item.text,
-                0.1 + 0.2 * index as f32,
+                0.05 + 0.062 * index as f32,
                 &window_dimensions,
             );
         }
@@ -451,8 +1489,14 @@ impl InputConfigMenu {
             let rect_w = window_dimensions.0 / 2.0;
             let rect_h = window_dimensions.1 / 2.0;
             let rect_x = (window_dimensions.0 - rect_w) / 2.0;
-            let rect_y = window_dimensions.1 * 0.4;
-            if !self.sub_selection_keyboard_flag {
+            let rect_y = window_dimensions.1 * 0.47;
+            if !self.sub_selection_keyboard_flag
+                && !self.sub_selection_gamepad_flag
+                && !self.sub_selection_menu_keys_flag
+                && !self.sub_selection_sticks_flag
+                && !self.sub_selection_quick_setup_flag
+                && self.menu_keys_before_change.is_none()
+            {
                 editing_indicator_rectangle = graphics::Mesh::new_rectangle(
                     ctx,
                     graphics::DrawMode::fill(),
@@ -462,7 +1506,7 @@ impl InputConfigMenu {
                         w: rect_w,
                         h: rect_h,
                     },
-                    DARK_GRAY,
+                    game_options.theme().menu_dark_color(),
                 )
                 .unwrap();
             } else {
@@ -475,7 +1519,7 @@ impl InputConfigMenu {
                         w: rect_w,
                         h: rect_h,
                     },
-                    LIGHT_GRAY,
+                    game_options.theme().menu_light_color(),
                 )
                 .unwrap();
             }
@@ -486,31 +1530,129 @@ impl InputConfigMenu {
             )
             .unwrap();
 
-            if self.vec_menu_items_main[self.selection].trigger == MenuItemTrigger::SubSelection {
-                if self.keycode_conflict_flag {
-                    self.draw_text(ctx, &self.keycode_conflict_text, 0.43, &window_dimensions);
-                }
+            if self.vec_menu_items_main[self.selection].id == InputConfigMenuItemId::MenuKeys as u8
+            {
+                self.draw_menu_keys(ctx, &window_dimensions);
+                return;
+            }
+            // the quick setup's movements that still need keys are asked for in the usual place
+            if self.vec_menu_items_main[self.selection].id
+                == InputConfigMenuItemId::QuickSetup as u8
+                && !self.sub_selection_keyboard_flag
+            {
+                self.draw_quick_setup(ctx, &window_dimensions);
+                return;
+            }
+            if self.vec_menu_items_main[self.selection].id == InputConfigMenuItemId::Sticks as u8 {
+                self.draw_sticks(ctx, &window_dimensions);
+                return;
+            }
 
-                if self.sub_selection_keyboard_flag
-                    || !(game_options.arr_controls[self.player_num as usize].0).is_empty()
-                {
-                    for (index, item) in self.vec_menu_items_keycode.iter().enumerate() {
-                        self.draw_text(
-                            ctx,
-                            &item.text,
-                            0.5 + 0.05 * index as f32,
-                            &window_dimensions,
-                        );
-                    }
-                } else if game_options.arr_controls[self.player_num as usize].1 {
-                    self.draw_text(ctx, &self.is_gamepad_text, 0.63, &window_dimensions);
-                } else {
-                    self.draw_text(ctx, &self.input_uninitialized_text, 0.5, &window_dimensions);
+            // the controls of the slot (and so of its profile) show on both the player and profile items
+            if self.keycode_conflict_flag {
+                self.draw_text(ctx, &self.keycode_conflict_text, 0.5, &window_dimensions);
+            } else if self.gamepad_conflict_flag {
+                self.draw_text(ctx, &self.gamepad_conflict_text, 0.5, &window_dimensions);
+            } else if self.profile_conflict_flag {
+                self.draw_text(ctx, &self.profile_conflict_text, 0.5, &window_dimensions);
+            } else if self.vec_menu_items_main[self.selection].id
+                == InputConfigMenuItemId::Socd as u8
+            {
+                self.draw_text(ctx, &self.socd_text, 0.5, &window_dimensions);
+            } else if self.shows_gamepad(game_options) {
+                self.draw_text(ctx, &self.is_gamepad_text, 0.5, &window_dimensions);
+            } else if self.filling_gaps_flag {
+                self.draw_text(ctx, &self.filling_gaps_text, 0.5, &window_dimensions);
+            } else if self.scheme_before_adding_keys.is_some() {
+                self.draw_text(ctx, &self.adding_keys_text, 0.5, &window_dimensions);
+            } else if self.sub_selection_keyboard_flag
+                && self.is_restart_row(self.sub_selection_keyboard)
+            {
+                self.draw_text(ctx, &self.restart_key_text, 0.5, &window_dimensions);
+            } else if !self.sub_selection_keyboard_flag
+                && !(game_options.arr_controls[self.player_num as usize].0).is_empty()
+            {
+                self.draw_text(ctx, &self.add_keys_text, 0.5, &window_dimensions);
+            }
+
+            if self.sub_selection_keyboard_flag
+                || self.shows_gamepad(game_options)
+                || !(game_options.arr_controls[self.player_num as usize].0).is_empty()
+            {
+                for (index, item) in self.vec_menu_items_keycode.iter().enumerate() {
+                    self.draw_text(
+                        ctx,
+                        &item.text,
+                        0.57 + 0.05 * index as f32,
+                        &window_dimensions,
+                    );
                 }
+            } else {
+                self.draw_text(
+                    ctx,
+                    &self.input_uninitialized_text,
+                    0.57,
+                    &window_dimensions,
+                );
             }
         }
     }
 
+    fn draw_menu_keys(&self, ctx: &mut Context, window_dimensions: &(f32, f32)) {
+        if self.keycode_conflict_flag {
+            self.draw_text(ctx, &self.keycode_conflict_text, 0.5, window_dimensions);
+        } else if self.sub_selection_menu_keys_flag {
+            self.draw_text(ctx, &self.menu_keys_capture_text, 0.5, window_dimensions);
+        } else if self.menu_keys_before_change.is_some() {
+            self.draw_text(ctx, &self.menu_keys_confirm_text, 0.5, window_dimensions);
+        } else if self.menu_keys_reverted_flag {
+            self.draw_text(ctx, &self.menu_keys_reverted_text, 0.5, window_dimensions);
+        }
+        for (index, item) in self.vec_menu_items_menu_keys.iter().enumerate() {
+            self.draw_text(
+                ctx,
+                &item.text,
+                0.55 + 0.04 * index as f32,
+                window_dimensions,
+            );
+        }
+    }
+
+    fn draw_quick_setup(&self, ctx: &mut Context, window_dimensions: &(f32, f32)) {
+        if self.quick_setup_failed_flag {
+            self.draw_text(ctx, &self.quick_setup_failed_text, 0.5, window_dimensions);
+        } else {
+            self.draw_text(ctx, &self.quick_setup_help_text, 0.5, window_dimensions);
+        }
+        for (index, item) in self.vec_menu_items_quick_setup.iter().enumerate() {
+            self.draw_text(
+                ctx,
+                &item.text,
+                0.57 + 0.05 * index as f32,
+                window_dimensions,
+            );
+        }
+    }
+
+    fn draw_sticks(&self, ctx: &mut Context, window_dimensions: &(f32, f32)) {
+        if self.stick_conflict_flag {
+            self.draw_text(ctx, &self.stick_conflict_text, 0.5, window_dimensions);
+        } else if self.sub_selection_sticks_flag {
+            self.draw_text(ctx, &self.sticks_editing_text, 0.5, window_dimensions);
+        } else {
+            self.draw_text(ctx, &self.sticks_help_text, 0.5, window_dimensions);
+        }
+        for (index, item) in self.vec_menu_items_sticks.iter().enumerate() {
+            self.draw_text(
+                ctx,
+                &item.text,
+                0.55 + 0.045 * index as f32,
+                window_dimensions,
+            );
+        }
+        self.draw_text(ctx, &self.stick_readout_text, 0.89, window_dimensions);
+    }
+
     fn draw_text(
         &self,
         ctx: &mut Context,
@@ -541,7 +1683,48 @@ impl InputConfigMenu {
             .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
         self.keycode_conflict_text
             .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.gamepad_conflict_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.profile_conflict_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.socd_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
         self.is_gamepad_text
             .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.add_keys_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.adding_keys_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.restart_key_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        for item in self.vec_menu_items_menu_keys.iter_mut() {
+            item.resize(height);
+        }
+        self.menu_keys_capture_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.menu_keys_confirm_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.menu_keys_reverted_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        for item in self.vec_menu_items_quick_setup.iter_mut() {
+            item.resize(height);
+        }
+        self.quick_setup_help_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.quick_setup_failed_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.filling_gaps_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        for item in self.vec_menu_items_sticks.iter_mut() {
+            item.resize(height);
+        }
+        self.sticks_help_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.sticks_editing_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.stick_conflict_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.stick_readout_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
     }
 }
@@ -0,0 +1,296 @@
+use ggez::event::{Button, GamepadId};
+use ggez::graphics::{self, Color, DrawParam, Font, Image, PxScale, Text, TextFragment};
+use ggez::input::gamepad;
+use ggez::mint::{Point2, Vector2};
+use ggez::Context;
+
+use crate::game::lane_color;
+use crate::game::tile::{TileGraphic, TileSkin};
+use crate::inputs::Input;
+use crate::menu::menuhelpers::{MenuGameOptions, MenuItemTrigger};
+use crate::menu::menuhelpers::{SUB_TEXT_SCALE_DOWN, TEXT_SCALE_DOWN};
+use crate::theme::Theme;
+
+// the rows have to fit between the title and the help text, however many players there are
+const ROWS_TOP: f32 = 0.2;
+const ROWS_BOTTOM: f32 = 0.8;
+const MAX_ROW_SPACING: f32 = 0.08;
+
+// one row per player that's going to be in the game
+struct LobbySlot {
+    // which slot of the controls menu the player comes from
+    slot: usize,
+    player_num: u8,
+    color: u8,
+    // the pattern their tiles will have in the game, if tile patterns are on
+    pattern: Option<u8>,
+    profile_name: Option<String>,
+    gamepad_flag: bool,
+    // the gamepad that pressed a button for this player, and its name
+    gamepad: Option<(GamepadId, String)>,
+    text: Text,
+    text_color: Color,
+    help_color: Color,
+    // made the first time it's drawn since that needs the context
+    swatch: Option<Image>,
+}
+
+impl LobbySlot {
+    fn update_text(&mut self, window_height: f32) {
+        let mut line = format!("Player {}", self.player_num + 1);
+        if let Some(name) = &self.profile_name {
+            line.push_str(&format!(" ({})", name));
+        }
+        line.push_str(": ");
+        let mut text = Text::new(TextFragment::new(line).color(self.text_color));
+        text.add(if !self.gamepad_flag {
+            TextFragment::new("Keyboard").color(self.text_color)
+        } else {
+            match &self.gamepad {
+                Some((_, name)) => TextFragment::new(name.as_str()).color(self.text_color),
+                None => TextFragment::new("press a button on a gamepad").color(self.help_color),
+            }
+        });
+        text.set_font(
+            Font::default(),
+            PxScale::from(window_height / SUB_TEXT_SCALE_DOWN),
+        );
+        self.text = text;
+    }
+}
+
+// gamepads are picked by pressing a button here so nobody ends up in someone else's spot
+pub struct LobbyMenu {
+    // logic
+    vec_lobby_slots: Vec<LobbySlot>,
+    start_flag: bool,
+    back_flag: bool,
+    waiting_flag: bool,
+    // drawing
+    title_text: Text,
+    help_text: Text,
+    waiting_text: Text,
+    // for the color swatches
+    theme: Theme,
+    tile_skin: TileSkin,
+    window_height: f32,
+}
+
+impl LobbyMenu {
+    pub fn new(game_options: &MenuGameOptions, window_dimensions: (f32, f32)) -> Self {
+        let vec_active_slots = game_options.active_slots();
+        let num_players = vec_active_slots.len() as u8;
+        let mut vec_lobby_slots: Vec<LobbySlot> = Vec::with_capacity(vec_active_slots.len());
+        for (player_num, slot) in vec_active_slots.into_iter().enumerate() {
+            let profile = game_options.slot_profile(slot);
+            let mut lobby_slot = LobbySlot {
+                slot,
+                player_num: player_num as u8,
+                color: lane_color(player_num as u8, profile, num_players),
+                pattern: game_options.settings.tile_pattern(player_num as u8),
+                profile_name: profile.map(|profile| profile.name.clone()),
+                gamepad_flag: game_options.arr_controls[slot].0.is_empty(),
+                gamepad: None,
+                text: Text::default(),
+                text_color: game_options.theme().menu_text_color(),
+                help_color: game_options.theme().menu_help_color(),
+                swatch: None,
+            };
+            lobby_slot.update_text(window_dimensions.1);
+            vec_lobby_slots.push(lobby_slot);
+        }
+        let mut title_text = Text::new(
+            TextFragment::new("Press a button to join")
+                .color(game_options.theme().menu_text_color()),
+        );
+        title_text.set_font(
+            Font::default(),
+            PxScale::from(window_dimensions.1 / TEXT_SCALE_DOWN),
+        );
+        let mut help_text = Text::new(
+            TextFragment::new(format!(
+                "Start/{:?}/Enter: play    B (East): leave your spot/back    {:?}: back",
+                game_options.menu_keys.select, game_options.menu_keys.back
+            ))
+            .color(game_options.theme().menu_text_color()),
+        );
+        help_text.set_font(
+            Font::default(),
+            PxScale::from(window_dimensions.1 / SUB_TEXT_SCALE_DOWN),
+        );
+        let mut waiting_text = Text::new(
+            TextFragment::new("[!] Every gamepad player needs a gamepad first")
+                .color(game_options.theme().menu_help_color()),
+        );
+        waiting_text.set_font(
+            Font::default(),
+            PxScale::from(window_dimensions.1 / SUB_TEXT_SCALE_DOWN),
+        );
+        Self {
+            vec_lobby_slots,
+            start_flag: false,
+            back_flag: false,
+            waiting_flag: false,
+            title_text,
+            help_text,
+            waiting_text,
+            theme: *game_options.theme(),
+            tile_skin: game_options.tile_skin.clone(),
+            window_height: window_dimensions.1,
+        }
+    }
+
+    // only games with somebody on a gamepad go through here
+    pub fn is_needed(game_options: &MenuGameOptions) -> bool {
+        game_options
+            .active_slots()
+            .iter()
+            .any(|slot| game_options.arr_controls[*slot].0.is_empty())
+    }
+
+    // the assignment goes into the options, so it carries into the game (and its restarts)
+    pub fn update(&mut self, input: &Input, game_options: &mut MenuGameOptions) -> MenuItemTrigger {
+        if input.keydown_rotate_ccw.1 || self.back_flag {
+            self.start_flag = false;
+            self.back_flag = false;
+            self.waiting_flag = false;
+            return MenuItemTrigger::Back;
+        }
+        if input.keydown_start.1 || self.start_flag {
+            self.start_flag = false;
+            if self
+                .vec_lobby_slots
+                .iter()
+                .any(|lobby_slot| lobby_slot.gamepad_flag && lobby_slot.gamepad.is_none())
+            {
+                self.waiting_flag = true;
+                return MenuItemTrigger::None;
+            }
+            for gamepad_id in game_options.arr_gamepad_ids.iter_mut() {
+                *gamepad_id = None;
+            }
+            for lobby_slot in self.vec_lobby_slots.iter() {
+                game_options.arr_gamepad_ids[lobby_slot.slot] =
+                    lobby_slot.gamepad.as_ref().map(|gamepad| gamepad.0);
+            }
+            return MenuItemTrigger::StartGame;
+        }
+
+        MenuItemTrigger::None
+    }
+
+    // a new gamepad takes the first open spot or goes back with B; one with a spot can start the game or leave it
+    pub fn gamepad_button_down_event(&mut self, ctx: &Context, btn: Button, id: GamepadId) {
+        if let Some(lobby_slot) = self.vec_lobby_slots.iter_mut().find(|lobby_slot| {
+            lobby_slot
+                .gamepad
+                .as_ref()
+                .is_some_and(|gamepad| gamepad.0 == id)
+        }) {
+            if btn == Button::East {
+                println!("[+] Player {} left their spot", lobby_slot.player_num + 1);
+                lobby_slot.gamepad = None;
+                lobby_slot.update_text(self.window_height);
+            } else if btn == Button::Start {
+                self.start_flag = true;
+            }
+            return;
+        }
+        if btn == Button::East {
+            self.back_flag = true;
+            return;
+        }
+        if let Some(lobby_slot) = self
+            .vec_lobby_slots
+            .iter_mut()
+            .find(|lobby_slot| lobby_slot.gamepad_flag && lobby_slot.gamepad.is_none())
+        {
+            let name = gamepad::gamepad(ctx, id).name().to_string();
+            println!("[+] {} is player {}", name, lobby_slot.player_num + 1);
+            lobby_slot.gamepad = Some((id, name));
+            lobby_slot.update_text(self.window_height);
+            self.waiting_flag = false;
+        }
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context) {
+        let window_dimensions = graphics::size(ctx);
+
+        self.draw_text(ctx, &self.title_text, 0.05, &window_dimensions);
+        let row_spacing = if self.vec_lobby_slots.len() > 1 {
+            ((ROWS_BOTTOM - ROWS_TOP) / (self.vec_lobby_slots.len() - 1) as f32)
+                .min(MAX_ROW_SPACING)
+        } else {
+            0.0
+        };
+        for index in 0..self.vec_lobby_slots.len() {
+            if self.vec_lobby_slots[index].swatch.is_none() {
+                let color = self.vec_lobby_slots[index].color;
+                let pattern = self.vec_lobby_slots[index].pattern;
+                self.vec_lobby_slots[index].swatch = Some(
+                    TileGraphic::new_player(ctx, color, pattern, &self.theme, &self.tile_skin)
+                        .image,
+                );
+            }
+            let lobby_slot = &self.vec_lobby_slots[index];
+            let text_dimensions = lobby_slot.text.dimensions(ctx);
+            let x = (window_dimensions.0 - text_dimensions.w) / 2.0;
+            let y =
+                (window_dimensions.1 - text_dimensions.h) * (ROWS_TOP + row_spacing * index as f32);
+            graphics::draw(
+                ctx,
+                &lobby_slot.text,
+                DrawParam::new().dest(Point2::from_slice(&[x, y])),
+            )
+            .unwrap();
+            // the player's color goes to the left of their row, one text line tall
+            if let Some(swatch) = &lobby_slot.swatch {
+                let scale = text_dimensions.h / swatch.height() as f32;
+                graphics::draw(
+                    ctx,
+                    swatch,
+                    DrawParam::new()
+                        .dest(Point2::from_slice(&[x - text_dimensions.h * 1.5, y]))
+                        .scale(Vector2::from_slice(&[scale, scale])),
+                )
+                .unwrap();
+            }
+        }
+        if self.waiting_flag {
+            self.draw_text(ctx, &self.waiting_text, 0.88, &window_dimensions);
+        }
+        self.draw_text(ctx, &self.help_text, 0.95, &window_dimensions);
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text_var: &Text,
+        vertical_position: f32,
+        window_dimensions: &(f32, f32),
+    ) {
+        let text_var_dimensions = text_var.dimensions(ctx);
+        graphics::draw(
+            ctx,
+            text_var,
+            DrawParam::new().dest(Point2::from_slice(&[
+                (window_dimensions.0 - text_var_dimensions.w) / 2.0,
+                (window_dimensions.1 - text_var_dimensions.h) * vertical_position,
+            ])),
+        )
+        .unwrap();
+    }
+
+    pub fn resize_event(&mut self, height: f32) {
+        self.window_height = height;
+        for lobby_slot in self.vec_lobby_slots.iter_mut() {
+            lobby_slot.update_text(height);
+        }
+        self.title_text
+            .set_font(Font::default(), PxScale::from(height / TEXT_SCALE_DOWN));
+        self.help_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        self.waiting_text
+            .set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+    }
+}
@@ -1,17 +1,20 @@
-use ggez::event::KeyCode;
-use ggez::graphics::{self, Color, Font, PxScale, Text, TextFragment};
+use ggez::event::{GamepadId, KeyCode};
+use ggez::graphics::{Color, Font, PxScale, Text, TextFragment};
 
+use crate::cli::OptionsUnderCli;
+use crate::game::board::BoardDim;
+use crate::game::tile::TileSkin;
 use crate::game::{GameMode, GameSettings};
-use crate::inputs::KeyboardControlScheme;
+use crate::inputs::{GamepadControlScheme, KeyboardControlScheme, MenuKeys};
+use crate::inputs::{SocdPolicy, StickSettings};
+use crate::profiles::Profile;
+use crate::theme::{self, Theme};
 
 pub const MAX_STARTING_LEVEL: u8 = 29; // this is just the fastest speed, so yeah
 pub const MAX_NUM_PLAYERS: u8 = 64; // num_players being u8 technically caps this at 255
-
-pub const GRAY: Color = Color::new(0.4, 0.4, 0.4, 1.0);
-pub const DARK_GRAY: Color = Color::new(0.3, 0.3, 0.3, 1.0);
-pub const LIGHT_GRAY: Color = Color::new(0.6, 0.6, 0.6, 1.0);
-pub const SELECT_GREEN: Color = Color::new(0.153, 0.839, 0.075, 1.0);
-pub const HELP_RED: Color = Color::new(0.9, 0.11, 0.11, 1.0);
+pub const MIN_BOARD_WIDTH_PER_PLAYER: BoardDim = 4;
+pub const MAX_BOARD_WIDTH_PER_PLAYER: BoardDim = 10;
+pub const MAX_BOARD_WIDTH_CONSTANT: BoardDim = 20;
 
 pub const TEXT_SCALE_DOWN: f32 = 15.0;
 pub const SUB_TEXT_SCALE_DOWN: f32 = 25.0;
@@ -25,6 +28,9 @@ pub enum MenuState {
     Start,
     Settings,
     InputConfig,
+    HighScores,
+    Profiles,
+    Lobby,
 }
 
 #[repr(u8)]
@@ -42,6 +48,7 @@ pub enum MenuItemValueType {
 pub enum MenuItemTrigger {
     None,
     StartGame,
+    ContinueGame,
     SubMenu(MenuState),
     Back,
     SubSelection,
@@ -52,6 +59,12 @@ pub enum MenuItemTrigger {
     KeyRotateCcw,
     KeyBoardCw,
     KeyBoardCcw,
+    KeyRestart,
+    Resume,
+    Restart,
+    QuitToMenu,
+    SaveAndQuit,
+    SaveReplay,
 }
 
 pub struct MenuItem {
@@ -63,12 +76,14 @@ pub struct MenuItem {
     min_value: u8,
     num_values: u8,
     value_show_increase: u8,
-    pub keycode: Option<KeyCode>,
+    pub vec_keycodes: Vec<KeyCode>,
     pub trigger: MenuItemTrigger,
     selected: bool,
     value_type: MenuItemValueType,
     // draw
     text_scale_down: f32,
+    select_color: Color,
+    text_color: Color,
 }
 
 impl MenuItem {
@@ -83,10 +98,11 @@ impl MenuItem {
         title: &str,
         id: u8,
         trigger: MenuItemTrigger,
+        theme: &Theme,
         window_height: f32,
         text_scale_down: f32,
     ) -> Self {
-        let mut text = Text::new(TextFragment::new(title).color(graphics::Color::BLACK));
+        let mut text = Text::new(TextFragment::new(title).color(theme.menu_text_color()));
         text.set_font(
             Font::default(),
             PxScale::from(window_height / text_scale_down),
@@ -99,11 +115,13 @@ impl MenuItem {
             min_value: 0u8,
             num_values: 0u8,
             value_show_increase: 0u8,
-            keycode: None,
+            vec_keycodes: vec![],
             trigger,
             selected: false,
             value_type: MenuItemValueType::None,
             text_scale_down,
+            select_color: theme.menu_select_color(),
+            text_color: theme.menu_text_color(),
         }
     }
 
@@ -112,11 +130,12 @@ impl MenuItem {
         id: u8,
         start_on: bool,
         trigger: MenuItemTrigger,
+        theme: &Theme,
         window_height: f32,
         text_scale_down: f32,
     ) -> Self {
-        let mut text = Text::new(TextFragment::new(title).color(graphics::Color::BLACK));
-        text.add(TextFragment::new(Self::onoffstr(start_on)).color(graphics::Color::BLACK));
+        let mut text = Text::new(TextFragment::new(title).color(theme.menu_text_color()));
+        text.add(TextFragment::new(Self::onoffstr(start_on)).color(theme.menu_text_color()));
         text.set_font(
             Font::default(),
             PxScale::from(window_height / text_scale_down),
@@ -129,11 +148,13 @@ impl MenuItem {
             min_value: 0u8,
             num_values: 0u8,
             value_show_increase: 0u8,
-            keycode: None,
+            vec_keycodes: vec![],
             trigger,
             selected: false,
             value_type: MenuItemValueType::OnOff,
             text_scale_down,
+            select_color: theme.menu_select_color(),
+            text_color: theme.menu_text_color(),
         }
     }
 
@@ -145,13 +166,14 @@ impl MenuItem {
         num_values: u8,
         value_show_increase: u8,
         trigger: MenuItemTrigger,
+        theme: &Theme,
         window_height: f32,
         text_scale_down: f32,
     ) -> Self {
-        let mut text = Text::new(TextFragment::new(title).color(graphics::Color::BLACK));
+        let mut text = Text::new(TextFragment::new(title).color(theme.menu_text_color()));
         text.add(
             TextFragment::new(format!("{}", start_value + value_show_increase))
-                .color(graphics::Color::BLACK),
+                .color(theme.menu_text_color()),
         );
         text.set_font(
             Font::default(),
@@ -165,27 +187,29 @@ impl MenuItem {
             min_value,
             num_values,
             value_show_increase,
-            keycode: None,
+            vec_keycodes: vec![],
             trigger,
             selected: false,
             value_type: MenuItemValueType::Numerical,
             text_scale_down,
+            select_color: theme.menu_select_color(),
+            text_color: theme.menu_text_color(),
         }
     }
 
     pub fn new_keycodevalue(
         title: &str,
         id: u8,
-        opt_start_keycode: Option<KeyCode>,
+        start_keycodes: &[KeyCode],
         trigger: MenuItemTrigger,
+        theme: &Theme,
         window_height: f32,
         text_scale_down: f32,
     ) -> Self {
-        let mut text = Text::new(TextFragment::new(title).color(graphics::Color::BLACK));
-        text.add(match opt_start_keycode {
-            Some(key) => TextFragment::new(format!("{:?}", key)).color(graphics::Color::BLACK),
-            None => TextFragment::new("None").color(graphics::Color::BLACK),
-        });
+        let mut text = Text::new(TextFragment::new(title).color(theme.menu_text_color()));
+        text.add(
+            TextFragment::new(Self::keycodes_str(start_keycodes)).color(theme.menu_text_color()),
+        );
         text.set_font(
             Font::default(),
             PxScale::from(window_height / text_scale_down),
@@ -198,11 +222,13 @@ impl MenuItem {
             min_value: 0u8,
             num_values: 0u8,
             value_show_increase: 0u8,
-            keycode: opt_start_keycode,
+            vec_keycodes: start_keycodes.to_vec(),
             trigger,
             selected: false,
             value_type: MenuItemValueType::KeyCode,
             text_scale_down,
+            select_color: theme.menu_select_color(),
+            text_color: theme.menu_text_color(),
         }
     }
 
@@ -213,11 +239,12 @@ impl MenuItem {
         value: u8,
         num_values: u8,
         trigger: MenuItemTrigger,
+        theme: &Theme,
         window_height: f32,
         text_scale_down: f32,
     ) -> Self {
-        let mut text = Text::new(TextFragment::new(title).color(graphics::Color::BLACK));
-        text.add(TextFragment::new(start_custom_str).color(graphics::Color::BLACK));
+        let mut text = Text::new(TextFragment::new(title).color(theme.menu_text_color()));
+        text.add(TextFragment::new(start_custom_str).color(theme.menu_text_color()));
         text.set_font(
             Font::default(),
             PxScale::from(window_height / text_scale_down),
@@ -230,26 +257,28 @@ impl MenuItem {
             min_value: 0u8,
             num_values,
             value_show_increase: 0u8,
-            keycode: None,
+            vec_keycodes: vec![],
             trigger,
             selected: false,
             value_type: MenuItemValueType::Custom,
             text_scale_down,
+            select_color: theme.menu_select_color(),
+            text_color: theme.menu_text_color(),
         }
     }
 
     pub fn set_select(&mut self, select: bool) {
         self.selected = select;
         self.text.fragments_mut()[0].color = Some(if select {
-            SELECT_GREEN
+            self.select_color
         } else {
-            graphics::Color::BLACK
+            self.text_color
         });
         if self.value_type != MenuItemValueType::None {
             self.text.fragments_mut()[1].color = Some(if select {
-                SELECT_GREEN
+                self.select_color
             } else {
-                graphics::Color::BLACK
+                self.text_color
             });
             if self.value_type == MenuItemValueType::Numerical {
                 self.text.fragments_mut()[1].text = if select {
@@ -291,12 +320,21 @@ impl MenuItem {
         }
     }
 
-    pub fn set_keycode(&mut self, keycode: Option<KeyCode>) {
-        self.keycode = keycode;
-        match self.keycode {
-            Some(key) => self.text.fragments_mut()[1].text = format!("{:?}", key),
-            None => self.text.fragments_mut()[1].text = "None".to_string(),
-        };
+    pub fn set_keycodes(&mut self, keycodes: &[KeyCode]) {
+        self.vec_keycodes = keycodes.to_vec();
+        self.text.fragments_mut()[1].text = Self::keycodes_str(&self.vec_keycodes);
+    }
+
+    // every key bound to the movement, in the order they were bound
+    fn keycodes_str(keycodes: &[KeyCode]) -> String {
+        if keycodes.is_empty() {
+            return "None".to_string();
+        }
+        keycodes
+            .iter()
+            .map(|key| format!("{:?}", key))
+            .collect::<Vec<String>>()
+            .join(" / ")
     }
 
     pub fn resize(&mut self, window_height: f32) {
@@ -305,6 +343,19 @@ impl MenuItem {
             PxScale::from(window_height / self.text_scale_down),
         );
     }
+
+    pub fn set_colors(&mut self, select_color: Color, text_color: Color) {
+        self.select_color = select_color;
+        self.text_color = text_color;
+        let color = if self.selected {
+            select_color
+        } else {
+            text_color
+        };
+        for fragment in self.text.fragments_mut().iter_mut() {
+            fragment.color = Some(color);
+        }
+    }
 }
 
 pub struct MenuGameOptions {
@@ -312,7 +363,27 @@ pub struct MenuGameOptions {
     pub starting_level: u8,
     pub game_mode: GameMode,
     pub arr_controls: Vec<(KeyboardControlScheme, bool)>,
+    // the gamepad layout of each slot of arr_controls, for when it's set to a gamepad
+    pub arr_gamepad_controls: Vec<GamepadControlScheme>,
+    // the gamepad picked for each slot in the lobby, for as long as the program is open
+    pub arr_gamepad_ids: Vec<Option<GamepadId>>,
+    // which profile (by name) is sitting in each slot of arr_controls
+    pub arr_profile_names: Vec<Option<String>>,
+    // how each slot of arr_controls settles holding left and right together
+    pub arr_socd: Vec<SocdPolicy>,
+    pub profiles: Vec<Profile>,
+    // saved with the controls
+    pub menu_keys: MenuKeys,
     pub settings: GameSettings,
+    // the built-in themes and any theme files, with the one in use picked by index
+    pub vec_themes: Vec<(String, Theme)>,
+    pub theme_index: usize,
+    // tiles from the skin sheets in the resource folders, drawn over whichever theme is picked
+    pub tile_skin: TileSkin,
+    // set when the game was started from the command line
+    pub options_under_cli: Option<OptionsUnderCli>,
+    // last initials entered on the results screen
+    pub initials: String,
 }
 
 impl Default for MenuGameOptions {
@@ -324,16 +395,127 @@ impl Default for MenuGameOptions {
             starting_level: 0,
             game_mode: GameMode::None,
             arr_controls,
+            arr_gamepad_controls: vec![GamepadControlScheme::default(); MAX_NUM_PLAYERS as usize],
+            arr_gamepad_ids: vec![None; MAX_NUM_PLAYERS as usize],
+            arr_profile_names: vec![None; MAX_NUM_PLAYERS as usize],
+            arr_socd: vec![SocdPolicy::default(); MAX_NUM_PLAYERS as usize],
+            profiles: vec![],
+            menu_keys: MenuKeys::default(),
             settings: GameSettings::default(),
+            vec_themes: theme::built_in_themes(),
+            theme_index: 0,
+            tile_skin: TileSkin::default(),
+            options_under_cli: None,
+            initials: "AAA".to_string(),
         }
     }
 }
 
 impl MenuGameOptions {
-    pub fn reset_controls(&mut self) {
+    pub fn theme(&self) -> &Theme {
+        &self.vec_themes[self.theme_index].1
+    }
+
+    // keyboard schemes that don't have a key for every movement of the game mode can't be used, so they're cleared;
+    // rotatris schemes have every classic movement, so those are kept when going to classic;
+    // gamepad layouts missing a movement go back to the default layout instead, keeping their stick settings
+    pub fn clear_incomplete_controls(&mut self) {
+        let num_required_inputs = self.game_mode.num_required_inputs();
         for ctrls in self.arr_controls.iter_mut() {
-            ctrls.0.clear();
-            ctrls.1 = false;
+            if ctrls.0.num_movements() < num_required_inputs {
+                ctrls.0.clear();
+            }
+        }
+        for g_ctrl_scheme in self.arr_gamepad_controls.iter_mut() {
+            if g_ctrl_scheme.num_movements() < num_required_inputs {
+                *g_ctrl_scheme = GamepadControlScheme::default().with_sticks(g_ctrl_scheme.sticks);
+            }
+        }
+    }
+
+    // whether there are enough usable controls for everyone to play
+    pub fn enough_controls(&self) -> bool {
+        let num_required_inputs = self.game_mode.num_required_inputs();
+        let ctrls_count = self
+            .arr_controls
+            .iter()
+            .filter(|ctrls| ctrls.1 || ctrls.0.num_movements() >= num_required_inputs)
+            .count();
+        ctrls_count >= self.num_players as usize
+    }
+
+    // the slots that will be playing, in player order; the same ones GameOptions picks
+    pub fn active_slots(&self) -> Vec<usize> {
+        self.arr_controls
+            .iter()
+            .enumerate()
+            .filter(|(_, ctrls)| !ctrls.0.is_empty() || ctrls.1)
+            .map(|(slot, _)| slot)
+            .take(self.num_players as usize)
+            .collect()
+    }
+
+    // gives the slot its profile's controls, taking any of those keys away from the other slots;
+    // returns whether another slot lost keys that way
+    pub fn apply_slot_profile(&mut self, slot: usize) -> bool {
+        let profile = match self.slot_profile(slot) {
+            Some(profile) => profile.clone(),
+            None => return false,
+        };
+        let k_ctrl_scheme = profile.control_scheme();
+        let mut conflict_flag = false;
+        for other in 0..self.arr_controls.len() {
+            if other != slot
+                && (self.arr_controls[other].0)
+                    .vec_keycode_movement_pair
+                    .iter()
+                    .any(|(key, _)| k_ctrl_scheme.movement_from_keycode(*key).is_some())
+            {
+                self.arr_controls[other].0.clear();
+                self.arr_profile_names[other] = None;
+                conflict_flag = true;
+                println!(
+                    "[!] Player {} lost their keys to profile {}",
+                    other + 1,
+                    profile.name
+                );
+            }
+        }
+        self.arr_controls[slot] = (k_ctrl_scheme, profile.gamepad);
+        self.arr_gamepad_controls[slot] = profile.gamepad_scheme();
+        self.arr_socd[slot] = profile.socd;
+        // a profile set up in classic doesn't have enough keys for rotatris
+        self.clear_incomplete_controls();
+        conflict_flag
+    }
+
+    // the stick settings of the slot that claimed this gamepad in the lobby, or the defaults
+    pub fn gamepad_sticks(&self, id: GamepadId) -> StickSettings {
+        match self
+            .arr_gamepad_ids
+            .iter()
+            .position(|gamepad_id| *gamepad_id == Some(id))
+        {
+            Some(slot) => self.arr_gamepad_controls[slot].sticks,
+            None => StickSettings::default(),
+        }
+    }
+
+    pub fn slot_profile(&self, slot: usize) -> Option<&Profile> {
+        let name = self.arr_profile_names[slot].as_ref()?;
+        self.profiles.iter().find(|profile| &profile.name == name)
+    }
+
+    // a slot can't hold on to a profile that was deleted (or is already sitting somewhere else)
+    pub fn forget_missing_profiles(&mut self) {
+        for slot in 0..self.arr_profile_names.len() {
+            if let Some(name) = &self.arr_profile_names[slot] {
+                if self.slot_profile(slot).is_none()
+                    || self.arr_profile_names[..slot].contains(&Some(name.clone()))
+                {
+                    self.arr_profile_names[slot] = None;
+                }
+            }
         }
     }
 }
@@ -0,0 +1,453 @@
+use ggez::event::KeyCode;
+use ggez::graphics::{self, DrawParam, Font, PxScale, Text, TextFragment};
+use ggez::mint::Point2;
+use ggez::Context;
+
+use crate::game::stats::format_frames;
+use crate::game::tile::NUM_PLAYERCOLORS;
+use crate::inputs::Input;
+use crate::menu::menuhelpers::{MenuGameOptions, MenuItem, MenuItemTrigger};
+use crate::menu::menuhelpers::{SUB_TEXT_SCALE_DOWN, TEXT_SCALE_DOWN};
+use crate::profiles::{is_valid_name, Profile, MAX_PROFILE_NAME_LENGTH};
+use crate::profiles::{MAX_DAS_THRESHOLD_BIG, MAX_DAS_THRESHOLD_LITTLE};
+
+static NEW_PROFILE_STR: &str = "New";
+static AUTO_COLOR_STR: &str = "Lane";
+
+enum ProfilesMenuItemId {
+    Back,
+    Profile,
+    DasThresholdBig,
+    DasThresholdLittle,
+    Color,
+    Delete,
+}
+
+pub struct ProfilesMenu {
+    // logic
+    selection: usize,
+    // index into the profiles, or the number of profiles for a new one
+    profile_index: usize,
+    naming_flag: bool,
+    name_buffer: String,
+    delete_confirm_flag: bool,
+    bad_name_flag: bool,
+    vec_menu_items: Vec<MenuItem>,
+    // drawing
+    stats_text: Text,
+    new_profile_text: Text,
+    delete_confirm_text: Text,
+    bad_name_text: Text,
+    text_color: graphics::Color,
+    window_height: f32,
+}
+
+impl ProfilesMenu {
+    pub fn new(game_options: &MenuGameOptions, window_dimensions: (f32, f32)) -> Self {
+        let mut vec_menu_items: Vec<MenuItem> = vec![
+            MenuItem::new_novalue(
+                "Back",
+                ProfilesMenuItemId::Back as u8,
+                MenuItemTrigger::Back,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "Profile: ",
+                ProfilesMenuItemId::Profile as u8,
+                NEW_PROFILE_STR,
+                0,
+                1,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_numericalvalue(
+                "DAS Delay: ",
+                ProfilesMenuItemId::DasThresholdBig as u8,
+                1,
+                1,
+                MAX_DAS_THRESHOLD_BIG,
+                0,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_numericalvalue(
+                "DAS Repeat: ",
+                ProfilesMenuItemId::DasThresholdLittle as u8,
+                1,
+                1,
+                MAX_DAS_THRESHOLD_LITTLE,
+                0,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "Color: ",
+                ProfilesMenuItemId::Color as u8,
+                AUTO_COLOR_STR,
+                0,
+                NUM_PLAYERCOLORS + 1,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_novalue(
+                "Delete",
+                ProfilesMenuItemId::Delete as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                SUB_TEXT_SCALE_DOWN,
+            ),
+        ];
+        vec_menu_items[0].set_select(true);
+        let mut profiles_menu = Self {
+            // logic
+            selection: 0,
+            profile_index: 0,
+            naming_flag: false,
+            name_buffer: String::new(),
+            delete_confirm_flag: false,
+            bad_name_flag: false,
+            vec_menu_items,
+            // drawing
+            stats_text: Text::default(),
+            new_profile_text: Text::new(
+                TextFragment::new(format!(
+                    "{:?}/Enter to name a new profile\n\nPick it for a player under Controls",
+                    game_options.menu_keys.select
+                ))
+                .color(game_options.theme().menu_text_color()),
+            ),
+            delete_confirm_text: Text::new(
+                TextFragment::new("[!] Press again to delete")
+                    .color(game_options.theme().menu_help_color()),
+            ),
+            bad_name_text: Text::new(
+                TextFragment::new("[!] Name is empty or taken")
+                    .color(game_options.theme().menu_help_color()),
+            ),
+            text_color: game_options.theme().menu_text_color(),
+            window_height: window_dimensions.1,
+        };
+        profiles_menu.resize_event(window_dimensions.1);
+        profiles_menu.load_profile_values(game_options);
+        profiles_menu
+    }
+
+    pub fn update(&mut self, input: &Input, game_options: &mut MenuGameOptions) -> MenuItemTrigger {
+        if self.naming_flag {
+            self.update_naming(input, game_options);
+            return MenuItemTrigger::None;
+        }
+
+        if input.keydown_rotate_ccw.1 {
+            // escape was pressed
+            self.delete_confirm_flag = false;
+            return MenuItemTrigger::Back;
+        }
+
+        if input.keydown_right.1 || input.keydown_left.1 {
+            self.delete_confirm_flag = false;
+            self.change_value(game_options, input.keydown_right.1);
+        }
+
+        if input.keydown_down.1 {
+            self.delete_confirm_flag = false;
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = (self.selection + 1) % self.num_items_shown(game_options);
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if input.keydown_up.1 {
+            self.delete_confirm_flag = false;
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = if self.selection == 0 {
+                self.num_items_shown(game_options) - 1
+            } else {
+                self.selection - 1
+            };
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+
+        if input.keydown_start.1 {
+            let id = self.vec_menu_items[self.selection].id;
+            if id == ProfilesMenuItemId::Profile as u8
+                && self.profile_index == game_options.profiles.len()
+            {
+                self.naming_flag = true;
+                self.bad_name_flag = false;
+                self.name_buffer.clear();
+                self.update_name_text();
+            } else if id == ProfilesMenuItemId::Delete as u8 {
+                if self.delete_confirm_flag {
+                    self.delete_profile(game_options);
+                } else {
+                    self.delete_confirm_flag = true;
+                }
+            } else {
+                return self.vec_menu_items[self.selection].trigger.clone();
+            }
+        }
+
+        MenuItemTrigger::None
+    }
+
+    // typing letters fills in the name; the menu keys confirm or cancel it;
+    // returns whether the key was a letter or Backspace that went to the name, so it doesn't also work the menu
+    pub fn key_down_event(&mut self, keycode: KeyCode) -> bool {
+        if !self.naming_flag {
+            return false;
+        }
+        let letter = format!("{:?}", keycode);
+        if keycode == KeyCode::Back {
+            self.name_buffer.pop();
+        } else if letter.len() == 1 && letter.chars().all(|c| c.is_ascii_alphabetic()) {
+            if self.name_buffer.len() < MAX_PROFILE_NAME_LENGTH {
+                self.name_buffer.push_str(&letter);
+            }
+        } else {
+            return false;
+        }
+        self.update_name_text();
+        true
+    }
+
+    fn update_naming(&mut self, input: &Input, game_options: &mut MenuGameOptions) {
+        if input.keydown_rotate_ccw.1 {
+            self.naming_flag = false;
+            self.bad_name_flag = false;
+            self.load_profile_values(game_options);
+        } else if input.keydown_start.1 {
+            if is_valid_name(&self.name_buffer)
+                && !game_options
+                    .profiles
+                    .iter()
+                    .any(|profile| profile.name == self.name_buffer)
+            {
+                game_options
+                    .profiles
+                    .push(Profile::new(self.name_buffer.clone()));
+                self.naming_flag = false;
+                self.bad_name_flag = false;
+                self.load_profile_values(game_options);
+            } else {
+                self.bad_name_flag = true;
+            }
+        }
+    }
+
+    fn change_value(&mut self, game_options: &mut MenuGameOptions, rightward_press: bool) {
+        let id = self.vec_menu_items[self.selection].id;
+        if id == ProfilesMenuItemId::Profile as u8 {
+            let num_choices = game_options.profiles.len() + 1;
+            self.profile_index = if rightward_press {
+                (self.profile_index + 1) % num_choices
+            } else {
+                (self.profile_index + num_choices - 1) % num_choices
+            };
+            self.load_profile_values(game_options);
+            return;
+        }
+        let profile = match game_options.profiles.get_mut(self.profile_index) {
+            Some(profile) => profile,
+            None => return,
+        };
+        let item = &mut self.vec_menu_items[self.selection];
+        item.change_val(rightward_press);
+        if id == ProfilesMenuItemId::DasThresholdBig as u8 {
+            profile.das_threshold_big = item.value;
+        } else if id == ProfilesMenuItemId::DasThresholdLittle as u8 {
+            profile.das_threshold_little = item.value;
+        } else if id == ProfilesMenuItemId::Color as u8 {
+            // 0 is the lane's color, the rest are the player colors
+            profile.color = item.value.checked_sub(1);
+            item.text.fragments_mut()[1].text = Self::color_str(profile.color);
+        }
+    }
+
+    fn delete_profile(&mut self, game_options: &mut MenuGameOptions) {
+        self.delete_confirm_flag = false;
+        if self.profile_index < game_options.profiles.len() {
+            let profile = game_options.profiles.remove(self.profile_index);
+            println!("[+] Deleted profile {}", profile.name);
+            game_options.forget_missing_profiles();
+            if self.profile_index > 0 {
+                self.profile_index -= 1;
+            }
+            self.load_profile_values(game_options);
+        }
+    }
+
+    // only Back and the profile picker are shown until there's a profile to edit
+    fn num_items_shown(&self, game_options: &MenuGameOptions) -> usize {
+        if self.profile_index < game_options.profiles.len() {
+            self.vec_menu_items.len()
+        } else {
+            2
+        }
+    }
+
+    fn color_str(color: Option<u8>) -> String {
+        match color {
+            Some(color) => format!("{}", color + 1),
+            None => AUTO_COLOR_STR.to_string(),
+        }
+    }
+
+    fn update_name_text(&mut self) {
+        let name_text = format!("{}_", self.name_buffer);
+        for item in self.vec_menu_items.iter_mut() {
+            if item.id == ProfilesMenuItemId::Profile as u8 {
+                item.text.fragments_mut()[1].text = name_text.clone();
+            }
+        }
+    }
+
+    fn load_profile_values(&mut self, game_options: &MenuGameOptions) {
+        if self.selection >= self.num_items_shown(game_options) {
+            self.vec_menu_items[self.selection].set_select(false);
+            self.selection = 1;
+            self.vec_menu_items[self.selection].set_select(true);
+        }
+        let profile = game_options.profiles.get(self.profile_index);
+        for (index, item) in self.vec_menu_items.iter_mut().enumerate() {
+            let id = item.id;
+            if id == ProfilesMenuItemId::Profile as u8 {
+                item.text.fragments_mut()[1].text = match profile {
+                    Some(profile) => profile.name.clone(),
+                    None => NEW_PROFILE_STR.to_string(),
+                };
+                continue;
+            }
+            let profile = match profile {
+                Some(profile) => profile,
+                None => continue,
+            };
+            if id == ProfilesMenuItemId::DasThresholdBig as u8 {
+                item.value = profile.das_threshold_big;
+            } else if id == ProfilesMenuItemId::DasThresholdLittle as u8 {
+                item.value = profile.das_threshold_little;
+            } else if id == ProfilesMenuItemId::Color as u8 {
+                item.value = profile.color.map_or(0, |color| color + 1);
+                item.text.fragments_mut()[1].text = Self::color_str(profile.color);
+            }
+            // refreshes how the value is shown
+            item.set_select(index == self.selection);
+        }
+        self.update_stats_text(profile);
+    }
+
+    fn update_stats_text(&mut self, profile: Option<&Profile>) {
+        let stats_string = match profile {
+            Some(profile) => {
+                let stats = &profile.stats;
+                format!(
+                    "Games: {}    Time Played: {}\nPieces: {}    Lines: {}    Tetrisn'ts: {}\nBest Score: {}    Total Score: {}",
+                    stats.games_played,
+                    format_frames(stats.frames_played.min(u32::MAX as u64) as u32),
+                    stats.pieces_placed,
+                    stats.lines,
+                    stats.tetrisnts,
+                    stats.best_score,
+                    stats.total_score
+                )
+            }
+            None => String::new(),
+        };
+        self.stats_text = Text::new(TextFragment::new(stats_string).color(self.text_color));
+        self.stats_text.set_font(
+            Font::default(),
+            PxScale::from(self.window_height / SUB_TEXT_SCALE_DOWN),
+        );
+    }
+
+    pub fn draw(&mut self, ctx: &mut Context, game_options: &MenuGameOptions) {
+        let window_dimensions = graphics::size(ctx);
+
+        for (index, item) in self
+            .vec_menu_items
+            .iter()
+            .take(self.num_items_shown(game_options))
+            .enumerate()
+        {
+            let vertical_position = if index < 2 {
+                0.05 + 0.12 * index as f32
+            } else {
+                0.25 + 0.07 * index as f32
+            };
+            self.draw_text(ctx, &item.text, vertical_position, &window_dimensions);
+        }
+        if self.bad_name_flag {
+            self.draw_text(ctx, &self.bad_name_text, 0.3, &window_dimensions);
+        } else if self.delete_confirm_flag {
+            self.draw_text(ctx, &self.delete_confirm_text, 0.3, &window_dimensions);
+        }
+        if self.profile_index < game_options.profiles.len() {
+            self.draw_text(ctx, &self.stats_text, 0.95, &window_dimensions);
+        } else if !self.naming_flag {
+            self.draw_text(ctx, &self.new_profile_text, 0.5, &window_dimensions);
+        }
+    }
+
+    fn draw_text(
+        &self,
+        ctx: &mut Context,
+        text_var: &Text,
+        vertical_position: f32,
+        window_dimensions: &(f32, f32),
+    ) {
+        let text_var_dimensions = text_var.dimensions(ctx);
+        graphics::draw(
+            ctx,
+            text_var,
+            DrawParam::new().dest(Point2::from_slice(&[
+                (window_dimensions.0 - text_var_dimensions.w) / 2.0,
+                (window_dimensions.1 - text_var_dimensions.h) * vertical_position,
+            ])),
+        )
+        .unwrap();
+    }
+
+    pub fn resize_event(&mut self, height: f32) {
+        self.window_height = height;
+        for item in self.vec_menu_items.iter_mut() {
+            item.resize(height);
+        }
+        for text in [
+            &mut self.stats_text,
+            &mut self.new_profile_text,
+            &mut self.delete_confirm_text,
+            &mut self.bad_name_text,
+        ] {
+            text.set_font(Font::default(), PxScale::from(height / SUB_TEXT_SCALE_DOWN));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn naming_keeps_letters_and_backspace_from_the_menu() {
+        let mut profiles_menu = ProfilesMenu::new(&MenuGameOptions::default(), (800.0, 600.0));
+        assert!(!profiles_menu.key_down_event(KeyCode::Back));
+        profiles_menu.naming_flag = true;
+        assert!(profiles_menu.key_down_event(KeyCode::A));
+        assert!(profiles_menu.key_down_event(KeyCode::B));
+        assert!(profiles_menu.key_down_event(KeyCode::Back));
+        assert_eq!(profiles_menu.name_buffer, "A");
+        // the menu keys still confirm or cancel the name
+        assert!(!profiles_menu.key_down_event(KeyCode::Escape));
+        assert!(!profiles_menu.key_down_event(KeyCode::Return));
+    }
+}
@@ -2,10 +2,10 @@ use ggez::graphics::{self, DrawParam, Text};
 use ggez::mint::Point2;
 use ggez::Context;
 
-use crate::game::GameSettings;
 use crate::inputs::Input;
 use crate::menu::menuhelpers::TEXT_SCALE_DOWN;
-use crate::menu::menuhelpers::{MenuItem, MenuItemTrigger};
+use crate::menu::menuhelpers::{MenuGameOptions, MenuItem, MenuItemTrigger};
+use crate::theme::Theme;
 
 use crate::game::board::BoardDim;
 
@@ -14,6 +14,11 @@ enum SettingsMenuItemId {
     GhostPiecesState,
     BoardWidthPerPlayer,
     ExtraBoardWidth,
+    EliminationState,
+    InitialRotationState,
+    InputOverlayState,
+    TilePatternsState,
+    Theme,
 }
 
 pub struct SettingsMenu {
@@ -23,12 +28,14 @@ pub struct SettingsMenu {
 }
 
 impl SettingsMenu {
-    pub fn new(starting_settings: &GameSettings, window_dimensions: (f32, f32)) -> Self {
+    pub fn new(game_options: &MenuGameOptions, window_dimensions: (f32, f32)) -> Self {
+        let starting_settings = &game_options.settings;
         let mut vec_menu_items: Vec<MenuItem> = vec![
             MenuItem::new_novalue(
                 "Back",
                 SettingsMenuItemId::Back as u8,
                 MenuItemTrigger::Back,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -37,6 +44,7 @@ impl SettingsMenu {
                 SettingsMenuItemId::GhostPiecesState as u8,
                 starting_settings.ghost_pieces_state,
                 MenuItemTrigger::None,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -48,6 +56,7 @@ impl SettingsMenu {
                 7,
                 0,
                 MenuItemTrigger::None,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -59,6 +68,54 @@ impl SettingsMenu {
                 21,
                 0,
                 MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Elimination: ",
+                SettingsMenuItemId::EliminationState as u8,
+                starting_settings.elimination_state,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Initial Rotation: ",
+                SettingsMenuItemId::InitialRotationState as u8,
+                starting_settings.initial_rotation_state,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Input Overlay: ",
+                SettingsMenuItemId::InputOverlayState as u8,
+                starting_settings.input_overlay_state,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_onoffvalue(
+                "Tile Patterns: ",
+                SettingsMenuItemId::TilePatternsState as u8,
+                starting_settings.tile_patterns_state,
+                MenuItemTrigger::None,
+                game_options.theme(),
+                window_dimensions.1,
+                TEXT_SCALE_DOWN,
+            ),
+            MenuItem::new_customvalue(
+                "Theme: ",
+                SettingsMenuItemId::Theme as u8,
+                &game_options.vec_themes[game_options.theme_index].0,
+                game_options.theme_index as u8,
+                game_options.vec_themes.len() as u8,
+                MenuItemTrigger::None,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ),
@@ -71,7 +128,7 @@ impl SettingsMenu {
         }
     }
 
-    pub fn update(&mut self, input: &Input, settings: &mut GameSettings) -> MenuItemTrigger {
+    pub fn update(&mut self, input: &Input, game_options: &mut MenuGameOptions) -> MenuItemTrigger {
         if input.keydown_rotate_ccw.1 {
             // escape was pressed
             return MenuItemTrigger::Back;
@@ -85,9 +142,19 @@ impl SettingsMenu {
             self.vec_menu_items[self.selection].change_val(false);
         }
 
+        let settings = &mut game_options.settings;
         settings.ghost_pieces_state = self.get_ghost_pieces_state();
         settings.board_width_per_player = self.get_board_width_per_player() as BoardDim;
         settings.board_width_constant = self.get_board_width_constant() as BoardDim;
+        settings.elimination_state = self.get_elimination_state();
+        settings.initial_rotation_state = self.get_initial_rotation_state();
+        settings.input_overlay_state = self.get_input_overlay_state();
+        settings.tile_patterns_state = self.get_tile_patterns_state();
+        let theme_index = self.get_theme_index();
+        if theme_index != game_options.theme_index {
+            game_options.theme_index = theme_index;
+            self.set_theme_text(&game_options.vec_themes[theme_index].0);
+        }
 
         if input.keydown_down.1 {
             self.vec_menu_items[self.selection].set_select(false);
@@ -139,6 +206,66 @@ impl SettingsMenu {
         unreachable!("Failed to get extra board width in Menu::Settings");
     }
 
+    fn get_elimination_state(&self) -> bool {
+        for item in self.vec_menu_items.iter() {
+            if item.id == SettingsMenuItemId::EliminationState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get elimination state in Menu::Settings");
+    }
+
+    fn get_initial_rotation_state(&self) -> bool {
+        for item in self.vec_menu_items.iter() {
+            if item.id == SettingsMenuItemId::InitialRotationState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get initial rotation state in Menu::Settings");
+    }
+
+    fn get_input_overlay_state(&self) -> bool {
+        for item in self.vec_menu_items.iter() {
+            if item.id == SettingsMenuItemId::InputOverlayState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get input overlay state in Menu::Settings");
+    }
+
+    fn get_tile_patterns_state(&self) -> bool {
+        for item in self.vec_menu_items.iter() {
+            if item.id == SettingsMenuItemId::TilePatternsState as u8 {
+                return item.on;
+            }
+        }
+        unreachable!("Failed to get tile patterns state in Menu::Settings");
+    }
+
+    fn get_theme_index(&self) -> usize {
+        for item in self.vec_menu_items.iter() {
+            if item.id == SettingsMenuItemId::Theme as u8 {
+                return item.value as usize;
+            }
+        }
+        unreachable!("Failed to get theme in Menu::Settings");
+    }
+
+    fn set_theme_text(&mut self, name: &str) {
+        for item in self.vec_menu_items.iter_mut() {
+            if item.id == SettingsMenuItemId::Theme as u8 {
+                item.text.fragments_mut()[1].text = name.to_string();
+            }
+        }
+    }
+
+    // this menu stays up while its theme is changed, so it's recolored in place instead of made again
+    pub fn set_theme(&mut self, theme: &Theme) {
+        for item in self.vec_menu_items.iter_mut() {
+            item.set_colors(theme.menu_select_color(), theme.menu_text_color());
+        }
+    }
+
     pub fn draw(&mut self, ctx: &mut Context) {
         let window_dimensions = graphics::size(ctx);
         let num_menu_items_to_draw = self.vec_menu_items.len();
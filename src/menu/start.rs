@@ -4,8 +4,8 @@ use ggez::Context;
 
 use crate::game::GameMode;
 use crate::inputs::Input;
+use crate::menu::menuhelpers::TEXT_SCALE_DOWN;
 use crate::menu::menuhelpers::{MenuGameOptions, MenuItem, MenuItemTrigger, MenuState};
-use crate::menu::menuhelpers::{HELP_RED, TEXT_SCALE_DOWN};
 use crate::menu::menuhelpers::{MAX_NUM_PLAYERS, MAX_STARTING_LEVEL};
 
 enum StartMenuItemId {
@@ -14,6 +14,7 @@ enum StartMenuItemId {
     StartingLevel,
     Settings,
     Controls,
+    Profiles,
 }
 
 pub struct StartMenu {
@@ -28,7 +29,7 @@ pub struct StartMenu {
 
 impl StartMenu {
     pub fn new(game_options: &MenuGameOptions, window_dimensions: (f32, f32)) -> Self {
-        let mut vec_menu_items: Vec<MenuItem> = Vec::with_capacity(4);
+        let mut vec_menu_items: Vec<MenuItem> = Vec::with_capacity(6);
         Self::fill_vec_menu_items(game_options, &mut vec_menu_items, window_dimensions);
         vec_menu_items[0].set_select(true);
         Self {
@@ -39,7 +40,8 @@ impl StartMenu {
             vec_menu_items,
             // drawing
             not_enough_controls_text: Text::new(
-                TextFragment::new("[!] Not enough Controls Setup to Start").color(HELP_RED),
+                TextFragment::new("[!] Not enough Controls Setup to Start")
+                    .color(game_options.theme().menu_help_color()),
             ),
         }
     }
@@ -108,6 +110,7 @@ impl StartMenu {
             "Start",
             StartMenuItemId::Start as u8,
             MenuItemTrigger::StartGame,
+            game_options.theme(),
             window_dimensions.1,
             TEXT_SCALE_DOWN,
         ));
@@ -120,6 +123,7 @@ impl StartMenu {
                 MAX_NUM_PLAYERS,
                 0,
                 MenuItemTrigger::None,
+                game_options.theme(),
                 window_dimensions.1,
                 TEXT_SCALE_DOWN,
             ));
@@ -132,6 +136,7 @@ impl StartMenu {
             MAX_STARTING_LEVEL + 1,
             0,
             MenuItemTrigger::None,
+            game_options.theme(),
             window_dimensions.1,
             TEXT_SCALE_DOWN,
         ));
@@ -139,6 +144,7 @@ impl StartMenu {
             "Settings",
             StartMenuItemId::Settings as u8,
             MenuItemTrigger::SubMenu(MenuState::Settings),
+            game_options.theme(),
             window_dimensions.1,
             TEXT_SCALE_DOWN,
         ));
@@ -146,6 +152,15 @@ impl StartMenu {
             "Controls",
             StartMenuItemId::Controls as u8,
             MenuItemTrigger::SubMenu(MenuState::InputConfig),
+            game_options.theme(),
+            window_dimensions.1,
+            TEXT_SCALE_DOWN,
+        ));
+        vec_menu_items.push(MenuItem::new_novalue(
+            "Profiles",
+            StartMenuItemId::Profiles as u8,
+            MenuItemTrigger::SubMenu(MenuState::Profiles),
+            game_options.theme(),
             window_dimensions.1,
             TEXT_SCALE_DOWN,
         ));
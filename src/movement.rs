@@ -1,9 +1,10 @@
 use crate::menu::menuhelpers::MenuItemTrigger;
+use serde::{Deserialize, Serialize};
 
 use std::convert::TryFrom;
 
 #[repr(u8)]
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Copy, Clone, Serialize, Deserialize)]
 pub enum Movement {
     Down,
     Left,
@@ -14,6 +15,7 @@ pub enum Movement {
     DoubleRotate,
     BoardCw,
     BoardCcw,
+    Restart,
     None,
 }
 
@@ -33,7 +35,8 @@ impl TryFrom<u8> for Movement {
             6 => Ok(Movement::DoubleRotate),
             7 => Ok(Movement::BoardCw),
             8 => Ok(Movement::BoardCcw),
-            9 => Ok(Movement::None),
+            9 => Ok(Movement::Restart),
+            10 => Ok(Movement::None),
             _ => Err("Invalid u8 value"),
         }
     }
@@ -54,6 +57,7 @@ impl TryFrom<MenuItemTrigger> for Movement {
             MenuItemTrigger::KeyRotateCcw => Ok(Movement::RotateCcw),
             MenuItemTrigger::KeyBoardCw => Ok(Movement::BoardCw),
             MenuItemTrigger::KeyBoardCcw => Ok(Movement::BoardCcw),
+            MenuItemTrigger::KeyRestart => Ok(Movement::Restart),
             _ => Err("Invalid MenuItemTrigger value"),
         }
     }
@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::{check_version, report_corrupt_file, write_file, CONFIG_VERSION};
+use crate::config::{GamepadBinding, KeyBinding};
+use crate::game::stats::PlayerStats;
+use crate::game::tile::NUM_PLAYERCOLORS;
+use crate::game::{DAS_THRESHOLD_BIG, DAS_THRESHOLD_LITTLE};
+use crate::inputs::{GamepadControlScheme, KeyboardControlScheme, MAX_KEYS_PER_MOVEMENT};
+use crate::inputs::{SocdPolicy, StickSettings};
+
+use std::fs;
+use std::path::Path;
+
+pub static PROFILES_FILE: &str = "profiles.toml";
+pub const MAX_PROFILE_NAME_LENGTH: usize = 10;
+pub const MAX_DAS_THRESHOLD_BIG: u8 = 30;
+pub const MAX_DAS_THRESHOLD_LITTLE: u8 = 15;
+
+// a named player that carries their controls, handling and stats from seat to seat;
+// plain values have to come before keys and stats, which are written out as tables
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    pub gamepad: bool,
+    pub das_threshold_big: u8,
+    pub das_threshold_little: u8,
+    pub socd: SocdPolicy,
+    // None keeps the color of whichever lane the player ends up in
+    pub color: Option<u8>,
+    pub keys: Vec<KeyBinding>,
+    // empty for the default gamepad layout, which isn't written out since it would land after the keys tables
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub buttons: Vec<GamepadBinding>,
+    #[serde(skip_serializing_if = "StickSettings::is_default")]
+    pub sticks: StickSettings,
+    pub stats: LifetimeStats,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            gamepad: false,
+            keys: vec![],
+            buttons: vec![],
+            sticks: StickSettings::default(),
+            das_threshold_big: DAS_THRESHOLD_BIG,
+            das_threshold_little: DAS_THRESHOLD_LITTLE,
+            socd: SocdPolicy::default(),
+            color: None,
+            stats: LifetimeStats::default(),
+        }
+    }
+}
+
+impl Profile {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            ..Self::default()
+        }
+    }
+
+    pub fn has_controls(&self) -> bool {
+        self.gamepad || !self.keys.is_empty()
+    }
+
+    pub fn control_scheme(&self) -> KeyboardControlScheme {
+        let mut k_ctrl_scheme = KeyboardControlScheme::default();
+        for binding in self.keys.iter() {
+            k_ctrl_scheme.add_pair(binding.key, binding.movement);
+        }
+        k_ctrl_scheme
+    }
+
+    pub fn gamepad_scheme(&self) -> GamepadControlScheme {
+        GamepadBinding::to_scheme(&self.buttons, self.sticks)
+    }
+
+    pub fn set_controls(
+        &mut self,
+        ctrls: &(KeyboardControlScheme, bool),
+        g_ctrl_scheme: &GamepadControlScheme,
+        socd: SocdPolicy,
+    ) {
+        self.keys = KeyBinding::from_scheme(&ctrls.0);
+        self.gamepad = ctrls.1;
+        self.buttons = GamepadBinding::from_scheme(g_ctrl_scheme);
+        self.sticks = g_ctrl_scheme.sticks;
+        self.socd = socd;
+    }
+
+    // anything a hand-edited file got wrong goes back to its default
+    fn fix_up(&mut self, path: &Path) {
+        if !(1..=MAX_DAS_THRESHOLD_BIG).contains(&self.das_threshold_big) {
+            self.das_threshold_big = DAS_THRESHOLD_BIG;
+        }
+        if !(1..=MAX_DAS_THRESHOLD_LITTLE).contains(&self.das_threshold_little) {
+            self.das_threshold_little = DAS_THRESHOLD_LITTLE;
+        }
+        if self.color.is_some_and(|color| color >= NUM_PLAYERCOLORS) {
+            self.color = None;
+        }
+        let mut vec_keys: Vec<KeyBinding> = Vec::with_capacity(self.keys.len());
+        for binding in self.keys.drain(..) {
+            if vec_keys.iter().any(|b| b.key == binding.key)
+                || vec_keys
+                    .iter()
+                    .filter(|b| b.movement == binding.movement)
+                    .count()
+                    >= MAX_KEYS_PER_MOVEMENT
+            {
+                println!(
+                    "[!] {}: ignoring {:?} for {:?} of profile {}, it's already in use",
+                    path.display(),
+                    binding.key,
+                    binding.movement,
+                    self.name
+                );
+                continue;
+            }
+            vec_keys.push(binding);
+        }
+        self.keys = vec_keys;
+        let owner = format!("profile {}", self.name);
+        self.buttons = GamepadBinding::fix_up(&self.buttons, path, &owner);
+        if self.sticks.fix_up() {
+            println!(
+                "[!] {}: the stick settings of {} are out of range, using the defaults for those",
+                path.display(),
+                owner
+            );
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub frames_played: u64,
+    pub pieces_placed: u64,
+    // rings in rotatris
+    pub lines: u64,
+    pub tetrisnts: u64,
+    pub total_score: u64,
+    pub best_score: u64,
+}
+
+impl LifetimeStats {
+    pub fn add_game(&mut self, player_stats: &PlayerStats, frames: u32) {
+        self.games_played += 1;
+        self.frames_played += frames as u64;
+        self.pieces_placed += player_stats.pieces_placed as u64;
+        self.lines += player_stats.lines as u64;
+        self.tetrisnts += player_stats.clears[3] as u64;
+        self.total_score += player_stats.score;
+        self.best_score = std::cmp::max(self.best_score, player_stats.score);
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProfilesConfig {
+    #[serde(default)]
+    version: u32,
+    #[serde(default)]
+    profiles: Vec<Profile>,
+}
+
+pub fn load_profiles(dir: &Path) -> Vec<Profile> {
+    let path = dir.join(PROFILES_FILE);
+    let string = match fs::read_to_string(&path) {
+        Ok(string) => string,
+        Err(_) => return vec![],
+    };
+    let profiles_config: ProfilesConfig = match toml::from_str(&string) {
+        Ok(profiles_config) => profiles_config,
+        Err(e) => {
+            report_corrupt_file(&path, &e.to_string());
+            return vec![];
+        }
+    };
+    check_version(&path, profiles_config.version);
+    let mut vec_profiles: Vec<Profile> = Vec::with_capacity(profiles_config.profiles.len());
+    for mut profile in profiles_config.profiles.into_iter() {
+        // profiles are picked by name, so names have to be usable and unique
+        if !is_valid_name(&profile.name) || vec_profiles.iter().any(|p| p.name == profile.name) {
+            println!(
+                "[!] {}: ignoring profile with a missing, bad or duplicate name \"{}\"",
+                path.display(),
+                profile.name
+            );
+            continue;
+        }
+        profile.fix_up(&path);
+        vec_profiles.push(profile);
+    }
+    println!(
+        "[+] Loaded {} profiles from {}",
+        vec_profiles.len(),
+        path.display()
+    );
+    vec_profiles
+}
+
+pub fn save_profiles(dir: &Path, profiles: &[Profile]) {
+    let profiles_config = ProfilesConfig {
+        version: CONFIG_VERSION,
+        profiles: profiles.to_vec(),
+    };
+    match toml::to_string(&profiles_config) {
+        Ok(string) => write_file(dir, PROFILES_FILE, &string),
+        Err(e) => println!("[!] failed to serialize profiles: {}", e),
+    }
+}
+
+// names are typed in with letters only, same as initials
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= MAX_PROFILE_NAME_LENGTH
+        && name.chars().all(|c| c.is_ascii_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TestDir;
+
+    #[test]
+    fn profiles_are_fixed_up_on_load() {
+        let dir = TestDir::new("profiles");
+        write_file(
+            &dir,
+            PROFILES_FILE,
+            "version = 1\n\n[[profiles]]\nname = \"CAT\"\ndas_threshold_big = 0\ncolor = 200\n\n[[profiles.keys]]\nkey = \"Left\"\nmovement = \"Left\"\n\n[[profiles.keys]]\nkey = \"Left\"\nmovement = \"Right\"\n\n[[profiles]]\nname = \"CAT\"\n\n[[profiles]]\nname = \"cow\"\n",
+        );
+        let profiles = load_profiles(&dir);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].das_threshold_big, DAS_THRESHOLD_BIG);
+        assert_eq!(profiles[0].das_threshold_little, DAS_THRESHOLD_LITTLE);
+        assert_eq!(profiles[0].color, None);
+        assert_eq!(profiles[0].keys.len(), 1);
+        assert_eq!(profiles[0].control_scheme().len(), 1);
+
+        let mut profiles = profiles;
+        let mut player_stats = PlayerStats::default();
+        player_stats.add_clear(4, 1200);
+        profiles[0].stats.add_game(&player_stats, 600);
+        profiles[0].stats.add_game(&PlayerStats::default(), 60);
+        save_profiles(&dir, &profiles);
+        let loaded = load_profiles(&dir);
+        assert_eq!(loaded[0].stats.games_played, 2);
+        assert_eq!(loaded[0].stats.frames_played, 660);
+        assert_eq!(loaded[0].stats.tetrisnts, 1);
+        assert_eq!(loaded[0].stats.best_score, 1200);
+    }
+}
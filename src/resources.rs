@@ -0,0 +1,94 @@
+use ggez::filesystem;
+use ggez::Context;
+
+use crate::config;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub static GAMEPAD_MAPPINGS_FILE: &str = "gamecontrollerdb.txt";
+// points at a resource folder without having to pass --resources every time
+pub static RESOURCES_ENV_VAR: &str = "TETRISNT_RESOURCES";
+
+// every folder a resource could be in, in the order they're looked through, with where each one came from;
+// a shortcut can start the program from anywhere, so the working directory isn't one of them
+pub fn resource_dirs(ctx: &Context, cli_dir: Option<&Path>) -> Vec<(&'static str, PathBuf)> {
+    let mut vec_dirs: Vec<(&'static str, PathBuf)> = vec![];
+    if let Some(dir) = cli_dir {
+        vec_dirs.push(("--resources", dir.to_path_buf()));
+    }
+    if let Some(dir) = env::var_os(RESOURCES_ENV_VAR) {
+        vec_dirs.push((RESOURCES_ENV_VAR, PathBuf::from(dir)));
+    }
+    // folders that were asked for by name should be there
+    for (source, dir) in vec_dirs.iter() {
+        if !dir.is_dir() {
+            println!(
+                "[!] resource folder {} (from {}) doesn't exist",
+                dir.display(),
+                source
+            );
+        }
+    }
+    vec_dirs.push(("user config directory", config::config_dir(ctx)));
+    vec_dirs.push(("user data directory", config::data_dir(ctx)));
+    vec_dirs.push((
+        "next to the executable",
+        filesystem::resources_dir(ctx).to_path_buf(),
+    ));
+    // for `cargo run`
+    if let Some(dir) = env::var_os("CARGO_MANIFEST_DIR") {
+        vec_dirs.push(("CARGO_MANIFEST_DIR", PathBuf::from(dir).join("resources")));
+    }
+    vec_dirs
+}
+
+// the first copy of `file_name` in `resource_dirs`
+pub fn find_resource(vec_dirs: &[(&'static str, PathBuf)], file_name: &str) -> Option<PathBuf> {
+    vec_dirs
+        .iter()
+        .map(|(_, dir)| dir.join(file_name))
+        .find(|path| path.is_file())
+}
+
+// SDL2 mappings for gamepads that aren't detected (or laid out) right on their own;
+// returns the contents of the first mappings file found, if there is one
+pub fn load_gamepad_mappings(vec_dirs: &[(&'static str, PathBuf)]) -> Option<String> {
+    let path = match find_resource(vec_dirs, GAMEPAD_MAPPINGS_FILE) {
+        Some(path) => path,
+        None => {
+            println!(
+                "[!] no {} found, so no custom gamepad mappings will be used; looked in:",
+                GAMEPAD_MAPPINGS_FILE
+            );
+            for (source, dir) in vec_dirs.iter() {
+                println!("[!]     {} ({})", dir.display(), source);
+            }
+            return None;
+        }
+    };
+    match fs::read_to_string(&path) {
+        Ok(string) => {
+            println!(
+                "[+] Loaded {} gamepad mappings from {}",
+                count_mappings(&string),
+                path.display()
+            );
+            Some(string)
+        }
+        Err(e) => {
+            println!("[!] failed to read {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+// one mapping per line; blank lines and # comments don't count
+fn count_mappings(string: &str) -> usize {
+    string
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .count()
+}
@@ -0,0 +1,409 @@
+use ggez::graphics::Color;
+use serde::Deserialize;
+
+use crate::game::tile::NUM_PLAYERCOLORS;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// each .toml file in here (in the config directory) is a theme named after the file
+pub static THEMES_DIR: &str = "themes";
+// the theme is picked with a u8 menu value
+pub const MAX_THEMES: usize = u8::MAX as usize;
+
+// every color the game and the menus are drawn with; a theme file only needs the colors it changes,
+// everything it leaves out is the classic theme's
+#[derive(Deserialize, Copy, Clone)]
+#[serde(default)]
+pub struct Theme {
+    // the first NUM_PLAYERCOLORS players, and the colors a profile can pick
+    pub player_colors: [[u8; 3]; NUM_PLAYERCOLORS as usize],
+    // multiplied by the player number for every player past those
+    pub base_player_color: [u8; 3],
+    // how far a player tile's edge is averaged with black, from the corners to the middle of each side
+    pub player_tile_darken: [f32; 3],
+    // how far a player tile's middle is averaged with white, from the center out
+    pub player_tile_brighten: [f32; 3],
+    pub empty_tile: [u8; 3],
+    pub empty_tile_edge: [u8; 3],
+    // opacities (out of 0xff) of the white drawn over tiles, from the corners in (see tile.rs)
+    pub active_highlight: [u8; 3],
+    pub clearing_standard_highlight: [u8; 3],
+    pub clearing_tetrisnt_highlight: [u8; 3],
+    pub ghost_highlight: [u8; 4],
+    pub menu_background: [u8; 3],
+    // every menu and results screen text that isn't selected or a warning
+    pub menu_text: [u8; 3],
+    pub menu_dark: [u8; 3],
+    pub menu_light: [u8; 3],
+    pub menu_select: [u8; 3],
+    pub menu_help: [u8; 3],
+}
+
+pub const CLASSIC: Theme = Theme {
+    player_colors: [
+        [69, 125, 225],
+        [240, 40, 40],
+        [80, 200, 60],
+        [230, 230, 50],
+        [220, 150, 70],
+        [125, 125, 125],
+        [230, 100, 210],
+    ],
+    base_player_color: [25, 80, 212],
+    player_tile_darken: [0.80, 0.70, 0.50],
+    player_tile_brighten: [0.40, 0.25, 0.10],
+    empty_tile: [60, 60, 60],
+    empty_tile_edge: [20, 20, 20],
+    active_highlight: [0x50, 0x09, 0x00],
+    clearing_standard_highlight: [0x60, 0x10, 0x05],
+    clearing_tetrisnt_highlight: [0xa0, 0x30, 0x10],
+    ghost_highlight: [0x20, 0x04, 0x00, 0x00],
+    menu_background: [102, 102, 102],
+    menu_text: [0, 0, 0],
+    menu_dark: [77, 77, 77],
+    menu_light: [153, 153, 153],
+    menu_select: [39, 214, 19],
+    menu_help: [230, 28, 28],
+};
+
+const PASTEL: Theme = Theme {
+    player_colors: [
+        [140, 175, 235],
+        [240, 140, 140],
+        [150, 215, 140],
+        [235, 225, 140],
+        [235, 185, 140],
+        [185, 185, 195],
+        [225, 160, 220],
+    ],
+    base_player_color: [110, 150, 220],
+    player_tile_darken: [0.60, 0.45, 0.30],
+    player_tile_brighten: [0.45, 0.30, 0.15],
+    empty_tile: [95, 90, 105],
+    empty_tile_edge: [60, 55, 70],
+    active_highlight: [0x50, 0x09, 0x00],
+    clearing_standard_highlight: [0x60, 0x10, 0x05],
+    clearing_tetrisnt_highlight: [0xa0, 0x30, 0x10],
+    ghost_highlight: [0x20, 0x04, 0x00, 0x00],
+    menu_background: [190, 180, 200],
+    menu_text: [0, 0, 0],
+    menu_dark: [150, 140, 165],
+    menu_light: [220, 212, 228],
+    menu_select: [30, 130, 50],
+    menu_help: [190, 30, 40],
+};
+
+const MIDNIGHT: Theme = Theme {
+    player_colors: [
+        [40, 80, 200],
+        [190, 30, 60],
+        [30, 150, 90],
+        [200, 170, 30],
+        [200, 100, 30],
+        [90, 90, 120],
+        [150, 60, 190],
+    ],
+    base_player_color: [30, 50, 160],
+    player_tile_darken: [0.85, 0.75, 0.55],
+    player_tile_brighten: [0.30, 0.18, 0.06],
+    empty_tile: [22, 24, 40],
+    empty_tile_edge: [8, 8, 16],
+    active_highlight: [0x40, 0x08, 0x00],
+    clearing_standard_highlight: [0x50, 0x0c, 0x04],
+    clearing_tetrisnt_highlight: [0x90, 0x28, 0x0c],
+    ghost_highlight: [0x18, 0x03, 0x00, 0x00],
+    menu_background: [90, 95, 130],
+    menu_text: [235, 235, 245],
+    menu_dark: [60, 64, 95],
+    menu_light: [130, 135, 170],
+    menu_select: [120, 240, 110],
+    menu_help: [255, 90, 90],
+};
+
+const HIGH_CONTRAST: Theme = Theme {
+    player_colors: [
+        [0, 110, 255],
+        [255, 0, 0],
+        [0, 220, 0],
+        [255, 255, 0],
+        [255, 130, 0],
+        [255, 255, 255],
+        [255, 0, 255],
+    ],
+    base_player_color: [0, 90, 255],
+    player_tile_darken: [0.90, 0.85, 0.70],
+    player_tile_brighten: [0.50, 0.35, 0.15],
+    empty_tile: [0, 0, 0],
+    empty_tile_edge: [45, 45, 45],
+    active_highlight: [0x70, 0x18, 0x00],
+    clearing_standard_highlight: [0x90, 0x20, 0x08],
+    clearing_tetrisnt_highlight: [0xd0, 0x50, 0x20],
+    ghost_highlight: [0x40, 0x0c, 0x00, 0x00],
+    menu_background: [200, 200, 200],
+    menu_text: [0, 0, 0],
+    menu_dark: [120, 120, 120],
+    menu_light: [240, 240, 240],
+    menu_select: [0, 120, 0],
+    menu_help: [200, 0, 0],
+};
+
+// the colorblind themes keep the classic board and only change colors that get mixed up,
+// using blues, oranges and yellows (from the Okabe-Ito palette) that stay apart; no red against green
+const PROTANOPIA: Theme = Theme {
+    player_colors: [
+        [0, 114, 178],
+        [230, 159, 0],
+        [86, 180, 233],
+        [240, 228, 66],
+        [0, 158, 115],
+        [204, 121, 167],
+        [200, 200, 200],
+    ],
+    base_player_color: [0, 90, 150],
+    player_tile_darken: [0.80, 0.70, 0.50],
+    player_tile_brighten: [0.40, 0.25, 0.10],
+    empty_tile: [60, 60, 60],
+    empty_tile_edge: [20, 20, 20],
+    active_highlight: [0x50, 0x09, 0x00],
+    clearing_standard_highlight: [0x60, 0x10, 0x05],
+    clearing_tetrisnt_highlight: [0xa0, 0x30, 0x10],
+    ghost_highlight: [0x20, 0x04, 0x00, 0x00],
+    menu_background: [102, 102, 102],
+    menu_text: [0, 0, 0],
+    menu_dark: [77, 77, 77],
+    menu_light: [153, 153, 153],
+    menu_select: [240, 228, 66],
+    menu_help: [86, 180, 233],
+};
+
+// like protanopia, with vermillion instead of the bluish green that looks close to gray here
+const DEUTERANOPIA: Theme = Theme {
+    player_colors: [
+        [0, 114, 178],
+        [230, 159, 0],
+        [86, 180, 233],
+        [240, 228, 66],
+        [213, 94, 0],
+        [204, 121, 167],
+        [200, 200, 200],
+    ],
+    base_player_color: [0, 90, 150],
+    player_tile_darken: [0.80, 0.70, 0.50],
+    player_tile_brighten: [0.40, 0.25, 0.10],
+    empty_tile: [60, 60, 60],
+    empty_tile_edge: [20, 20, 20],
+    active_highlight: [0x50, 0x09, 0x00],
+    clearing_standard_highlight: [0x60, 0x10, 0x05],
+    clearing_tetrisnt_highlight: [0xa0, 0x30, 0x10],
+    ghost_highlight: [0x20, 0x04, 0x00, 0x00],
+    menu_background: [102, 102, 102],
+    menu_text: [0, 0, 0],
+    menu_dark: [77, 77, 77],
+    menu_light: [153, 153, 153],
+    menu_select: [240, 228, 66],
+    menu_help: [86, 180, 233],
+};
+
+// blue against yellow is what gets mixed up here, so reds, teals and pinks instead
+const TRITANOPIA: Theme = Theme {
+    player_colors: [
+        [215, 48, 39],
+        [0, 150, 160],
+        [240, 150, 170],
+        [230, 230, 230],
+        [120, 30, 80],
+        [80, 200, 210],
+        [110, 110, 110],
+    ],
+    base_player_color: [160, 30, 30],
+    player_tile_darken: [0.80, 0.70, 0.50],
+    player_tile_brighten: [0.40, 0.25, 0.10],
+    empty_tile: [60, 60, 60],
+    empty_tile_edge: [20, 20, 20],
+    active_highlight: [0x50, 0x09, 0x00],
+    clearing_standard_highlight: [0x60, 0x10, 0x05],
+    clearing_tetrisnt_highlight: [0xa0, 0x30, 0x10],
+    ghost_highlight: [0x20, 0x04, 0x00, 0x00],
+    menu_background: [102, 102, 102],
+    menu_text: [0, 0, 0],
+    menu_dark: [77, 77, 77],
+    menu_light: [153, 153, 153],
+    menu_select: [0, 210, 210],
+    menu_help: [230, 40, 40],
+};
+
+impl Default for Theme {
+    fn default() -> Self {
+        CLASSIC
+    }
+}
+
+impl Theme {
+    pub fn menu_background_color(&self) -> Color {
+        to_color(self.menu_background)
+    }
+
+    pub fn menu_text_color(&self) -> Color {
+        to_color(self.menu_text)
+    }
+
+    pub fn menu_dark_color(&self) -> Color {
+        to_color(self.menu_dark)
+    }
+
+    pub fn menu_light_color(&self) -> Color {
+        to_color(self.menu_light)
+    }
+
+    pub fn menu_select_color(&self) -> Color {
+        to_color(self.menu_select)
+    }
+
+    pub fn menu_help_color(&self) -> Color {
+        to_color(self.menu_help)
+    }
+
+    // darken and brighten amounts a hand-edited file got out of 0.0 to 1.0 go back to the classic ones;
+    // returns whether any did
+    fn fix_up(&mut self) -> bool {
+        let mut fixed_flag = false;
+        for (amount, classic_amount) in self
+            .player_tile_darken
+            .iter_mut()
+            .chain(self.player_tile_brighten.iter_mut())
+            .zip(
+                CLASSIC
+                    .player_tile_darken
+                    .iter()
+                    .chain(CLASSIC.player_tile_brighten.iter()),
+            )
+        {
+            if !(0.0..=1.0).contains(amount) {
+                *amount = *classic_amount;
+                fixed_flag = true;
+            }
+        }
+        fixed_flag
+    }
+}
+
+fn to_color(rgb: [u8; 3]) -> Color {
+    Color::from_rgb(rgb[0], rgb[1], rgb[2])
+}
+
+pub fn built_in_themes() -> Vec<(String, Theme)> {
+    vec![
+        ("Classic".to_string(), CLASSIC),
+        ("Pastel".to_string(), PASTEL),
+        ("Midnight".to_string(), MIDNIGHT),
+        ("High Contrast".to_string(), HIGH_CONTRAST),
+        ("Protanopia".to_string(), PROTANOPIA),
+        ("Deuteranopia".to_string(), DEUTERANOPIA),
+        ("Tritanopia".to_string(), TRITANOPIA),
+    ]
+}
+
+// the built-in themes, then every theme file in alphabetical order;
+// a file named after a built-in theme replaces it, and a file that can't be read is skipped
+pub fn load_themes(dir: &Path) -> Vec<(String, Theme)> {
+    let mut vec_themes = built_in_themes();
+    let themes_dir = dir.join(THEMES_DIR);
+    let read_dir = match fs::read_dir(&themes_dir) {
+        Ok(read_dir) => read_dir,
+        Err(_) => return vec_themes,
+    };
+    let mut vec_paths: Vec<PathBuf> = read_dir
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    vec_paths.sort();
+    for path in vec_paths.iter() {
+        let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let string = match fs::read_to_string(path) {
+            Ok(string) => string,
+            Err(e) => {
+                println!("[!] failed to read {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        let mut theme: Theme = match toml::from_str(&string) {
+            Ok(theme) => theme,
+            Err(e) => {
+                println!("[!] {}: ignoring theme, {}", path.display(), e);
+                continue;
+            }
+        };
+        if theme.fix_up() {
+            println!(
+                "[!] {}: darken and brighten amounts go from 0.0 to 1.0, using the classic ones for those",
+                path.display()
+            );
+        }
+        match vec_themes
+            .iter()
+            .position(|(built_in_name, _)| built_in_name.eq_ignore_ascii_case(&name))
+        {
+            Some(index) => vec_themes[index].1 = theme,
+            None if vec_themes.len() >= MAX_THEMES => {
+                println!(
+                    "[!] {}: ignoring theme, there can't be more than {} themes",
+                    path.display(),
+                    MAX_THEMES
+                );
+                continue;
+            }
+            None => vec_themes.push((name.clone(), theme)),
+        }
+        println!("[+] Loaded theme {} from {}", name, path.display());
+    }
+    vec_themes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{write_file, TestDir};
+
+    #[test]
+    fn theme_files_fill_in_from_classic() {
+        let dir = TestDir::new("themes");
+        let themes_dir = dir.join(THEMES_DIR);
+        write_file(
+            &themes_dir,
+            "Ocean.toml",
+            "menu_background = [20, 60, 120]\nplayer_tile_darken = [0.5, 1.5, 0.25]\n",
+        );
+        write_file(&themes_dir, "pastel.toml", "menu_select = [1, 2, 3]\n");
+        write_file(&themes_dir, "broken.toml", "menu_help = \"red\"\n");
+        write_file(&themes_dir, "notes.txt", "not a theme\n");
+        let vec_themes = load_themes(&dir);
+        let names: Vec<&str> = vec_themes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Classic",
+                "Pastel",
+                "Midnight",
+                "High Contrast",
+                "Protanopia",
+                "Deuteranopia",
+                "Tritanopia",
+                "Ocean"
+            ]
+        );
+        // replaced, but keeping the built-in name
+        assert_eq!(vec_themes[1].1.menu_select, [1, 2, 3]);
+        assert_eq!(vec_themes[1].1.menu_help, CLASSIC.menu_help);
+        let ocean = vec_themes[7].1;
+        assert_eq!(ocean.menu_background, [20, 60, 120]);
+        assert_eq!(ocean.player_colors, CLASSIC.player_colors);
+        assert_eq!(
+            ocean.player_tile_darken,
+            [0.5, CLASSIC.player_tile_darken[1], 0.25]
+        );
+    }
+}